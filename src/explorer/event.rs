@@ -9,9 +9,12 @@ use tokio::{
     sync::{mpsc, RwLock},
     time::sleep,
 };
-use utxorpc::{CardanoSyncClient, TipEvent};
+use utxorpc::TipEvent;
 
-use crate::types::DetailedBalance;
+use crate::{
+    provider::types::{AddressMatch, ChainEvent, ProviderKind},
+    types::DetailedBalance,
+};
 
 use super::{ChainBlock, ExplorerContext};
 
@@ -129,7 +132,10 @@ impl EventTask {
     }
 
     async fn get_balance(&self, address: &Address) -> Result<DetailedBalance> {
-        self.context.provider.get_detailed_balance(address).await
+        self.context
+            .provider
+            .get_detailed_balance(address, AddressMatch::Full)
+            .await
     }
 
     async fn check_balances(&self) -> Result<()> {
@@ -201,8 +207,11 @@ impl EventTask {
             self.update_balance(address.clone(), value.clone()).await;
         }
 
-        let mut client: CardanoSyncClient = self.context.provider.client().await?;
-        let mut tip = client.follow_tip(vec![]).await?;
+        if self.context.provider.kind() != ProviderKind::Utxorpc {
+            return self.follow_chain().await;
+        }
+
+        let mut tip = self.context.provider.follow_tip().await?;
 
         self.update_connection(ConnectionState::Connected).await?;
 
@@ -248,4 +257,38 @@ impl EventTask {
 
         Err(anyhow::anyhow!("Tip stream ended unexpectedly"))
     }
+
+    /// Ogmios and nodes only report rollbacks as the point to go back to, so
+    /// undone blocks surface as resets.
+    async fn follow_chain(&self) -> Result<()> {
+        let mut follower = self.context.provider.follow_chain().await?;
+
+        self.update_connection(ConnectionState::Connected).await?;
+
+        loop {
+            match follower.next_block().await? {
+                ChainEvent::Forward {
+                    slot,
+                    hash,
+                    height,
+                    tx_count,
+                } => {
+                    let chainblock = ChainBlock {
+                        slot,
+                        hash,
+                        number: height,
+                        tx_count,
+                        body: None,
+                    };
+
+                    self.send(Event::App(AppEvent::NewTip(chainblock)))?;
+                }
+                ChainEvent::Backward { slot } => {
+                    self.send(Event::App(AppEvent::Reset(slot)))?;
+                }
+            }
+
+            self.check_balances().await?;
+        }
+    }
 }
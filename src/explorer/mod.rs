@@ -14,7 +14,12 @@ use strum::Display;
 use tokio::sync::RwLock;
 use utxorpc::spec::cardano::BlockBody;
 
-use crate::{provider::types::Provider, types::DetailedBalance, utils::Name, Context};
+use crate::{
+    provider::types::{AddressMatch, Provider},
+    types::DetailedBalance,
+    utils::Name,
+    Context,
+};
 
 pub mod event;
 pub mod widgets;
@@ -32,10 +37,17 @@ use widgets::{
     },
 };
 
+/// Chain updates held back while paused. Past it the oldest ones are
+/// dropped, so a long pause doesn't grow the queue without bound.
+const MAX_PENDING_EVENTS: usize = 1000;
+
 #[derive(Parser)]
 pub struct Args {
     #[arg(long, help = "Name of the provider to use")]
     provider: Option<String>,
+
+    #[arg(long, help = "Hide absent fields in the transaction detail tree")]
+    compact_tree: bool,
 }
 
 #[derive(Default)]
@@ -79,6 +91,8 @@ pub struct App {
     selected_popup: Option<SelectedPopup>,
 
     chain: ChainState,
+    paused: bool,
+    pending_events: VecDeque<AppEvent>,
     accounts_tab_state: AccountsTabState,
     blocks_tab_state: BlocksTabState,
     transactions_tab_state: TransactionsTabState,
@@ -99,6 +113,8 @@ impl App {
             app_state: ConnectionState::Disconnected,
 
             chain: ChainState::default(),
+            paused: false,
+            pending_events: VecDeque::new(),
             events: EventHandler::new(context.clone()),
             accounts_tab_state: AccountsTabState::default(),
             blocks_tab_state: BlocksTabState::default(),
@@ -119,18 +135,40 @@ impl App {
                         self.handle_key(key_event).await
                     }
                 }
-                Event::App(app_event) => match app_event {
-                    AppEvent::Reset(tip) => self.handle_reset(tip),
-                    AppEvent::NewTip(tip) => self.handle_new_tip(tip),
-                    AppEvent::UndoTip(tip) => self.handle_undo_tip(tip),
-                    AppEvent::State(app_state) => self.app_state = app_state,
-                },
+                Event::App(app_event) => self.handle_app_event(app_event),
                 Event::Tick => self.handle_tick(),
             }
         }
         Ok(())
     }
 
+    fn handle_app_event(&mut self, app_event: AppEvent) {
+        match app_event {
+            AppEvent::State(app_state) => self.app_state = app_state,
+            // Chain updates are held back while paused and applied in order
+            // once resumed, so the views stay stable in the meantime
+            app_event if self.paused => {
+                if self.pending_events.len() == MAX_PENDING_EVENTS {
+                    self.pending_events.pop_front();
+                }
+                self.pending_events.push_back(app_event)
+            }
+            AppEvent::Reset(tip) => self.handle_reset(tip),
+            AppEvent::NewTip(tip) => self.handle_new_tip(tip),
+            AppEvent::UndoTip(tip) => self.handle_undo_tip(tip),
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+
+        if !self.paused {
+            while let Some(app_event) = self.pending_events.pop_front() {
+                self.handle_app_event(app_event);
+            }
+        }
+    }
+
     async fn handle_key(&mut self, key: KeyEvent) {
         if key.kind != KeyEventKind::Press {
             return;
@@ -159,6 +197,9 @@ impl App {
                 KeyCode::Char('?') => {
                     self.selected_popup = Some(SelectedPopup::Help(HelpPopup::new()))
                 }
+                KeyCode::Char('p' | ' ') if !self.transactions_tab_state.is_editing() => {
+                    self.toggle_pause()
+                }
 
                 _ => {}
             }
@@ -326,11 +367,11 @@ impl ExplorerContext {
             ctx.store
                 .wallets()
                 .iter()
-                .map(|w| {
-                    (
-                        w.address(provider.is_testnet()),
+                .filter_map(|w| {
+                    Some((
+                        w.address(provider.is_testnet()).ok()?,
                         ExplorerWallet::new(w.name.clone()),
-                    )
+                    ))
                 })
                 .collect::<IndexMap<_, _>>(),
         );
@@ -341,7 +382,7 @@ impl ExplorerContext {
     pub async fn insert_wallet(&self, address: Address, name: Name) {
         let balance = self
             .provider
-            .get_detailed_balance(&address)
+            .get_detailed_balance(&address, AddressMatch::Full)
             .await
             .unwrap_or_default();
 
@@ -356,7 +397,10 @@ pub async fn run(args: Args, ctx: &Context) -> Result<()> {
     let terminal = ratatui::init();
 
     let context: Arc<ExplorerContext> = Arc::new(ExplorerContext::new(&args, ctx)?);
-    let app = App::new(context.clone());
+    let mut app = App::new(context.clone());
+    app.transactions_tab_state = app
+        .transactions_tab_state
+        .with_compact_tree(args.compact_tree);
     let result = app.run(terminal).await;
     ratatui::restore();
     result
@@ -6,6 +6,8 @@ use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
 use crate::explorer::{App, ChainBlock};
 
+/// Tx count of the blocks in the last `size` slots. Slots without a block
+/// are `Some(0)` and empty blocks are `None`, so they can be told apart.
 fn get_last_slots(data: Rc<RefCell<VecDeque<ChainBlock>>>, size: usize) -> Vec<Option<u64>> {
     let data = data.borrow();
     let mut result = vec![Some(0); size];
@@ -70,15 +72,54 @@ impl Widget for ActivityMonitor {
             None => ("Chain Activity ".to_string(), Color::Green),
         };
 
+        let empty_blocks: Vec<usize> = points
+            .iter()
+            .enumerate()
+            .filter_map(|(i, point)| point.is_none().then_some(i))
+            .collect();
+        let points: Vec<u64> = points.into_iter().map(Option::unwrap_or_default).collect();
+
+        let block = Block::bordered()
+            .border_style(Style::new().dark_gray())
+            .title(title);
+        let inner = block.inner(area);
+
         let sparkline = Sparkline::default()
-            .block(
-                Block::bordered()
-                    .border_style(Style::new().dark_gray())
-                    .title(title),
-            )
+            .block(block)
             .data(&points)
-            .absent_value_symbol(symbols::shade::LIGHT)
             .style(Style::default().fg(color));
         sparkline.render(area, buf);
+
+        // empty blocks get a thin marker instead of a full-height bar
+        if inner.height > 0 {
+            for i in empty_blocks.into_iter().filter(|i| *i < inner.width as usize) {
+                buf[(inner.left() + i as u16, inner.bottom() - 1)]
+                    .set_symbol(symbols::bar::ONE_EIGHTH)
+                    .set_style(Style::new().dark_gray());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_blocks_are_absent() {
+        let block = |slot, tx_count| ChainBlock {
+            slot,
+            hash: vec![0; 32],
+            number: slot,
+            tx_count,
+            body: None,
+        };
+
+        let blocks = Rc::new(RefCell::new(VecDeque::from([block(10, 0), block(8, 3)])));
+
+        assert_eq!(
+            get_last_slots(blocks, 4),
+            vec![Some(0), Some(3), Some(0), None]
+        );
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
@@ -7,7 +9,7 @@ use ratatui::{
 };
 
 use crate::{
-    explorer::{event::ConnectionState, App, SelectedTab},
+    explorer::{event::ConnectionState, App, ChainBlock, SelectedTab},
     provider::types::Provider,
 };
 
@@ -17,6 +19,47 @@ pub struct Header {
     pub tip: Option<u64>,
     pub provider: Provider,
     pub app_state: ConnectionState,
+    pub paused: bool,
+    pub density: Option<ChainDensity>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainDensity {
+    /// Ratio of blocks produced over the slots elapsed
+    pub ratio: f64,
+    /// Biggest amount of slots elapsed between two consecutive blocks
+    pub max_gap: u64,
+}
+impl ChainDensity {
+    /// Computes the density over the buffered blocks, which are ordered from
+    /// the newest to the oldest. Pairs that don't move the chain forward (e.g.
+    /// after a rollback) are skipped.
+    pub fn from_blocks(blocks: &VecDeque<ChainBlock>) -> Option<Self> {
+        let mut heights = 0;
+        let mut slots = 0;
+        let mut max_gap = 0;
+
+        for (newer, older) in blocks.iter().zip(blocks.iter().skip(1)) {
+            if newer.number <= older.number || newer.slot <= older.slot {
+                continue;
+            }
+
+            let gap = newer.slot - older.slot;
+
+            heights += newer.number - older.number;
+            slots += gap;
+            max_gap = max_gap.max(gap);
+        }
+
+        if slots == 0 {
+            return None;
+        }
+
+        Some(Self {
+            ratio: heights as f64 / slots as f64,
+            max_gap,
+        })
+    }
 }
 impl From<&App> for Header {
     fn from(value: &App) -> Self {
@@ -25,6 +68,8 @@ impl From<&App> for Header {
             tip: value.chain.tip,
             provider: value.context.provider.clone(),
             app_state: value.app_state.clone(),
+            paused: value.paused,
+            density: ChainDensity::from_blocks(&value.chain.blocks.borrow()),
         }
     }
 }
@@ -42,17 +87,27 @@ impl Widget for Header {
             SelectedTab::Blocks(_) => 1,
             SelectedTab::Transactions(_) => 2,
         };
+        let mut navigation = Block::bordered()
+            .padding(Padding::vertical(1))
+            .title(" Navigation ")
+            .border_style(Style::new().dark_gray());
+        if let Some(density) = &self.density {
+            navigation = navigation.title(
+                Line::from(format!(
+                    " Density: {:.2}% | Max gap: {} slots ",
+                    density.ratio * 100.0,
+                    density.max_gap
+                ))
+                .right_aligned(),
+            );
+        }
+
         Tabs::new(titles)
             .highlight_style((Color::Green, Modifier::BOLD))
             .select(selected_tab_index)
             .padding(" ", " ")
             .divider("|")
-            .block(
-                Block::bordered()
-                    .padding(Padding::vertical(1))
-                    .title(" Navigation ")
-                    .border_style(Style::new().dark_gray()),
-            )
+            .block(navigation)
             .render(title_area, buf);
 
         let tip = self.tip.map(|tip| tip.to_string()).unwrap_or("-".into());
@@ -70,10 +125,62 @@ impl Widget for Header {
             ConnectionState::Disconnected => (Color::Red, Style::new().red()),
         };
 
+        let mut block = Block::bordered().border_style(style).title(" Connection ");
+        if self.paused {
+            block = block.title(Line::from(" PAUSED ").right_aligned().yellow().bold());
+        }
+
         let name = Paragraph::new(text)
-            .block(Block::bordered().border_style(style).title(" Connection "))
+            .block(block)
             .style(Style::default().fg(color));
 
         name.render(provider_area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(number: u64, slot: u64) -> ChainBlock {
+        ChainBlock {
+            slot,
+            hash: vec![],
+            number,
+            tx_count: 0,
+            body: None,
+        }
+    }
+
+    #[test]
+    fn density_over_buffered_blocks() {
+        assert_eq!(ChainDensity::from_blocks(&VecDeque::new()), None);
+        assert_eq!(ChainDensity::from_blocks(&[block(1, 10)].into()), None);
+
+        // Newest first: 4 blocks over 40 slots, with a 25 slot gap
+        let blocks = [
+            block(14, 140),
+            block(13, 115),
+            block(12, 110),
+            block(10, 100),
+        ]
+        .into();
+        assert_eq!(
+            ChainDensity::from_blocks(&blocks),
+            Some(ChainDensity {
+                ratio: 0.1,
+                max_gap: 25
+            })
+        );
+
+        // A rollback leaves a lower height on top, which is skipped
+        let blocks = [block(12, 130), block(13, 120), block(12, 110)].into();
+        assert_eq!(
+            ChainDensity::from_blocks(&blocks),
+            Some(ChainDensity {
+                ratio: 0.1,
+                max_gap: 10
+            })
+        );
+    }
+}
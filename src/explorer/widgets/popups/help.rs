@@ -20,7 +20,7 @@ impl Widget for HelpPopup {
     where
         Self: Sized,
     {
-        let popup_area = centered_rect(60, 20, area);
+        let popup_area = centered_rect(60, 26, area);
         Clear.render(popup_area, buf);
 
         let help = Paragraph::new(vec![
@@ -28,6 +28,7 @@ impl Widget for HelpPopup {
             Line::from("  q   : Quit CShell"),
             Line::from("  esc : Go back or close popup"),
             Line::from("  ?   : Show this help"),
+            Line::from("  p | space : Pause or resume following the chain"),
             Line::default(),
             Line::from("Navigation"),
             Line::from("  Tab | Shift + Tab : Scroll through tabs"),
@@ -40,7 +41,11 @@ impl Widget for HelpPopup {
             Line::from("  f | / : Focus on filter"),
             Line::default(),
             Line::from("Account"),
-            Line::from("  i   : Add a temp account address"),
+            Line::from("  i     : Add a temp account address"),
+            Line::from("  enter : Show account UTxOs"),
+            Line::default(),
+            Line::from("Transaction detail"),
+            Line::from("  c   : Toggle compact tree"),
         ])
         .block(
             Block::bordered()
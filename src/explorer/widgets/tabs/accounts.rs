@@ -1,26 +1,48 @@
 use std::sync::Arc;
 
-use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::layout::{Constraint, Layout};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Margin};
 use ratatui::style::{Color, Modifier, Style, Stylize};
 use ratatui::text::{Line, Text};
 use ratatui::widgets::{
-    Block, Cell, HighlightSpacing, List, ListItem, ListState, Padding, Paragraph, Row,
-    StatefulWidget, Table, TableState, Widget,
+    Block, Cell, HighlightSpacing, List, ListItem, ListState, Padding, Paragraph, Row, Scrollbar,
+    ScrollbarOrientation, ScrollbarState, StatefulWidget, Table, TableState, Widget,
 };
 
 use crate::explorer::{ExplorerContext, ExplorerWallet};
+use crate::types::UTxO;
 use crate::utils::{clip, AdaFormat};
 
+#[derive(Clone, Default, Debug, PartialEq)]
+enum ViewMode {
+    #[default]
+    Normal,
+    Detail,
+}
+
 #[derive(Default)]
 pub struct AccountsTabState {
     list_state: ListState,
     table_state: TableState,
     focus_on_table: bool,
+    view_mode: ViewMode,
+    detail_state: AccountsDetailState,
 }
 impl AccountsTabState {
     pub fn handle_key(&mut self, key: &KeyEvent) {
+        if self.view_mode == ViewMode::Detail {
+            match key.code {
+                KeyCode::Esc => self.view_mode = ViewMode::Normal,
+                _ => self.detail_state.handle_key(key),
+            }
+            return;
+        }
+
         match (key.code, key.modifiers) {
+            (KeyCode::Enter, _) if self.list_state.selected().is_some() => {
+                self.detail_state = AccountsDetailState::default();
+                self.view_mode = ViewMode::Detail;
+            }
             (KeyCode::Char('l') | KeyCode::Right, _) if self.list_state.selected().is_some() => {
                 self.focus_on_table = true;
                 self.table_state.select_next();
@@ -73,7 +95,8 @@ impl StatefulWidget for AccountsTab {
         let [summary_area, utxos_area] =
             Layout::vertical([Constraint::Length(7), Constraint::Fill(1)]).areas(details_area);
 
-        let block = Block::bordered().title(Line::raw(" Accounts ").centered());
+        let block =
+            Block::bordered().title(Line::raw(" Accounts | press Enter for UTxOs ").centered());
 
         let guard = tokio::task::block_in_place(|| self.context.wallets.blocking_read());
         let wallets: Vec<(String, ExplorerWallet)> = guard
@@ -81,6 +104,14 @@ impl StatefulWidget for AccountsTab {
             .map(|(address, wallet)| (address.to_string(), wallet.clone()))
             .collect();
 
+        if let (ViewMode::Detail, Some(i)) = (&state.view_mode, state.list_state.selected()) {
+            if !wallets.is_empty() {
+                let (address, wallet) = wallets[i % wallets.len()].clone();
+                AccountsDetail::new(address, wallet).render(area, buf, &mut state.detail_state);
+                return;
+            }
+        }
+
         let items: Vec<ListItem> = wallets
             .iter()
             .map(|(address, wallet)| {
@@ -149,7 +180,7 @@ impl StatefulWidget for AccountsTab {
                     .unwrap_or(utxo.coin.clone());
 
                 Row::new(vec![
-                    format!("\n{}#{}\n", hex::encode(&utxo.tx), utxo.tx_index),
+                    format!("\n{}\n", utxo.ref_string()),
                     format!("\n{coin}\n"),
                     format!("\n{}\n", utxo.assets.len()),
                     format!(
@@ -190,3 +221,223 @@ impl StatefulWidget for AccountsTab {
         };
     }
 }
+
+#[derive(Default)]
+pub struct AccountsDetailState {
+    scroll_state: ScrollbarState,
+    table_state: TableState,
+}
+impl AccountsDetailState {
+    pub fn handle_key(&mut self, key: &KeyEvent) {
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('J') | KeyCode::Down, KeyModifiers::SHIFT) => {
+                self.table_state.select_last()
+            }
+            (KeyCode::Char('j') | KeyCode::Down, _) => self.table_state.select_next(),
+            (KeyCode::Char('K') | KeyCode::Up, KeyModifiers::SHIFT) => {
+                self.table_state.select_first()
+            }
+            (KeyCode::Char('k') | KeyCode::Up, _) => self.table_state.select_previous(),
+            _ => {}
+        }
+    }
+}
+
+pub struct AccountsDetail {
+    address: String,
+    wallet: ExplorerWallet,
+}
+impl AccountsDetail {
+    pub fn new(address: String, wallet: ExplorerWallet) -> Self {
+        Self { address, wallet }
+    }
+
+    /// One line per native asset held by the UTxO, as `quantity policy.name`.
+    fn asset_lines(utxo: &UTxO) -> Vec<String> {
+        utxo.assets
+            .iter()
+            .flat_map(|policy| {
+                let policy_id = clip(hex::encode(&policy.policy_id), 16);
+                policy.assets.iter().map(move |asset| {
+                    let name = match String::from_utf8(asset.name.clone()) {
+                        Ok(name) if name.chars().all(|c| !c.is_control()) => name,
+                        _ => hex::encode(&asset.name),
+                    };
+                    format!("{} {policy_id}.{name}", asset.quantity)
+                })
+            })
+            .collect()
+    }
+}
+
+impl StatefulWidget for AccountsDetail {
+    type State = AccountsDetailState;
+
+    fn render(
+        self,
+        area: ratatui::prelude::Rect,
+        buf: &mut ratatui::prelude::Buffer,
+        state: &mut Self::State,
+    ) {
+        let block = Block::bordered()
+            .title(" Account Detail | press ESC to go back ")
+            .padding(Padding::symmetric(2, 1));
+        block.clone().render(area, buf);
+
+        let area = block.inner(area);
+        let [summary_area, utxos_area] =
+            Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).areas(area);
+
+        let coin: u64 = self
+            .wallet
+            .balance
+            .iter()
+            .map(|utxo| utxo.coin.parse::<u64>().unwrap_or_default())
+            .sum();
+        let assets: usize = self
+            .wallet
+            .balance
+            .iter()
+            .map(|utxo| Self::asset_lines(utxo).len())
+            .sum();
+
+        Paragraph::new(vec![
+            Line::styled(
+                format!("{} wallet", self.wallet.name),
+                (Color::White, Modifier::UNDERLINED),
+            ),
+            Line::styled(format!("Address: {}", self.address), Color::White),
+            Line::styled(
+                format!(
+                    "Total: {} in {} UTxOs, {assets} assets",
+                    coin.format_ada(),
+                    self.wallet.balance.len()
+                ),
+                Color::White,
+            ),
+        ])
+        .render(summary_area, buf);
+
+        let header = ["Utxo Ref", "Coin", "Assets"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::default().fg(Color::Green).bold())
+            .height(1);
+
+        let rows = self.wallet.balance.iter().map(|utxo| {
+            let coin = utxo
+                .coin
+                .parse::<u64>()
+                .map(|v| v.format_ada())
+                .unwrap_or(utxo.coin.clone());
+            let assets = Self::asset_lines(utxo);
+            let height = assets.len().max(1) as u16;
+
+            Row::new(vec![
+                Cell::from(utxo.ref_string()),
+                Cell::from(coin),
+                Cell::from(Text::from_iter(assets)),
+            ])
+            .style(Style::new().fg(Color::White))
+            .height(height)
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(70),
+                Constraint::Length(25),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Modifier::BOLD)
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(table, utxos_area, buf, &mut state.table_state);
+
+        state.scroll_state = state
+            .scroll_state
+            .content_length(self.wallet.balance.len())
+            .position(state.table_state.selected().unwrap_or_default());
+        StatefulWidget::render(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            utxos_area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            buf,
+            &mut state.scroll_state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyEventKind;
+
+    use super::*;
+    use crate::types::{Asset, BalanceAsset};
+
+    fn press(state: &mut AccountsTabState, code: KeyCode) {
+        state.handle_key(&KeyEvent::new_with_kind(
+            code,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        ));
+    }
+
+    #[test]
+    fn enter_opens_detail_and_esc_returns() {
+        let mut state = AccountsTabState::default();
+
+        press(&mut state, KeyCode::Enter);
+        assert_eq!(state.view_mode, ViewMode::Normal);
+
+        press(&mut state, KeyCode::Down);
+        press(&mut state, KeyCode::Enter);
+        assert_eq!(state.view_mode, ViewMode::Detail);
+
+        press(&mut state, KeyCode::Down);
+        assert_eq!(state.detail_state.table_state.selected(), Some(0));
+        assert_eq!(state.list_state.selected(), Some(0));
+
+        press(&mut state, KeyCode::Esc);
+        assert_eq!(state.view_mode, ViewMode::Normal);
+    }
+
+    #[test]
+    fn asset_lines_per_token() {
+        let utxo = UTxO {
+            tx: vec![0xaa; 32],
+            tx_index: 0,
+            address: "addr_test1".into(),
+            coin: "2000000".into(),
+            assets: vec![BalanceAsset {
+                policy_id: vec![0xbb; 28],
+                assets: vec![
+                    Asset {
+                        name: b"TOKEN".to_vec(),
+                        quantity: "10".into(),
+                    },
+                    Asset {
+                        name: vec![0x00, 0x01],
+                        quantity: "1".into(),
+                    },
+                ],
+            }],
+            datum: None,
+        };
+
+        let policy_id = clip(hex::encode([0xbb; 28]), 16);
+        assert_eq!(
+            AccountsDetail::asset_lines(&utxo),
+            vec![
+                format!("10 {policy_id}.TOKEN"),
+                format!("1 {policy_id}.0001")
+            ]
+        );
+    }
+}
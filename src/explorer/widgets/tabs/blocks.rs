@@ -104,11 +104,15 @@ impl StatefulWidget for BlocksTab {
                     0 => Color::Black,
                     _ => Color::Reset,
                 };
+                let tx_count = match block.tx_count {
+                    0 => Cell::from("\nempty\n").dark_gray(),
+                    count => Cell::from(format!("\n{count}\n")),
+                };
                 Row::new(vec![
-                    format!("\n{}\n", block.slot),
-                    format!("\n{}\n", hex::encode(&block.hash)),
-                    format!("\n{}\n", block.number),
-                    format!("\n{}\n", block.tx_count),
+                    Cell::from(format!("\n{}\n", block.slot)),
+                    Cell::from(format!("\n{}\n", hex::encode(&block.hash))),
+                    Cell::from(format!("\n{}\n", block.number)),
+                    tx_count,
                 ])
                 .style(Style::new().fg(Color::White).bg(color))
                 .height(3)
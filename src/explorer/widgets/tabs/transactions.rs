@@ -57,6 +57,15 @@ impl TransactionsTabState {
         }
     }
 
+    pub fn is_editing(&self) -> bool {
+        self.input_mode == InputMode::Editing
+    }
+
+    pub fn with_compact_tree(mut self, compact: bool) -> Self {
+        self.detail_state.compact = compact;
+        self
+    }
+
     pub async fn handle_key(&mut self, key: &KeyEvent) {
         match self.view_mode {
             ViewMode::Normal => match self.input_mode {
@@ -77,7 +86,12 @@ impl TransactionsTabState {
                             .flat_map(TxView::from_chain_block)
                             .collect();
                     }
-                    (KeyCode::Enter, _) if self.table_state.selected().is_some() => {
+                    (KeyCode::Enter, _)
+                        if self
+                            .table_state
+                            .selected()
+                            .is_some_and(|i| i < self.txs.len()) =>
+                    {
                         self.detail_state.tree_state.close_all();
                         self.detail_state.tree_state.select_first();
                         self.view_mode = ViewMode::Detail;
@@ -102,10 +116,7 @@ impl TransactionsTabState {
                         if !self.search_input.is_empty() {
                             let input_regex = Regex::new(&self.search_input).unwrap();
 
-                            txs.retain(|tx| {
-                                input_regex.is_match(&tx.hash)
-                                    || input_regex.is_match(&tx.block_slot.to_string())
-                            });
+                            txs.retain(|tx| tx.matches(&input_regex));
 
                             if txs.is_empty() {
                                 if let Ok(v) = hex::decode(&self.search_input) {
@@ -307,11 +318,19 @@ impl StatefulWidget for TransactionsTab {
             }
             ViewMode::Detail => {
                 if state.tx_selected.is_none() {
-                    let index = state.table_state.selected().unwrap();
-                    state.tx_selected = Some(state.txs[index].clone());
+                    state.tx_selected = state
+                        .table_state
+                        .selected()
+                        .and_then(|index| state.txs.get(index))
+                        .cloned();
                 }
 
-                TransactionsDetail::new(state.tx_selected.clone().unwrap()).render(
+                let Some(tx_view) = state.tx_selected.clone() else {
+                    state.view_mode = ViewMode::Normal;
+                    return;
+                };
+
+                TransactionsDetail::new(tx_view, state.detail_state.compact).render(
                     area,
                     buf,
                     &mut state.detail_state,
@@ -324,10 +343,14 @@ impl StatefulWidget for TransactionsTab {
 #[derive(Default)]
 pub struct TransactionsDetailState {
     tree_state: TreeState<String>,
+    compact: bool,
 }
 impl TransactionsDetailState {
     pub fn handle_key(&mut self, key: &KeyEvent) {
         match key.code {
+            KeyCode::Char('c') => {
+                self.compact = !self.compact;
+            }
             KeyCode::Enter => {
                 self.tree_state.toggle_selected();
             }
@@ -353,12 +376,14 @@ pub struct TransactionsDetail {
     items: Vec<TreeItem<'static, String>>,
 }
 impl TransactionsDetail {
-    pub fn new(tx_view: TxView) -> Self {
-        let items = Self::build_tree_items(tx_view);
+    pub fn new(tx_view: TxView, compact: bool) -> Self {
+        let items = Self::build_tree_items(tx_view, compact);
         Self { items }
     }
 
-    fn build_tree_items(tx_view: TxView) -> Vec<TreeItem<'static, String>> {
+    /// Builds the detail tree of the tx. When `compact` is set, placeholder
+    /// nodes for absent data (e.g. `Validity: None`) are left out.
+    fn build_tree_items(tx_view: TxView, compact: bool) -> Vec<TreeItem<'static, String>> {
         let tx = tx_view.tx.as_ref().unwrap();
         let tx_hash = hex::encode(&tx.hash);
 
@@ -366,7 +391,7 @@ impl TransactionsDetail {
             TreeItem::new_leaf("tx_hash_info".to_string(), format!("Hash: {tx_hash}")),
             TreeItem::new_leaf(
                 "tx_fee_info".to_string(),
-                format!("Fee: {:?}", crate::utils::format_bigint_opt(&tx.fee)),
+                format!("Fee: {}", crate::utils::format_coin_opt(&tx.fee)),
             ),
         ];
 
@@ -396,7 +421,7 @@ impl TransactionsDetail {
             tx.inputs
                 .iter()
                 .enumerate()
-                .flat_map(|(i, input)| map_tx_input(input, &i.to_string(), &tx_hash))
+                .flat_map(|(i, input)| map_tx_input(input, &i.to_string(), &tx_hash, compact))
                 .collect(),
         )
         .expect("Failed to create inputs node");
@@ -409,7 +434,7 @@ impl TransactionsDetail {
             tx.outputs
                 .iter()
                 .enumerate()
-                .map(|(i, output)| map_tx_output(output, i, &tx_hash))
+                .map(|(i, output)| map_tx_output(output, i, &tx_hash, compact))
                 .collect(),
         )
         .expect("Failed to create outputs node");
@@ -423,7 +448,9 @@ impl TransactionsDetail {
                 tx.reference_inputs
                     .iter()
                     .enumerate()
-                    .flat_map(|(i, input)| map_tx_input(input, &format!("reference_{i}"), &tx_hash))
+                    .flat_map(|(i, input)| {
+                        map_tx_input(input, &format!("reference_{i}"), &tx_hash, compact)
+                    })
                     .collect(),
             )
             .expect("Failed to create reference inputs node");
@@ -461,7 +488,9 @@ impl TransactionsDetail {
                                 .expect("Failed to create mint asset node")
                             })
                             .collect::<Vec<_>>();
-                        children.extend(map_redeemer(&mint.redeemer, &format!("mint_{i}")));
+                        if !compact || mint.redeemer.is_some() {
+                            children.extend(map_redeemer(&mint.redeemer, &format!("mint_{i}")));
+                        }
                         TreeItem::new(
                             format!("mint_policy_{policy_id}_{i}"),
                             format!("Policy: {policy_id}"),
@@ -488,7 +517,7 @@ impl TransactionsDetail {
                             .iter()
                             .enumerate()
                             .flat_map(|(i, input)| {
-                                map_tx_input(input, &format!("collateral_{i}"), &tx_hash)
+                                map_tx_input(input, &format!("collateral_{i}"), &tx_hash, compact)
                             })
                             .collect(),
                     )
@@ -498,8 +527,8 @@ impl TransactionsDetail {
             children.push(TreeItem::new_leaf(
                 "total_collateral".to_string(),
                 format!(
-                    "Total Collateral: {:?}",
-                    crate::utils::format_bigint_opt(&collateral.total_collateral)
+                    "Total Collateral: {}",
+                    crate::utils::format_coin_opt(&collateral.total_collateral)
                 ),
             ));
             let collateral_node =
@@ -517,7 +546,7 @@ impl TransactionsDetail {
                     .iter()
                     .enumerate()
                     .flat_map(|(i, withdrawal)| {
-                        map_withdrawal(withdrawal, &format!("withdrawal_{i}"))
+                        map_withdrawal(withdrawal, &format!("withdrawal_{i}"), compact)
                     })
                     .collect(),
             )
@@ -526,10 +555,18 @@ impl TransactionsDetail {
         }
 
         // Witness Set
-        root.extend(map_witness_set(&tx.witnesses, 0));
+        if !compact || tx.witnesses.is_some() {
+            root.extend(map_witness_set(&tx.witnesses, 0));
+        }
 
         // Validity
-        root.extend(map_tx_validity(&tx.validity, 0));
+        let has_validity = tx
+            .validity
+            .as_ref()
+            .is_some_and(|validity| validity.start > 0 || validity.ttl > 0);
+        if !compact || has_validity {
+            root.extend(map_tx_validity(&tx.validity, 0));
+        }
 
         // Auxiliary Data
         root.extend(map_aux_data(&tx.auxiliary, 0));
@@ -548,7 +585,7 @@ impl StatefulWidget for TransactionsDetail {
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let block = Block::bordered()
-            .title(" Transaction Detail | press ESC to go back ")
+            .title(" Transaction Detail | press c to toggle compact | press ESC to go back ")
             .padding(Padding::symmetric(2, 1));
         block.clone().render(area, buf);
 
@@ -585,6 +622,7 @@ pub struct TxView {
     block_slot: u64,
     block_height: u64,
     block_hash: String,
+    addresses: Vec<String>,
 }
 impl TxView {
     pub fn from_chain_block(chain_block: &ChainBlock) -> Vec<Self> {
@@ -626,6 +664,7 @@ impl TxView {
                     block_slot: chain_block.slot,
                     block_height: chain_block.number,
                     block_hash: hex::encode(&chain_block.hash),
+                    addresses: collect_addresses(tx),
                 }
             })
             .collect()
@@ -665,8 +704,36 @@ impl TxView {
             block_slot: block_ref.slot,
             block_height: block_ref.height,
             block_hash: hex::encode(&block_ref.hash),
+            addresses: collect_addresses(tx),
         })
     }
+
+    /// Whether the search filter matches the tx hash, the block slot or any of
+    /// the input/output addresses involved in the tx.
+    pub fn matches(&self, regex: &Regex) -> bool {
+        regex.is_match(&self.hash)
+            || regex.is_match(&self.block_slot.to_string())
+            || self.addresses.iter().any(|address| regex.is_match(address))
+    }
+}
+
+/// Decodes the bech32 addresses of the tx outputs and of the resolved inputs,
+/// computed once so that filtering doesn't have to decode them on every search.
+fn collect_addresses(tx: &Tx) -> Vec<String> {
+    let inputs = tx
+        .inputs
+        .iter()
+        .filter_map(|input| input.as_output.as_ref());
+
+    let mut addresses: Vec<String> = inputs
+        .chain(tx.outputs.iter())
+        .filter_map(|output| Address::from_bytes(&output.address).ok())
+        .map(|address| address.to_string())
+        .collect();
+
+    addresses.sort();
+    addresses.dedup();
+    addresses
 }
 
 fn map_cert_stake_credential<'a>(v: &cardano::StakeCredential) -> Vec<TreeItem<'a, String>> {
@@ -769,11 +836,11 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                     ),
                     TreeItem::new_leaf(
                         format!("pledge_{i}"),
-                        format!("Pledge: {:?}", crate::utils::format_bigint_opt(&v.pledge)),
+                        format!("Pledge: {}", crate::utils::format_coin_opt(&v.pledge)),
                     ),
                     TreeItem::new_leaf(
                         format!("cost_{i}"),
-                        format!("Cost: {:?}", crate::utils::format_bigint_opt(&v.cost)),
+                        format!("Cost: {}", crate::utils::format_coin_opt(&v.cost)),
                     ),
                     TreeItem::new_leaf(
                         format!("reward_account_{i}"),
@@ -898,8 +965,8 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                                     target_children.push(TreeItem::new_leaf(
                                         format!("delta_coin_{i}_{j}"),
                                         format!(
-                                            "Delta Coin: {:?}",
-                                            crate::utils::format_bigint_opt(&target.delta_coin)
+                                            "Delta Coin: {}",
+                                            crate::utils::format_coin_opt(&target.delta_coin)
                                         ),
                                     ));
                                     TreeItem::new(
@@ -933,7 +1000,7 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                     .unwrap_or_default();
                 reg_children.push(TreeItem::new_leaf(
                     format!("coin_{i}"),
-                    format!("Coin: {:?}", crate::utils::format_bigint_opt(&v.coin)),
+                    format!("Coin: {}", crate::utils::format_coin_opt(&v.coin)),
                 ));
                 TreeItem::new(format!("reg_cert_{i}"), "Registration", reg_children)
                     .expect("Failed to create registration certificate node")
@@ -946,7 +1013,7 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                     .unwrap_or_default();
                 unreg_children.push(TreeItem::new_leaf(
                     format!("coin_{i}"),
-                    format!("Coin: {:?}", crate::utils::format_bigint_opt(&v.coin)),
+                    format!("Coin: {}", crate::utils::format_coin_opt(&v.coin)),
                 ));
                 TreeItem::new(format!("unreg_cert_{i}"), "Unregistration", unreg_children)
                     .expect("Failed to create unregistration certificate node")
@@ -981,7 +1048,7 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                 ));
                 stake_reg_children.push(TreeItem::new_leaf(
                     format!("coin_{i}"),
-                    format!("Coin: {:?}", crate::utils::format_bigint_opt(&v.coin)),
+                    format!("Coin: {}", crate::utils::format_coin_opt(&v.coin)),
                 ));
                 TreeItem::new(
                     format!("stake_reg_deleg_cert_{i}"),
@@ -999,7 +1066,7 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                 vote_reg_children.extend(map_drep(&v.drep, i));
                 vote_reg_children.push(TreeItem::new_leaf(
                     format!("coin_{i}"),
-                    format!("Coin: {:?}", crate::utils::format_bigint_opt(&v.coin)),
+                    format!("Coin: {}", crate::utils::format_coin_opt(&v.coin)),
                 ));
                 TreeItem::new(
                     format!("vote_reg_deleg_cert_{i}"),
@@ -1021,7 +1088,7 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                 stake_vote_reg_children.extend(map_drep(&v.drep, i));
                 stake_vote_reg_children.push(TreeItem::new_leaf(
                     format!("coin_{i}"),
-                    format!("Coin: {:?}", crate::utils::format_bigint_opt(&v.coin)),
+                    format!("Coin: {}", crate::utils::format_coin_opt(&v.coin)),
                 ));
                 TreeItem::new(
                     format!("stake_vote_reg_deleg_cert_{i}"),
@@ -1079,7 +1146,7 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                     .unwrap_or_default();
                 reg_drep_children.push(TreeItem::new_leaf(
                     format!("coin_{i}"),
-                    format!("Coin: {:?}", crate::utils::format_bigint_opt(&v.coin)),
+                    format!("Coin: {}", crate::utils::format_coin_opt(&v.coin)),
                 ));
                 if let Some(anchor) = &v.anchor {
                     reg_drep_children.push(TreeItem::new_leaf(
@@ -1106,7 +1173,7 @@ fn map_cert<'a>(tx: &Tx) -> TreeItem<'a, String> {
                     .unwrap_or_default();
                 unreg_drep_children.push(TreeItem::new_leaf(
                     format!("coin_{i}"),
-                    format!("Coin: {:?}", crate::utils::format_bigint_opt(&v.coin)),
+                    format!("Coin: {}", crate::utils::format_coin_opt(&v.coin)),
                 ));
                 TreeItem::new(
                     format!("unreg_drep_cert_{i}"),
@@ -1558,7 +1625,11 @@ fn map_metadatum<'a>(metadatum: &Metadatum, index: &str) -> Vec<TreeItem<'a, Str
     }
 }
 
-fn map_withdrawal<'a>(withdrawal: &Withdrawal, index: &str) -> Vec<TreeItem<'a, String>> {
+fn map_withdrawal<'a>(
+    withdrawal: &Withdrawal,
+    index: &str,
+    compact: bool,
+) -> Vec<TreeItem<'a, String>> {
     let mut children = vec![
         TreeItem::new_leaf(
             format!("withdrawal_account_{index}"),
@@ -1569,13 +1640,12 @@ fn map_withdrawal<'a>(withdrawal: &Withdrawal, index: &str) -> Vec<TreeItem<'a,
         ),
         TreeItem::new_leaf(
             format!("withdrawal_coin_{index}"),
-            format!(
-                "Coin: {:?}",
-                crate::utils::format_bigint_opt(&withdrawal.coin)
-            ),
+            format!("Coin: {}", crate::utils::format_coin_opt(&withdrawal.coin)),
         ),
     ];
-    children.extend(map_redeemer(&withdrawal.redeemer, index));
+    if !compact || withdrawal.redeemer.is_some() {
+        children.extend(map_redeemer(&withdrawal.redeemer, index));
+    }
     children
 }
 
@@ -1739,7 +1809,12 @@ fn map_tx_validity<'a>(validity: &Option<TxValidity>, index: usize) -> Vec<TreeI
     }
 }
 
-fn map_tx_input<'a>(input: &TxInput, index: &str, tx_hash: &str) -> Vec<TreeItem<'a, String>> {
+fn map_tx_input<'a>(
+    input: &TxInput,
+    index: &str,
+    tx_hash: &str,
+    compact: bool,
+) -> Vec<TreeItem<'a, String>> {
     let mut children = vec![
         TreeItem::new_leaf(
             format!("input_hash_{index}"),
@@ -1758,11 +1833,14 @@ fn map_tx_input<'a>(input: &TxInput, index: &str, tx_hash: &str) -> Vec<TreeItem
                 as_output,
                 input.output_index as usize,
                 tx_hash,
+                compact,
             )],
         )
         .expect("Failed to create as_output input node")]);
     }
-    children.extend(map_redeemer(&input.redeemer, index));
+    if !compact || input.redeemer.is_some() {
+        children.extend(map_redeemer(&input.redeemer, index));
+    }
     vec![TreeItem::new(
         format!("input_{tx_hash}_{index}"),
         format!("{}#{}", hex::encode(&input.tx_hash), input.output_index),
@@ -1771,7 +1849,12 @@ fn map_tx_input<'a>(input: &TxInput, index: &str, tx_hash: &str) -> Vec<TreeItem
     .expect("Failed to create input node")]
 }
 
-fn map_tx_output<'a>(output: &TxOutput, index: usize, tx_hash: &str) -> TreeItem<'a, String> {
+fn map_tx_output<'a>(
+    output: &TxOutput,
+    index: usize,
+    tx_hash: &str,
+    compact: bool,
+) -> TreeItem<'a, String> {
     let address = Address::from_bytes(&output.address)
         .map_or("decoded fail".to_string(), |addr| addr.to_string());
     let mut children = vec![
@@ -1781,7 +1864,7 @@ fn map_tx_output<'a>(output: &TxOutput, index: usize, tx_hash: &str) -> TreeItem
         ),
         TreeItem::new_leaf(
             format!("output_{tx_hash}_{index}_coin"),
-            format!("Coin: {:?}", crate::utils::format_bigint_opt(&output.coin)),
+            format!("Coin: {}", crate::utils::format_coin_opt(&output.coin)),
         ),
     ];
     if !output.assets.is_empty() {
@@ -1817,7 +1900,10 @@ fn map_tx_output<'a>(output: &TxOutput, index: usize, tx_hash: &str) -> TreeItem
                             })
                             .collect::<Vec<_>>();
 
-                        asset_children.extend(map_redeemer(&m.redeemer, &format!("output_{i}")));
+                        if !compact || m.redeemer.is_some() {
+                            asset_children
+                                .extend(map_redeemer(&m.redeemer, &format!("output_{i}")));
+                        }
 
                         TreeItem::new(
                             format!("output_policy_{policy_id}_{i}"),
@@ -1831,8 +1917,12 @@ fn map_tx_output<'a>(output: &TxOutput, index: usize, tx_hash: &str) -> TreeItem
             .expect("Failed to create assets node"),
         );
     }
-    children.extend(map_datum(&output.datum, &index.to_string()));
-    children.extend(map_script(&output.script, &index.to_string()));
+    if !compact || output.datum.is_some() {
+        children.extend(map_datum(&output.datum, &index.to_string()));
+    }
+    if !compact || output.script.is_some() {
+        children.extend(map_script(&output.script, &index.to_string()));
+    }
     TreeItem::new(
         format!("output_{tx_hash}_{index}"),
         format!("{tx_hash}#{index}"),
@@ -1840,3 +1930,136 @@ fn map_tx_output<'a>(output: &TxOutput, index: usize, tx_hash: &str) -> TreeItem
     )
     .expect("Failed to create output node")
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas::{
+        crypto::hash::Hash,
+        ledger::addresses::{Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart},
+    };
+
+    use super::*;
+
+    fn address(seed: u8) -> Address {
+        ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::key_hash(Hash::new([seed; 28])),
+            ShelleyDelegationPart::Null,
+        )
+        .into()
+    }
+
+    fn output(address: &Address) -> TxOutput {
+        TxOutput {
+            address: address.to_vec().into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matches_input_and_output_addresses() {
+        let sender = address(1);
+        let receiver = address(2);
+        let stranger = address(3);
+
+        let tx = Tx {
+            hash: vec![0xab; 32].into(),
+            inputs: vec![TxInput {
+                as_output: Some(output(&sender)),
+                ..Default::default()
+            }],
+            outputs: vec![output(&receiver)],
+            ..Default::default()
+        };
+
+        let block = ChainBlock {
+            slot: 42,
+            hash: vec![0; 32],
+            number: 1,
+            tx_count: 1,
+            body: Some(cardano::BlockBody { tx: vec![tx] }),
+        };
+
+        let views = TxView::from_chain_block(&block);
+        let view = views.first().unwrap();
+
+        let regex = |value: &str| Regex::new(value).unwrap();
+
+        assert!(view.matches(&regex(&sender.to_string())));
+        assert!(view.matches(&regex(&receiver.to_string())));
+        assert!(!view.matches(&regex(&stranger.to_string())));
+        assert!(view.matches(&regex("^abab")));
+        assert!(view.matches(&regex("^42$")));
+    }
+
+    #[test]
+    fn collects_each_address_once() {
+        let (sender, receiver) = (address(1), address(2));
+
+        let tx = Tx {
+            inputs: vec![TxInput {
+                as_output: Some(output(&sender)),
+                ..Default::default()
+            }],
+            outputs: vec![output(&receiver), output(&sender)],
+            ..Default::default()
+        };
+
+        assert_eq!(collect_addresses(&tx).len(), 2);
+    }
+
+    #[test]
+    fn compact_tree_skips_absent_fields() {
+        let tx = Tx {
+            hash: vec![0xab; 32].into(),
+            inputs: vec![TxInput {
+                tx_hash: vec![0xcd; 32].into(),
+                ..Default::default()
+            }],
+            outputs: vec![output(&address(1))],
+            validity: Some(TxValidity { start: 0, ttl: 0 }),
+            ..Default::default()
+        };
+
+        let block = ChainBlock {
+            slot: 42,
+            hash: vec![0; 32],
+            number: 1,
+            tx_count: 1,
+            body: Some(cardano::BlockBody { tx: vec![tx] }),
+        };
+        let view = TxView::from_chain_block(&block).pop().unwrap();
+
+        // hash, fee, block info, inputs, outputs, witness set, validity
+        let full = TransactionsDetail::build_tree_items(view.clone(), false);
+        assert_eq!(full.len(), 7);
+
+        // hash, fee, block info, inputs, outputs
+        let compact = TransactionsDetail::build_tree_items(view, true);
+        assert_eq!(compact.len(), 5);
+
+        // input: hash, index (redeemer placeholder skipped)
+        let input = &compact[3].children()[0];
+        assert_eq!(input.children().len(), 2);
+
+        // output: address, coin (datum and script placeholders skipped)
+        let output = &compact[4].children()[0];
+        assert_eq!(output.children().len(), 2);
+
+        let full_output = &full[4].children()[0];
+        assert_eq!(full_output.children().len(), 4);
+    }
+
+    #[test]
+    fn body_less_block_has_no_txs() {
+        let block = ChainBlock {
+            slot: 42,
+            hash: vec![0; 32],
+            number: 1,
+            tx_count: 0,
+            body: None,
+        };
+
+        assert!(TxView::from_chain_block(&block).is_empty());
+    }
+}
@@ -9,6 +9,7 @@ mod output;
 mod provider;
 mod reports;
 mod search;
+mod serve;
 mod store;
 mod tx;
 mod types;
@@ -41,6 +42,14 @@ struct Cli {
     )]
     output_format: Option<output::OutputFormat>,
 
+    #[arg(
+        long,
+        global = true,
+        help = "JSON pointer (RFC-6901) used to extract a single value from the JSON output",
+        env = "CSHELL_SELECT"
+    )]
+    select: Option<String>,
+
     #[arg(
         long,
         help = "Control the verbosity of CShell logging",
@@ -48,6 +57,23 @@ struct Cli {
         global = true
     )]
     log_level: Option<LogLevel>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Print provider request stats of this run to stderr on exit",
+        env = "CSHELL_METRICS"
+    )]
+    metrics: bool,
+
+    // not global, as some subcommands have a `--network` of their own
+    #[arg(
+        long,
+        value_enum,
+        help = "Use the default provider of this network",
+        env = "CSHELL_NETWORK"
+    )]
+    network: Option<provider::types::NetworkName>,
 }
 
 #[derive(Subcommand)]
@@ -69,6 +95,12 @@ enum Commands {
 
     /// Search on chain data
     Search(search::Args),
+
+    /// Manage the config store
+    Store(store::Args),
+
+    /// Serve wallet and chain queries over JSON-RPC
+    Serve(serve::Args),
 }
 
 #[derive(Clone, ValueEnum)]
@@ -97,13 +129,22 @@ pub struct Context {
     pub output_format: output::OutputFormat,
     pub log_level: LogLevel,
     pub output_format_overridden: bool,
+    pub output_select: Option<String>,
 }
 
 impl Context {
     fn from_cli(cli: &Cli) -> anyhow::Result<Self> {
-        let store = store::Store::open(cli.store_path.clone())?;
+        let mut store = store::Store::open(cli.store_path.clone())?;
+        store.select_network(cli.network);
         let (output_format, output_format_overridden) = match cli.output_format.clone() {
+            Some(output::OutputFormat::Table | output::OutputFormat::Markdown)
+                if cli.select.is_some() =>
+            {
+                anyhow::bail!("--select only applies to the json output format")
+            }
             Some(value) => (value, true),
+            // Selecting a value only makes sense on top of the JSON output
+            None if cli.select.is_some() => (output::OutputFormat::Json, false),
             None => (output::OutputFormat::Table, false),
         };
         let log_level = cli.log_level.clone().unwrap_or(LogLevel::Info);
@@ -113,9 +154,14 @@ impl Context {
             output_format,
             log_level,
             output_format_overridden,
+            output_select: cli.select.clone(),
         })
     }
 
+    pub fn output_select(&self) -> Option<&str> {
+        self.output_select.as_deref()
+    }
+
     pub fn with_tracing(&self) {
         let level_filter: LevelFilter = self.log_level.borrow().into();
         tracing_subscriber::registry()
@@ -138,9 +184,13 @@ async fn run_command() -> anyhow::Result<()> {
         Commands::Wallet(args) => wallet::run(args, &mut ctx).await,
         Commands::Explorer(args) => explorer::run(args, &ctx).await,
         Commands::Search(args) => search::run(args, &mut ctx).await,
+        Commands::Store(args) => store::run(args, &ctx).await,
+        Commands::Serve(args) => serve::run(args, &ctx).await,
     };
 
-    ctx.store.write()?;
+    if cli.metrics {
+        provider::metrics::report(&ctx.output_format)?;
+    }
 
     result
 }
@@ -1,19 +1,120 @@
+use anyhow::{bail, Result};
 use clap::ValueEnum;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use serde_json::Value;
 
 #[derive(ValueEnum, Clone)]
 pub enum OutputFormat {
     Json,
     Table,
+    /// Tables rendered as GitHub-flavored markdown
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Style of the tables printed in this format.
+    pub fn table_style(&self) -> TableStyle {
+        match self {
+            OutputFormat::Markdown => TableStyle::Markdown,
+            OutputFormat::Json | OutputFormat::Table => TableStyle::Ascii,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TableStyle {
+    Ascii,
+    Markdown,
+}
+
+/// Creates the table printed by `to_table`, in the given style.
+pub fn table(style: TableStyle) -> Table {
+    let mut table = Table::new();
+
+    if style == TableStyle::Markdown {
+        table.load_preset(ASCII_MARKDOWN);
+    }
+
+    table
 }
 
 pub trait OutputFormatter {
-    fn to_table(&self);
-    fn to_json(&self);
+    fn to_table(&self, style: TableStyle);
+    fn to_json(&self) -> Result<Value>;
 
-    fn output(&self, format: &OutputFormat) {
+    fn output(&self, format: &OutputFormat, select: Option<&str>) -> Result<()> {
         match format {
-            OutputFormat::Table => self.to_table(),
-            OutputFormat::Json => self.to_json(),
+            OutputFormat::Table | OutputFormat::Markdown => {
+                self.to_table(format.table_style());
+                Ok(())
+            }
+            OutputFormat::Json => print_json(&self.to_json()?, select),
         }
     }
 }
+
+/// Prints a JSON value, optionally narrowed down to the value found at the
+/// given RFC-6901 pointer.
+pub fn print_json(value: &Value, select: Option<&str>) -> Result<()> {
+    println!("{}", render_json(value, select)?);
+    Ok(())
+}
+
+/// Renders a JSON value as a pretty string. When a pointer is provided, only
+/// the selected value is rendered, and plain strings are rendered without
+/// quotes so they can be consumed directly by shell scripts.
+pub fn render_json(value: &Value, select: Option<&str>) -> Result<String> {
+    let value = match select {
+        Some(pointer) => match value.pointer(pointer) {
+            Some(Value::String(inner)) => return Ok(inner.clone()),
+            Some(inner) => inner,
+            None => bail!("json pointer '{pointer}' does not resolve to any value"),
+        },
+        None => value,
+    };
+
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn render_json_with_pointers() {
+        let value = json!({
+            "hash": "abcd",
+            "fee": 170000,
+            "outputs": [{ "address": "addr_test1", "coin": 2000000 }],
+            "signed": true,
+        });
+
+        assert_eq!(render_json(&value, Some("/hash")).unwrap(), "abcd");
+        assert_eq!(render_json(&value, Some("/fee")).unwrap(), "170000");
+        assert_eq!(render_json(&value, Some("/signed")).unwrap(), "true");
+        assert_eq!(
+            render_json(&value, Some("/outputs/0/address")).unwrap(),
+            "addr_test1"
+        );
+        assert_eq!(
+            render_json(&value, Some("/outputs/0")).unwrap(),
+            serde_json::to_string_pretty(&json!({ "address": "addr_test1", "coin": 2000000 }))
+                .unwrap()
+        );
+        assert_eq!(
+            render_json(&value, None).unwrap(),
+            serde_json::to_string_pretty(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn render_json_with_unresolved_pointer() {
+        let value = json!({ "hash": "abcd" });
+
+        assert!(render_json(&value, Some("/missing")).is_err());
+        assert!(render_json(&value, Some("/hash/0")).is_err());
+        assert!(render_json(&value, Some("hash")).is_err());
+    }
+}
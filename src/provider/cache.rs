@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use utxorpc::spec::query::AnyUtxoData;
+
+use super::types::AddressMatch;
+
+/// Provider name, address bytes and how the address is matched.
+pub type Key = (String, Vec<u8>, AddressMatch);
+
+/// UTxO searches of the current process, along with when they were made.
+static UTXOS: Mutex<BTreeMap<Key, (Instant, Vec<AnyUtxoData>)>> = Mutex::new(BTreeMap::new());
+
+fn get(key: &Key, ttl: Duration) -> Option<Vec<AnyUtxoData>> {
+    let mut utxos = UTXOS.lock().unwrap();
+
+    match utxos.get(key) {
+        Some((fetched, items)) if fetched.elapsed() < ttl => Some(items.clone()),
+        Some(_) => {
+            utxos.remove(key);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Returns the UTxOs fetched for `key` less than `ttl` ago, calling `fetch`
+/// otherwise. Without a ttl nothing is cached.
+pub async fn cached<F, Fut>(key: Key, ttl: Option<Duration>, fetch: F) -> Result<Vec<AnyUtxoData>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Vec<AnyUtxoData>>>,
+{
+    let Some(ttl) = ttl.filter(|x| !x.is_zero()) else {
+        return fetch().await;
+    };
+
+    if let Some(items) = get(&key, ttl) {
+        return Ok(items);
+    }
+
+    let items = fetch().await?;
+    UTXOS
+        .lock()
+        .unwrap()
+        .insert(key, (Instant::now(), items.clone()));
+
+    Ok(items)
+}
+
+/// Drops the searches made through `provider`, whose UTxOs a submitted tx
+/// may have spent.
+pub fn invalidate(provider: &str) {
+    UTXOS
+        .lock()
+        .unwrap()
+        .retain(|(name, _, _), _| name != provider);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_fresh_searches_from_cache() {
+        let fetches = AtomicU32::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![AnyUtxoData::default()])
+        };
+
+        let key = |x: u8| ("cache-test".to_string(), vec![x], AddressMatch::Full);
+        let ttl = Some(Duration::from_secs(60));
+
+        assert_eq!(cached(key(1), ttl, fetch).await.unwrap().len(), 1);
+        assert_eq!(cached(key(1), ttl, fetch).await.unwrap().len(), 1);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        cached(key(2), ttl, fetch).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        cached(key(3), None, fetch).await.unwrap();
+        cached(key(3), None, fetch).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 4);
+
+        cached(key(4), Some(Duration::from_millis(1)), fetch)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        cached(key(4), Some(Duration::from_millis(1)), fetch)
+            .await
+            .unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn invalidates_searches_of_a_provider() {
+        let fetches = AtomicU32::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![])
+        };
+
+        let key = |name: &str| (name.to_string(), vec![1], AddressMatch::Full);
+        let ttl = Some(Duration::from_secs(60));
+
+        cached(key("invalidate-a"), ttl, fetch).await.unwrap();
+        cached(key("invalidate-b"), ttl, fetch).await.unwrap();
+        invalidate("invalidate-a");
+
+        cached(key("invalidate-a"), ttl, fetch).await.unwrap();
+        cached(key("invalidate-b"), ttl, fetch).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
+}
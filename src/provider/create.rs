@@ -4,7 +4,14 @@ use anyhow::{bail, Result};
 use clap::Parser;
 use tracing::instrument;
 
-use crate::{output::OutputFormatter, provider::types::Provider, utils::Name};
+use crate::{
+    output::OutputFormatter,
+    provider::types::{
+        parse_header, validate_explorer_url, validate_fallback, NetworkName, Provider,
+        ProviderKind, RetryPolicy,
+    },
+    utils::Name,
+};
 
 #[derive(clap::ValueEnum, Clone, PartialEq)]
 enum NetworkKind {
@@ -41,6 +48,58 @@ pub struct Args {
     /// JSON encoded TRP headers
     #[arg(long)]
     trp_headers: Option<String>,
+
+    /// TRP header as `key:value`, can be repeated. An alternative to
+    /// `--trp-headers`.
+    #[arg(long = "trp-header", value_parser = parse_header, conflicts_with = "trp_headers")]
+    trp_header: Vec<(String, String)>,
+
+    /// Whether TRP requests also send the UTxORPC headers. TRP headers take
+    /// precedence on conflicts.
+    #[arg(long)]
+    trp_inherit_headers: Option<bool>,
+
+    /// Protocol the provider speaks. Defaults to UTxO RPC. For node
+    /// providers the url is the path to the node socket.
+    #[arg(long, value_enum)]
+    kind: Option<ProviderKind>,
+
+    /// Block-explorer url template for txs, with a `{hash}` placeholder.
+    #[arg(long)]
+    explorer_url: Option<String>,
+
+    /// Seconds to reuse UTxO search results for, to spare rate-limited
+    /// endpoints. Unset or 0 disables caching.
+    #[arg(long)]
+    utxo_cache_ttl: Option<u64>,
+
+    /// Network magic of node providers on testnets, e.g. 1 for preprod.
+    #[arg(long)]
+    network_magic: Option<u64>,
+
+    /// Public network the provider serves, when its url doesn't tell.
+    #[arg(long, value_enum)]
+    network: Option<NetworkName>,
+
+    /// Attempts of each request before giving up on the provider, e.g. on
+    /// rate limits. Requests aren't retried unless a retry flag is set.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    max_attempts: Option<u32>,
+
+    /// Milliseconds to wait before the first retry, roughly doubling on
+    /// each of the next ones.
+    #[arg(long)]
+    retry_delay_ms: Option<u64>,
+
+    /// Comma separated gRPC codes and HTTP statuses to retry on. Defaults
+    /// to 8,14,429,503.
+    #[arg(long, value_delimiter = ',')]
+    retry_on: Option<Vec<i32>>,
+
+    /// Provider to retry requests against when this one can't be reached.
+    /// Live chain feeds only move over to it while connecting.
+    #[arg(long)]
+    fallback: Option<String>,
 }
 
 #[instrument("create", skip_all)]
@@ -127,6 +186,7 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
     if trp_url.is_some() {
         let aux: HashMap<String, String> = match args.trp_headers {
             Some(inner) => serde_json::from_str(&inner).map_err(anyhow::Error::msg)?,
+            None if !args.trp_header.is_empty() => args.trp_header.into_iter().collect(),
 
             None => inquire::Text::new(
                 "Add request headers? Example: 'dmtr-api-key:dmtr_jdndajs,other:other-value'",
@@ -157,10 +217,16 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
         }
     }
 
+    if let Some(explorer_url) = &args.explorer_url {
+        validate_explorer_url(explorer_url)?;
+    }
+
     let provider = Provider {
         name,
+        kind: args.kind,
         is_default: Some(ctx.store.providers().is_empty()),
         is_testnet: Some(is_testnet),
+        network: args.network,
         url,
         headers: if headers.is_empty() {
             None
@@ -169,11 +235,20 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
         },
         trp_url,
         trp_headers,
+        trp_inherit_headers: args.trp_inherit_headers,
+        explorer_url: args.explorer_url,
+        utxo_cache_ttl: args.utxo_cache_ttl,
+        network_magic: args.network_magic,
+        retry: RetryPolicy::update(None, args.max_attempts, args.retry_delay_ms, args.retry_on),
+        fallback: args.fallback,
+        failover: vec![],
     };
 
+    validate_fallback(ctx.store.providers(), &provider)?;
+
     ctx.store.add_provider(&provider)?;
 
     // Log, print, and finish
-    provider.output(&ctx.output_format);
+    provider.output(&ctx.output_format, ctx.output_select())?;
     Ok(())
 }
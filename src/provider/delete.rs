@@ -1,3 +1,4 @@
+use anyhow::bail;
 use clap::Parser;
 use tracing::{info, instrument};
 
@@ -12,6 +13,15 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
     let provider = ctx.store.find_provider(&args.name);
     match provider {
         Some(provider) => {
+            let referrers = ctx.store.fallback_referrers(&args.name);
+            if !referrers.is_empty() {
+                bail!(
+                    "provider '{}' is the fallback of {}, edit them with `--fallback \"\"` first",
+                    args.name,
+                    referrers.join(", ")
+                );
+            }
+
             info!("Removing provider...");
             ctx.store.remove_provider(provider.clone())
         }
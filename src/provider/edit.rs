@@ -6,7 +6,10 @@ use inquire::list_option::ListOption;
 
 use crate::{
     output::OutputFormatter,
-    provider::types::Provider,
+    provider::types::{
+        parse_header, validate_explorer_url, validate_fallback, NetworkName, Provider,
+        ProviderKind, RetryPolicy,
+    },
     utils::{show_is_current, Name},
 };
 
@@ -31,6 +34,63 @@ pub struct Args {
 
     /// Whether it is mainnet or testnet.
     network_kind: Option<NetworkKind>,
+
+    /// TRP url. An empty url removes the TRP configuration.
+    #[arg(long)]
+    trp_url: Option<String>,
+
+    /// TRP header as `key:value`, can be repeated. Replaces the current TRP
+    /// headers.
+    #[arg(long = "trp-header", value_parser = parse_header)]
+    trp_header: Vec<(String, String)>,
+
+    /// Whether TRP requests also send the UTxORPC headers. TRP headers take
+    /// precedence on conflicts.
+    #[arg(long)]
+    trp_inherit_headers: Option<bool>,
+
+    /// Protocol the provider speaks. Defaults to UTxO RPC. For node
+    /// providers the url is the path to the node socket.
+    #[arg(long, value_enum)]
+    kind: Option<ProviderKind>,
+
+    /// Block-explorer url template for txs, with a `{hash}` placeholder.
+    #[arg(long)]
+    explorer_url: Option<String>,
+
+    /// Seconds to reuse UTxO search results for, to spare rate-limited
+    /// endpoints. Unset or 0 disables caching.
+    #[arg(long)]
+    utxo_cache_ttl: Option<u64>,
+
+    /// Network magic of node providers on testnets, e.g. 1 for preprod.
+    #[arg(long)]
+    network_magic: Option<u64>,
+
+    /// Public network the provider serves, when its url doesn't tell.
+    #[arg(long, value_enum)]
+    network: Option<NetworkName>,
+
+    /// Attempts of each request before giving up on the provider, e.g. on
+    /// rate limits. Requests aren't retried unless a retry flag is set.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    max_attempts: Option<u32>,
+
+    /// Milliseconds to wait before the first retry, roughly doubling on
+    /// each of the next ones.
+    #[arg(long)]
+    retry_delay_ms: Option<u64>,
+
+    /// Comma separated gRPC codes and HTTP statuses to retry on. Defaults
+    /// to 8,14,429,503.
+    #[arg(long, value_delimiter = ',')]
+    retry_on: Option<Vec<i32>>,
+
+    /// Provider to retry requests against when this one can't be reached.
+    /// Live chain feeds only move over to it while connecting.
+    /// An empty name removes the fallback.
+    #[arg(long)]
+    fallback: Option<String>,
 }
 
 // #[instrument("create", skip_all)]
@@ -135,47 +195,73 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
     })
     .collect::<Result<_, anyhow::Error>>()?;
 
-    let new_trp_url = inquire::Text::new("TRP URL:")
-        .with_default(&provider.trp_url.clone().unwrap_or("".to_string()))
-        .prompt()
-        .map_err(anyhow::Error::msg)?;
+    // a TRP url given as flag keeps the current headers unless they are
+    // given too, rather than prompting for them
+    let trp_url_given = args.trp_url.is_some();
 
-    let current_trp_headers = provider
-        .trp_headers
-        .clone()
-        .map(|headers| {
-            headers
-                .into_iter()
-                .map(|(key, value)| format!("{key}:{value}"))
-                .collect::<Vec<String>>()
-                .join(",")
+    let new_trp_url = match args.trp_url {
+        Some(trp_url) => trp_url,
+        None => inquire::Text::new("TRP URL:")
+            .with_default(&provider.trp_url.clone().unwrap_or("".to_string()))
+            .prompt()
+            .map_err(anyhow::Error::msg)?,
+    };
+
+    let new_trp_headers: HashMap<String, String> = if !args.trp_header.is_empty() {
+        args.trp_header.into_iter().collect()
+    } else if trp_url_given {
+        provider.trp_headers.clone().unwrap_or_default()
+    } else {
+        let current_trp_headers = provider
+            .trp_headers
+            .clone()
+            .map(|headers| {
+                headers
+                    .into_iter()
+                    .map(|(key, value)| format!("{key}:{value}"))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            })
+            .unwrap_or("".to_string());
+
+        inquire::Text::new(
+            "Add TRP request headers? Example: 'dmtr-api-key:dmtr_jdndajs,other:other-value'",
+        )
+        .with_default(&current_trp_headers)
+        .prompt()
+        .map_err(anyhow::Error::msg)?
+        .split(",")
+        .map(|keyval| {
+            let mut parts = keyval.split(":");
+            let key = match parts.next() {
+                Some(s) => s,
+                None => bail!("Invalid header."),
+            };
+            let val = match parts.next() {
+                Some(s) => s,
+                None => bail!("Invalid header."),
+            };
+            Ok((key.to_string(), val.to_string()))
         })
-        .unwrap_or("".to_string());
-    let new_trp_headers: HashMap<String, String> = inquire::Text::new(
-        "Add TRP request headers? Example: 'dmtr-api-key:dmtr_jdndajs,other:other-value'",
-    )
-    .with_default(&current_trp_headers)
-    .prompt()
-    .map_err(anyhow::Error::msg)?
-    .split(",")
-    .map(|keyval| {
-        let mut parts = keyval.split(":");
-        let key = match parts.next() {
-            Some(s) => s,
-            None => bail!("Invalid header."),
-        };
-        let val = match parts.next() {
-            Some(s) => s,
-            None => bail!("Invalid header."),
-        };
-        Ok((key.to_string(), val.to_string()))
-    })
-    .collect::<Result<_, anyhow::Error>>()?;
+        .collect::<Result<_, anyhow::Error>>()?
+    };
+
+    if let Some(explorer_url) = &args.explorer_url {
+        validate_explorer_url(explorer_url)?;
+    }
+
+    let new_fallback = match args.fallback {
+        Some(fallback) if fallback.is_empty() => None,
+        Some(fallback) => Some(fallback),
+        None => provider.fallback.clone(),
+    };
 
     let new_provider = Provider {
         name: new_name,
+        kind: args.kind.or(provider.kind),
         is_default: Some(new_is_default),
         is_testnet: Some(new_is_testnet),
+        network: args.network.or(provider.network),
         url: new_url,
         headers: if new_headers.is_empty() {
             None
@@ -192,12 +278,27 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
         } else {
             Some(new_trp_headers)
         },
+        trp_inherit_headers: args.trp_inherit_headers.or(provider.trp_inherit_headers),
+        explorer_url: args.explorer_url.or(provider.explorer_url.clone()),
+        utxo_cache_ttl: args.utxo_cache_ttl.or(provider.utxo_cache_ttl),
+        network_magic: args.network_magic.or(provider.network_magic),
+        retry: RetryPolicy::update(
+            provider.retry.clone(),
+            args.max_attempts,
+            args.retry_delay_ms,
+            args.retry_on,
+        ),
+        fallback: new_fallback,
+        failover: vec![],
     };
 
+    // a kept fallback is checked again, the network might have changed
+    validate_fallback(ctx.store.providers(), &new_provider)?;
+
     ctx.store.remove_provider(provider.clone())?;
     ctx.store.add_provider(&new_provider)?;
 
     // Log, print, and finish
-    new_provider.output(&ctx.output_format);
+    new_provider.output(&ctx.output_format, ctx.output_select())?;
     Ok(())
 }
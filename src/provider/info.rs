@@ -20,7 +20,7 @@ pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
 
     match provider {
         Some(provider) => {
-            provider.output(&ctx.output_format);
+            provider.output(&ctx.output_format, ctx.output_select())?;
             Ok(())
         }
         None => bail!("Wallet not found."),
@@ -1,6 +1,8 @@
 use crate::output::OutputFormatter;
 
 pub async fn run(ctx: &crate::Context) -> anyhow::Result<()> {
-    ctx.store.providers().output(&ctx.output_format);
+    ctx.store
+        .providers()
+        .output(&ctx.output_format, ctx.output_select())?;
     Ok(())
 }
@@ -0,0 +1,502 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{anyhow, bail, Context, Result};
+use pallas::{
+    interop::utxorpc::{LedgerContext, Mapper, TxoRef as TxoRefKey, UtxoMap},
+    ledger::{
+        addresses::{Address, ByronAddress},
+        traverse::MultiEraTx,
+    },
+};
+use prost::Message;
+use serde::{de::DeserializeOwned, Deserialize};
+use utxorpc::{
+    spec::{
+        cardano::{asset, Asset, Block, BlockBody, BlockHeader, Datum, Multiasset, Tx, TxOutput},
+        query::{any_utxo_data::ParsedState, AnyUtxoData, ChainPoint, TxoRef},
+    },
+    ChainBlock, ChainTx,
+};
+
+use super::types::{Rejected, Tip};
+use crate::utils::to_bigint;
+
+/// Largest page the utxos endpoint serves.
+const PAGE_SIZE: &str = "100";
+
+/// Attempts at each tx request of a block while maestro throttles it.
+const THROTTLED_ATTEMPTS: u32 = 3;
+
+/// Wait before the second attempt of a throttled request, doubling after.
+const THROTTLED_WAIT: Duration = Duration::from_secs(1);
+
+/// Maestro responses carry the payload under `data`, along with chain info
+/// we don't need.
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Amount {
+    /// `lovelace`, or the policy id followed by the asset name, in hex.
+    unit: String,
+    amount: u64,
+}
+
+#[derive(Deserialize)]
+struct UtxoDatum {
+    hash: String,
+    bytes: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Utxo {
+    tx_hash: String,
+    index: u32,
+    slot: u64,
+    address: String,
+    assets: Vec<Amount>,
+    datum: Option<UtxoDatum>,
+}
+
+#[derive(Deserialize)]
+struct TxInfo {
+    block_hash: String,
+    block_absolute_slot: u64,
+}
+
+#[derive(Deserialize)]
+struct ChainTip {
+    block_hash: String,
+    slot: u64,
+    height: u64,
+}
+
+#[derive(Deserialize)]
+struct BlockInfo {
+    hash: String,
+    height: u64,
+    absolute_slot: u64,
+    tx_hashes: Vec<String>,
+}
+
+impl TryFrom<Utxo> for AnyUtxoData {
+    type Error = anyhow::Error;
+
+    fn try_from(utxo: Utxo) -> Result<Self> {
+        let address = Address::from_bech32(&utxo.address)
+            .or_else(|_| ByronAddress::from_base58(&utxo.address).map(Address::Byron))
+            .with_context(|| format!("invalid address '{}'", utxo.address))?;
+
+        let mut coin = 0;
+        let mut assets: Vec<Multiasset> = vec![];
+
+        for amount in utxo.assets {
+            if amount.unit == "lovelace" {
+                coin = amount.amount;
+                continue;
+            }
+
+            let unit = hex::decode(&amount.unit)?;
+            if unit.len() < 28 {
+                bail!("invalid asset unit '{}'", amount.unit);
+            }
+            let (policy_id, name) = unit.split_at(28);
+
+            let asset = Asset {
+                name: name.to_vec().into(),
+                quantity: Some(asset::Quantity::OutputCoin(to_bigint(amount.amount))),
+            };
+
+            match assets.iter_mut().find(|x| x.policy_id == policy_id) {
+                Some(multiasset) => multiasset.assets.push(asset),
+                None => assets.push(Multiasset {
+                    policy_id: policy_id.to_vec().into(),
+                    assets: vec![asset],
+                    ..Default::default()
+                }),
+            }
+        }
+
+        let datum = match utxo.datum {
+            Some(datum) => Some(Datum {
+                hash: hex::decode(datum.hash)?.into(),
+                original_cbor: datum
+                    .bytes
+                    .map(hex::decode)
+                    .transpose()?
+                    .unwrap_or_default()
+                    .into(),
+                ..Default::default()
+            }),
+            None => None,
+        };
+
+        Ok(AnyUtxoData {
+            txo_ref: Some(TxoRef {
+                hash: hex::decode(&utxo.tx_hash)?.into(),
+                index: utxo.index,
+            }),
+            block_ref: Some(ChainPoint {
+                slot: utxo.slot,
+                ..Default::default()
+            }),
+            parsed_state: Some(ParsedState::Cardano(TxOutput {
+                address: address.to_vec().into(),
+                coin: Some(to_bigint(coin)),
+                assets,
+                datum,
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+fn is_throttled(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .and_then(reqwest::Error::status)
+            == Some(reqwest::StatusCode::TOO_MANY_REQUESTS)
+    })
+}
+
+#[derive(Clone)]
+struct NoLedger;
+
+impl LedgerContext for NoLedger {
+    fn get_utxos(&self, _refs: &[TxoRefKey]) -> Option<UtxoMap> {
+        None
+    }
+
+    fn get_slot_timestamp(&self, _slot: u64) -> Option<u64> {
+        None
+    }
+}
+
+/// Pallas maps txs to a newer revision of the utxorpc spec than the sdk's.
+/// The messages are wire compatible, so they're moved across through their
+/// protobuf encoding.
+fn map_tx(cbor: &[u8]) -> Result<Tx> {
+    let tx = MultiEraTx::decode(cbor).context("failed to parse transaction")?;
+    let mapped = Mapper::new(NoLedger).map_tx(&tx);
+
+    Ok(Tx::decode(mapped.encode_to_vec().as_slice())?)
+}
+
+/// Client of the Maestro REST API.
+pub struct Client {
+    url: String,
+    headers: HashMap<String, String>,
+    inner: reqwest::Client,
+}
+
+impl Client {
+    pub fn new(url: &str, headers: Option<HashMap<String, String>>) -> Result<Self> {
+        let headers = headers.unwrap_or_default();
+
+        if !headers.keys().any(|x| x.eq_ignore_ascii_case("api-key")) {
+            bail!("maestro providers require an `api-key` header");
+        }
+
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            headers,
+            inner: reqwest::Client::new(),
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let mut request = self.inner.request(method, format!("{}{path}", self.url));
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+
+        request
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<Response<T>>> {
+        let request = self.request(reqwest::Method::GET, path).query(query);
+        let response = request.send().await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let response = Self::check(response).await?;
+        Ok(Some(response.json().await?))
+    }
+
+    async fn check(response: reqwest::Response) -> Result<reqwest::Response> {
+        let status = response.status();
+        if let Err(err) = response.error_for_status_ref() {
+            let body = response.text().await.unwrap_or_default();
+
+            // bad requests, unlike auth or rate limit errors, mean maestro
+            // looked at what we sent
+            if status == reqwest::StatusCode::BAD_REQUEST {
+                return Err(anyhow!(Rejected(body)).context("maestro rejected the request"));
+            }
+
+            // kept as the source so the status can be retried on
+            return Err(anyhow!(err).context(format!("maestro responded with {status}: {body}")));
+        }
+
+        Ok(response)
+    }
+
+    /// UTxOs locked by exactly this address, across every page.
+    pub async fn utxos(&self, address: &Address) -> Result<Vec<AnyUtxoData>> {
+        let path = format!("/addresses/{address}/utxos");
+        let mut utxos = vec![];
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut query = vec![("count", PAGE_SIZE)];
+            if let Some(cursor) = &cursor {
+                query.push(("cursor", cursor));
+            }
+
+            let Some(page) = self.get::<Vec<Utxo>>(&path, &query).await? else {
+                break;
+            };
+
+            for utxo in page.data {
+                utxos.push(AnyUtxoData::try_from(utxo)?);
+            }
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(utxos)
+    }
+
+    /// Slot, hash and height of the chain tip.
+    pub async fn tip(&self) -> Result<Option<Tip>> {
+        match self.get::<ChainTip>("/chain-tip", &[]).await? {
+            Some(tip) => Ok(Some(Tip {
+                slot: tip.data.slot,
+                hash: hex::decode(tip.data.block_hash)?,
+                height: Some(tip.data.height),
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Submits a cbor encoded tx, returning its hash.
+    pub async fn submit(&self, tx: &[u8]) -> Result<Vec<u8>> {
+        let request = self
+            .request(reqwest::Method::POST, "/txmanager")
+            .header("Content-Type", "application/cbor")
+            .body(tx.to_vec());
+
+        let hash = Self::check(request.send().await?).await?.text().await?;
+
+        hex::decode(hash.trim().trim_matches('"')).context("maestro returned an invalid tx hash")
+    }
+
+    async fn tx_cbor(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        match self
+            .get::<String>(&format!("/transactions/{hash}/cbor"), &[])
+            .await?
+        {
+            Some(response) => Ok(Some(hex::decode(response.data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Blocks need a request per tx, which large blocks can get throttled
+    /// on. Throttled requests wait and are tried again on their own, rather
+    /// than failing the block and having the retry policy request every tx
+    /// again.
+    async fn block_tx_cbor(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        let mut wait = THROTTLED_WAIT;
+        let mut attempt = 1;
+
+        loop {
+            match self.tx_cbor(hash).await {
+                Err(err) if attempt < THROTTLED_ATTEMPTS && is_throttled(&err) => {
+                    tokio::time::sleep(wait).await;
+                    wait *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    pub async fn fetch_tx(&self, hash: &[u8]) -> Result<Option<ChainTx<Tx>>> {
+        let hash = hex::encode(hash);
+
+        let Some(cbor) = self.tx_cbor(&hash).await? else {
+            return Ok(None);
+        };
+
+        let block_ref = match self
+            .get::<TxInfo>(&format!("/transactions/{hash}"), &[])
+            .await?
+        {
+            Some(info) => Some(ChainPoint {
+                slot: info.data.block_absolute_slot,
+                hash: hex::decode(info.data.block_hash)?.into(),
+                ..Default::default()
+            }),
+            None => None,
+        };
+
+        Ok(Some(ChainTx {
+            parsed: Some(map_tx(&cbor)?),
+            native: cbor.into(),
+            block_ref,
+        }))
+    }
+
+    /// Maestro has no block cbor, so blocks are put together from the cbor
+    /// of their txs.
+    pub async fn fetch_block(&self, hash: &[u8]) -> Result<Option<ChainBlock<Block>>> {
+        let Some(info) = self
+            .get::<BlockInfo>(&format!("/blocks/{}", hex::encode(hash)), &[])
+            .await?
+        else {
+            return Ok(None);
+        };
+        let info = info.data;
+
+        let mut txs = vec![];
+        for hash in &info.tx_hashes {
+            let cbor = self
+                .block_tx_cbor(hash)
+                .await?
+                .with_context(|| format!("missing cbor of tx {hash}"))?;
+            txs.push(map_tx(&cbor)?);
+        }
+
+        Ok(Some(ChainBlock {
+            parsed: Some(Block {
+                header: Some(BlockHeader {
+                    slot: info.absolute_slot,
+                    hash: hex::decode(&info.hash)?.into(),
+                    height: info.height,
+                }),
+                body: Some(BlockBody { tx: txs }),
+                ..Default::default()
+            }),
+            native: Default::default(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::addresses::{
+        Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn maps_utxos() {
+        let address: Address = ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::key_hash([1; 28].into()),
+            ShelleyDelegationPart::Null,
+        )
+        .into();
+
+        let page: Response<Vec<Utxo>> = serde_json::from_value(json!({
+            "data": [{
+                "tx_hash": "aa".repeat(32),
+                "index": 2,
+                "slot": 1234,
+                "address": address.to_string(),
+                "assets": [
+                    { "unit": "lovelace", "amount": 2_000_000 },
+                    { "unit": format!("{}cafe", "bb".repeat(28)), "amount": 10 },
+                    { "unit": format!("{}beef", "bb".repeat(28)), "amount": 1 }
+                ],
+                "datum": { "type": "inline", "hash": "dd".repeat(32), "bytes": "d87980" },
+                "reference_script": null
+            }],
+            "last_updated": { "timestamp": "", "block_hash": "", "block_slot": 0 },
+            "next_cursor": null
+        }))
+        .unwrap();
+
+        assert_eq!(page.next_cursor, None);
+
+        let utxo = AnyUtxoData::try_from(page.data.into_iter().next().unwrap()).unwrap();
+
+        assert_eq!(utxo.txo_ref.unwrap().index, 2);
+        assert_eq!(utxo.block_ref.unwrap().slot, 1234);
+
+        let Some(ParsedState::Cardano(output)) = utxo.parsed_state else {
+            unreachable!()
+        };
+        assert_eq!(output.address.to_vec(), address.to_vec());
+        assert_eq!(crate::utils::format_bigint_opt(&output.coin), "2000000");
+        assert_eq!(output.assets.len(), 1);
+        assert_eq!(output.assets[0].policy_id.to_vec(), vec![0xbb; 28]);
+        assert_eq!(output.assets[0].assets.len(), 2);
+
+        let datum = output.datum.unwrap();
+        assert_eq!(datum.hash.to_vec(), vec![0xdd; 32]);
+        assert_eq!(datum.original_cbor.to_vec(), vec![0xd8, 0x79, 0x80]);
+    }
+
+    #[test]
+    fn maps_byron_utxos() {
+        let address = "Ae2tdPwUPEZLs4HtbuNey7tK4hTKrwNwYtGqp7bDfCy2WdR3P6735W5Yfpe";
+        let utxo: Utxo = serde_json::from_value(json!({
+            "tx_hash": "aa".repeat(32),
+            "index": 0,
+            "slot": 1234,
+            "address": address,
+            "assets": [{ "unit": "lovelace", "amount": 1_000_000 }],
+            "datum": null
+        }))
+        .unwrap();
+
+        let utxo = AnyUtxoData::try_from(utxo).unwrap();
+        let Some(ParsedState::Cardano(output)) = utxo.parsed_state else {
+            unreachable!()
+        };
+        assert_eq!(
+            Address::from_bytes(&output.address).unwrap().to_string(),
+            address
+        );
+    }
+
+    #[test]
+    fn maps_tx_cbor_to_sdk_types() {
+        let tx = map_tx(&crate::provider::types::probe_tx()).unwrap();
+
+        assert_eq!(tx.inputs.len(), 1);
+        assert_eq!(tx.inputs[0].output_index, 1);
+        assert_eq!(tx.outputs.len(), 1);
+        assert_eq!(
+            crate::utils::format_bigint_opt(&tx.outputs[0].coin),
+            "2000000"
+        );
+        assert_eq!(crate::utils::format_bigint_opt(&tx.fee), "170000");
+    }
+
+    #[test]
+    fn requires_api_key() {
+        assert!(Client::new("https://preprod.gomaestro-api.org/v1", None).is_err());
+
+        let headers = HashMap::from([("api-key".to_string(), "key".to_string())]);
+        let client = Client::new("https://preprod.gomaestro-api.org/v1/", Some(headers)).unwrap();
+        assert_eq!(client.url, "https://preprod.gomaestro-api.org/v1");
+    }
+}
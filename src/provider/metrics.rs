@@ -0,0 +1,164 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use comfy_table::Table;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::output::{OutputFormat, OutputFormatter, TableStyle};
+
+/// Request stats of the current process, keyed by provider and method.
+static METRICS: Mutex<BTreeMap<(String, String), CallStats>> = Mutex::new(BTreeMap::new());
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct CallStats {
+    calls: u64,
+    errors: u64,
+    total: Duration,
+    max: Duration,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct MethodMetrics {
+    pub provider: String,
+    pub method: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub avg_latency_ms: u128,
+    pub max_latency_ms: u128,
+}
+
+pub type Metrics = Vec<MethodMetrics>;
+
+/// Awaits a provider request, recording its latency and whether it failed.
+pub async fn track<T, E>(
+    provider: &str,
+    method: &str,
+    request: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let result = request.await;
+
+    record(provider, method, start.elapsed(), result.is_err());
+
+    result
+}
+
+fn record(provider: &str, method: &str, latency: Duration, failed: bool) {
+    let mut metrics = METRICS.lock().unwrap();
+
+    let stats = metrics
+        .entry((provider.to_string(), method.to_string()))
+        .or_default();
+
+    stats.calls += 1;
+    stats.errors += failed as u64;
+    stats.total += latency;
+    stats.max = stats.max.max(latency);
+}
+
+pub fn snapshot() -> Metrics {
+    METRICS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|((provider, method), stats)| MethodMetrics {
+            provider: provider.clone(),
+            method: method.clone(),
+            calls: stats.calls,
+            errors: stats.errors,
+            avg_latency_ms: stats.total.as_millis() / u128::from(stats.calls.max(1)),
+            max_latency_ms: stats.max.as_millis(),
+        })
+        .collect()
+}
+
+/// Prints the stats of this run to stderr, so they don't mix with the
+/// output of the command.
+pub fn report(format: &OutputFormat) -> anyhow::Result<()> {
+    let metrics = snapshot();
+
+    match format {
+        OutputFormat::Json => {
+            eprintln!("{}", crate::output::render_json(&metrics.to_json()?, None)?)
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            eprintln!("{}", table(&metrics, format.table_style()))
+        }
+    }
+
+    Ok(())
+}
+
+fn table(metrics: &Metrics, style: TableStyle) -> Table {
+    let mut table = crate::output::table(style);
+
+    table.set_header(vec![
+        "Provider", "Method", "Calls", "Errors", "Avg (ms)", "Max (ms)",
+    ]);
+
+    for entry in metrics {
+        table.add_row(vec![
+            entry.provider.clone(),
+            entry.method.clone(),
+            entry.calls.to_string(),
+            entry.errors.to_string(),
+            entry.avg_latency_ms.to_string(),
+            entry.max_latency_ms.to_string(),
+        ]);
+    }
+
+    table
+}
+
+impl OutputFormatter for Metrics {
+    fn to_table(&self, style: TableStyle) {
+        println!("{}", table(self, style));
+    }
+
+    fn to_json(&self) -> anyhow::Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tracks_calls_per_method() {
+        let ok = track("metrics-test", "read_tip", async { Ok::<_, ()>(()) }).await;
+        let err = track("metrics-test", "read_tip", async { Err::<(), _>(()) }).await;
+        assert!(ok.is_ok() && err.is_err());
+
+        record("metrics-test", "submit", Duration::from_millis(30), false);
+        record("metrics-test", "submit", Duration::from_millis(10), false);
+
+        let metrics: Metrics = snapshot()
+            .into_iter()
+            .filter(|entry| entry.provider == "metrics-test")
+            .collect();
+
+        assert_eq!(metrics.len(), 2);
+
+        assert_eq!(metrics[0].method, "read_tip");
+        assert_eq!(metrics[0].calls, 2);
+        assert_eq!(metrics[0].errors, 1);
+
+        assert_eq!(
+            metrics[1],
+            MethodMetrics {
+                provider: "metrics-test".into(),
+                method: "submit".into(),
+                calls: 2,
+                errors: 0,
+                avg_latency_ms: 20,
+                max_latency_ms: 30,
+            }
+        );
+    }
+}
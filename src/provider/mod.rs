@@ -1,13 +1,19 @@
 use clap::{Parser, Subcommand};
 use tracing::instrument;
 
+mod cache;
 mod create;
 mod delete;
 mod edit;
 mod info;
 mod list;
+mod maestro;
+pub mod metrics;
+mod node;
+mod ogmios;
 mod test;
 pub mod types;
+mod use_provider;
 
 #[derive(Parser)]
 pub struct Args {
@@ -27,8 +33,10 @@ enum Commands {
     List,
     /// Delete a wallet. Caution!! This cannot be undone.
     Delete(delete::Args),
-    /// Try connection.
+    /// Probe every capability of a provider and report which ones work.
     Test(test::Args),
+    /// Set the provider commands use when none is named
+    Use(use_provider::Args),
 }
 
 #[instrument("wallet", skip_all)]
@@ -40,5 +48,6 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
         Commands::List => list::run(ctx).await,
         Commands::Delete(args) => delete::run(args, ctx).await,
         Commands::Test(args) => test::run(args, ctx).await,
+        Commands::Use(args) => use_provider::run(args, ctx).await,
     }
 }
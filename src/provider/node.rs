@@ -0,0 +1,282 @@
+use anyhow::{anyhow, Context, Result};
+use pallas::{
+    codec::utils::{AnyCbor, KeyValuePairs},
+    crypto::hash::Hasher,
+    ledger::{
+        addresses::Address,
+        primitives::conway::DatumOption,
+        traverse::{Era, MultiEraBlock, MultiEraOutput, MultiEraTx},
+    },
+    network::{
+        facades::NodeClient,
+        miniprotocols::{
+            chainsync::NextResponse,
+            localstate::queries_v16::{self, BlockQuery, LedgerQuery, Request, UTxO},
+            localtxsubmission::{EraTx, Response},
+            Point,
+        },
+    },
+};
+use utxorpc::spec::{
+    cardano::{asset, Asset, Datum, Multiasset, TxOutput},
+    query::{any_utxo_data::ParsedState, AnyUtxoData, TxoRef},
+};
+
+use super::types::{ChainEvent, Rejected, Tip};
+use crate::utils::to_bigint;
+
+/// Maps an output as the ledger encodes it. Inline datums are hashed from
+/// their original bytes, re-encoding them could change the hash.
+fn map_output(cbor: &[u8]) -> Result<TxOutput> {
+    let output = MultiEraOutput::decode(Era::Conway, cbor).context("invalid utxo output")?;
+
+    let assets = output
+        .value()
+        .assets()
+        .iter()
+        .map(|policy| Multiasset {
+            policy_id: policy.policy().to_vec().into(),
+            assets: policy
+                .assets()
+                .iter()
+                .map(|asset| Asset {
+                    name: asset.name().to_vec().into(),
+                    quantity: asset
+                        .output_coin()
+                        .map(|quantity| asset::Quantity::OutputCoin(to_bigint(quantity))),
+                })
+                .collect(),
+            ..Default::default()
+        })
+        .collect();
+
+    let datum = match output.datum() {
+        Some(DatumOption::Hash(hash)) => Some(Datum {
+            hash: hash.to_vec().into(),
+            ..Default::default()
+        }),
+        Some(DatumOption::Data(data)) => {
+            let cbor = data.0.raw_cbor();
+            Some(Datum {
+                hash: Hasher::<256>::hash(cbor).to_vec().into(),
+                original_cbor: cbor.to_vec().into(),
+                ..Default::default()
+            })
+        }
+        None => None,
+    };
+
+    Ok(TxOutput {
+        address: output.address()?.to_vec().into(),
+        coin: Some(to_bigint(output.value().coin())),
+        assets,
+        datum,
+        ..Default::default()
+    })
+}
+
+/// Client of a local cardano-node, speaking the node-to-client mini
+/// protocols over its unix socket.
+pub struct Client {
+    inner: NodeClient,
+}
+
+impl Client {
+    pub async fn connect(socket: &str, magic: u64) -> Result<Self> {
+        #[cfg(unix)]
+        let inner = NodeClient::connect(socket, magic)
+            .await
+            .with_context(|| format!("failed to connect to node socket at {socket}"))?;
+
+        #[cfg(not(unix))]
+        anyhow::bail!(
+            "node providers require a unix socket, can't connect to {socket} (magic {magic})"
+        );
+
+        #[cfg(unix)]
+        Ok(Self { inner })
+    }
+
+    /// Hard fork era the node's ledger is at, as ledger queries and tx
+    /// submission expect it.
+    async fn era(&mut self) -> Result<u16> {
+        let client = self.inner.statequery();
+
+        client.acquire(None).await?;
+        let era = queries_v16::get_current_era(client).await?;
+        client.send_release().await?;
+
+        Ok(era)
+    }
+
+    /// UTxOs locked by exactly this address.
+    pub async fn utxos(&mut self, address: &Address) -> Result<Vec<AnyUtxoData>> {
+        let client = self.inner.statequery();
+
+        client.acquire(None).await?;
+        let era = queries_v16::get_current_era(client).await?;
+        // outputs are kept as the node sent them, see `map_output`
+        let query = Request::LedgerQuery(LedgerQuery::BlockQuery(
+            era,
+            BlockQuery::GetUTxOByAddress(vec![address.to_vec().into()]),
+        ));
+        let (utxos,): (KeyValuePairs<UTxO, AnyCbor>,) = client.query(query).await?;
+        client.send_release().await?;
+
+        utxos
+            .iter()
+            .map(|(utxo, output)| {
+                Ok(AnyUtxoData {
+                    txo_ref: Some(TxoRef {
+                        hash: utxo.transaction_id.to_vec().into(),
+                        index: u64::from(utxo.index) as u32,
+                    }),
+                    parsed_state: Some(ParsedState::Cardano(map_output(output)?)),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// Submits a cbor encoded tx, returning its hash.
+    pub async fn submit(&mut self, tx: &[u8]) -> Result<Vec<u8>> {
+        let hash = MultiEraTx::decode(tx)
+            .context("failed to parse transaction")?
+            .hash();
+
+        let era = self.era().await?;
+
+        match self
+            .inner
+            .submission()
+            .submit_tx(EraTx(era, tx.to_vec()))
+            .await?
+        {
+            Response::Accepted => Ok(hash.to_vec()),
+            Response::Rejected(reason) => {
+                Err(anyhow!(Rejected(format!("{reason:?}")))
+                    .context("node rejected the transaction"))
+            }
+        }
+    }
+
+    /// Node tip along with its block height, or `None` at the origin.
+    pub async fn tip(&mut self) -> Result<Option<Tip>> {
+        let client = self.inner.statequery();
+
+        client.acquire(None).await?;
+        let point = queries_v16::get_chain_point(client).await?;
+        let block_no = queries_v16::get_chain_block_no(client).await?;
+        client.send_release().await?;
+
+        match point {
+            Point::Origin => Ok(None),
+            Point::Specific(slot, hash) => Ok(Some(Tip {
+                slot,
+                hash,
+                height: Some(u64::from(block_no.block_number)),
+            })),
+        }
+    }
+
+    /// Moves the chain sync cursor to the current tip, so that `next_block`
+    /// only reports blocks from now on.
+    pub async fn intersect_tip(&mut self) -> Result<()> {
+        self.inner.chainsync().intersect_tip().await?;
+        Ok(())
+    }
+
+    /// Waits for the next chain sync step.
+    pub async fn next_block(&mut self) -> Result<ChainEvent> {
+        loop {
+            match self.inner.chainsync().request_or_await_next().await? {
+                NextResponse::RollForward(content, _) => {
+                    let block = MultiEraBlock::decode(&content.0)?;
+
+                    return Ok(ChainEvent::Forward {
+                        slot: block.slot(),
+                        hash: block.hash().to_vec(),
+                        height: block.number(),
+                        tx_count: block.tx_count(),
+                    });
+                }
+                NextResponse::RollBackward(point, _) => {
+                    return Ok(ChainEvent::Backward {
+                        slot: point.slot_or_default(),
+                    });
+                }
+                NextResponse::Await => continue,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::{
+        codec::{
+            minicbor,
+            utils::{AnyUInt, NonEmptyKeyValuePairs},
+        },
+        network::miniprotocols::localstate::queries_v16::{
+            LegacyTransactionOutput, PostAlonsoTransactionOutput, TransactionOutput, Value,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn maps_outputs() {
+        let cbor = minicbor::to_vec(TransactionOutput::Current(PostAlonsoTransactionOutput {
+            address: vec![0x60; 29].into(),
+            amount: Value::Multiasset(
+                AnyUInt::U32(1_500_000),
+                NonEmptyKeyValuePairs::Def(vec![(
+                    [0xbb; 28].into(),
+                    NonEmptyKeyValuePairs::Def(vec![(b"cafe".to_vec().into(), AnyUInt::U8(10))]),
+                )]),
+            ),
+            inline_datum: None,
+            script_ref: None,
+        }))
+        .unwrap();
+        let output = map_output(&cbor).unwrap();
+
+        assert_eq!(output.address.to_vec(), vec![0x60; 29]);
+        assert_eq!(crate::utils::format_bigint_opt(&output.coin), "1500000");
+        assert_eq!(output.assets[0].policy_id.to_vec(), vec![0xbb; 28]);
+        assert_eq!(output.assets[0].assets[0].name.to_vec(), b"cafe".to_vec());
+        assert_eq!(output.datum, None);
+
+        let cbor = minicbor::to_vec(TransactionOutput::Legacy(LegacyTransactionOutput {
+            address: vec![0x60; 29].into(),
+            amount: Value::Coin(AnyUInt::U32(2_000_000)),
+            datum_hash: Some([0xdd; 32].into()),
+        }))
+        .unwrap();
+        let output = map_output(&cbor).unwrap();
+
+        assert_eq!(crate::utils::format_bigint_opt(&output.coin), "2000000");
+        assert!(output.assets.is_empty());
+        assert_eq!(output.datum.unwrap().hash.to_vec(), vec![0xdd; 32]);
+    }
+
+    #[test]
+    fn hashes_inline_datums_as_encoded() {
+        // {0: address, 1: 2000000, 2: [1, 24(<< 121([_ 1]) >>)]}, the datum
+        // in an indefinite array that re-encoding would make definite
+        let datum = hex::decode("d8799f01ff").unwrap();
+        let cbor = [
+            hex::decode("a300581d").unwrap(),
+            vec![0x60; 29],
+            hex::decode("011a001e8480028201d81845").unwrap(),
+            datum.clone(),
+        ]
+        .concat();
+
+        let output = map_output(&cbor).unwrap().datum.unwrap();
+
+        assert_eq!(output.original_cbor.to_vec(), datum);
+        assert_eq!(output.hash.to_vec(), Hasher::<256>::hash(&datum).to_vec());
+    }
+}
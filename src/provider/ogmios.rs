@@ -0,0 +1,363 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context, Result};
+use jsonrpsee::{
+    core::{client::ClientT, params::ObjectParams, ClientError},
+    ws_client::{WsClient, WsClientBuilder},
+};
+use pallas::{
+    crypto::hash::Hasher,
+    ledger::addresses::{Address, ByronAddress},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use utxorpc::spec::{
+    cardano::{asset, Asset, Datum, Multiasset, TxOutput},
+    query::{any_utxo_data::ParsedState, AnyUtxoData, TxoRef},
+};
+
+use super::types::{ChainEvent, Rejected, Tip};
+use crate::utils::to_bigint;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Point {
+    Origin(#[allow(dead_code)] String),
+    At { slot: u64, id: String },
+}
+
+impl Point {
+    fn slot(&self) -> u64 {
+        match self {
+            Point::Origin(_) => 0,
+            Point::At { slot, .. } => *slot,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Block {
+    id: String,
+    // epoch boundary blocks have no slot nor height
+    #[serde(default)]
+    slot: u64,
+    #[serde(default)]
+    height: u64,
+    #[serde(default)]
+    transactions: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "direction", rename_all = "lowercase")]
+enum NextBlock {
+    Forward { block: Block },
+    Backward { point: Point },
+}
+
+#[derive(Deserialize)]
+struct TransactionId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Utxo {
+    transaction: TransactionId,
+    index: u32,
+    address: String,
+    /// Quantities by policy id and asset name, lovelace being `ada.lovelace`.
+    value: BTreeMap<String, BTreeMap<String, u64>>,
+    datum_hash: Option<String>,
+    /// Hex encoded cbor of an inline datum.
+    datum: Option<String>,
+}
+
+impl TryFrom<Utxo> for AnyUtxoData {
+    type Error = anyhow::Error;
+
+    fn try_from(utxo: Utxo) -> Result<Self> {
+        let address = Address::from_bech32(&utxo.address)
+            .or_else(|_| ByronAddress::from_base58(&utxo.address).map(Address::Byron))
+            .with_context(|| format!("invalid address '{}'", utxo.address))?;
+
+        let mut coin = 0;
+        let mut assets = vec![];
+
+        for (policy, quantities) in utxo.value {
+            if policy == "ada" {
+                coin = quantities.get("lovelace").copied().unwrap_or_default();
+                continue;
+            }
+
+            assets.push(Multiasset {
+                policy_id: hex::decode(&policy)?.into(),
+                assets: quantities
+                    .into_iter()
+                    .map(|(name, quantity)| {
+                        Ok(Asset {
+                            name: hex::decode(&name)?.into(),
+                            quantity: Some(asset::Quantity::OutputCoin(to_bigint(quantity))),
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                ..Default::default()
+            });
+        }
+
+        let datum = match (utxo.datum_hash, utxo.datum) {
+            (Some(hash), _) => Some(Datum {
+                hash: hex::decode(hash)?.into(),
+                ..Default::default()
+            }),
+            (None, Some(cbor)) => {
+                let cbor = hex::decode(cbor)?;
+                Some(Datum {
+                    hash: Hasher::<256>::hash(&cbor).to_vec().into(),
+                    original_cbor: cbor.into(),
+                    ..Default::default()
+                })
+            }
+            (None, None) => None,
+        };
+
+        Ok(AnyUtxoData {
+            txo_ref: Some(TxoRef {
+                hash: hex::decode(&utxo.transaction.id)?.into(),
+                index: utxo.index,
+            }),
+            parsed_state: Some(ParsedState::Cardano(TxOutput {
+                address: address.to_vec().into(),
+                coin: Some(to_bigint(coin)),
+                assets,
+                datum,
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+/// Client of an Ogmios (v6) server, speaking JSON-RPC over a websocket.
+pub struct Client {
+    inner: WsClient,
+}
+
+impl Client {
+    pub async fn connect(url: &str, headers: Option<&HashMap<String, String>>) -> Result<Self> {
+        let mut header_map = http::HeaderMap::new();
+        for (key, value) in headers.into_iter().flatten() {
+            header_map.insert(
+                http::HeaderName::from_bytes(key.as_bytes())?,
+                http::HeaderValue::from_str(value)?,
+            );
+        }
+
+        let inner = WsClientBuilder::default()
+            .set_headers(header_map)
+            .max_response_size(u32::MAX)
+            .build(url)
+            .await
+            .with_context(|| format!("failed to connect to ogmios at {url}"))?;
+
+        Ok(Self { inner })
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let mut object = ObjectParams::new();
+        if let Value::Object(params) = params {
+            for (key, value) in params {
+                object.insert(&key, value)?;
+            }
+        }
+
+        match self.inner.request(method, object).await {
+            Ok(response) => Ok(response),
+            Err(ClientError::Call(err)) => Err(anyhow!(Rejected(err.message().to_string()))
+                .context(format!("ogmios {method} request failed"))),
+            Err(err) => Err(anyhow!(err).context(format!("ogmios {method} request failed"))),
+        }
+    }
+
+    /// UTxOs locked by exactly this address.
+    pub async fn utxos(&self, address: &Address) -> Result<Vec<AnyUtxoData>> {
+        let utxos: Vec<Utxo> = self
+            .request(
+                "queryLedgerState/utxo",
+                json!({ "addresses": [address.to_string()] }),
+            )
+            .await?;
+
+        utxos.into_iter().map(AnyUtxoData::try_from).collect()
+    }
+
+    /// Submits a cbor encoded tx, returning its hash.
+    pub async fn submit(&self, tx: &[u8]) -> Result<Vec<u8>> {
+        let result: Value = self
+            .request(
+                "submitTransaction",
+                json!({ "transaction": { "cbor": hex::encode(tx) } }),
+            )
+            .await?;
+
+        let id = result["transaction"]["id"]
+            .as_str()
+            .context("ogmios didn't return the tx id")?;
+
+        Ok(hex::decode(id)?)
+    }
+
+    /// Slot and hash of the node tip, or `None` at the origin.
+    async fn tip_point(&self) -> Result<Option<(u64, Vec<u8>)>> {
+        let tip: Point = self.request("queryNetwork/tip", json!({})).await?;
+
+        match tip {
+            Point::Origin(_) => Ok(None),
+            Point::At { slot, id } => Ok(Some((slot, hex::decode(id)?))),
+        }
+    }
+
+    /// Node tip along with its block height, or `None` at the origin.
+    pub async fn tip(&self) -> Result<Option<Tip>> {
+        let Some((slot, hash)) = self.tip_point().await? else {
+            return Ok(None);
+        };
+
+        let height: Value = self.request("queryNetwork/blockHeight", json!({})).await?;
+
+        Ok(Some(Tip {
+            slot,
+            hash,
+            height: height.as_u64(),
+        }))
+    }
+
+    /// Moves the chain sync cursor to the current tip, so that `next_block`
+    /// only reports blocks from now on.
+    pub async fn intersect_tip(&self) -> Result<()> {
+        let point = match self.tip_point().await? {
+            Some((slot, hash)) => json!({ "slot": slot, "id": hex::encode(hash) }),
+            None => json!("origin"),
+        };
+
+        let _: Value = self
+            .request("findIntersection", json!({ "points": [point] }))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Waits for the next chain sync step.
+    pub async fn next_block(&self) -> Result<ChainEvent> {
+        let next: NextBlock = self.request("nextBlock", json!({})).await?;
+        next.try_into()
+    }
+}
+
+impl TryFrom<NextBlock> for ChainEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(next: NextBlock) -> Result<Self> {
+        match next {
+            NextBlock::Forward { block } => Ok(ChainEvent::Forward {
+                slot: block.slot,
+                hash: hex::decode(block.id)?,
+                height: block.height,
+                tx_count: block.transactions.len(),
+            }),
+            NextBlock::Backward { point } => Ok(ChainEvent::Backward { slot: point.slot() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::addresses::{
+        Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+    };
+
+    use super::*;
+
+    #[test]
+    fn maps_utxos() {
+        let address: Address = ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::key_hash([1; 28].into()),
+            ShelleyDelegationPart::Null,
+        )
+        .into();
+
+        let utxos: Vec<Utxo> = serde_json::from_value(json!([
+            {
+                "transaction": { "id": "aa".repeat(32) },
+                "index": 1,
+                "address": address.to_string(),
+                "value": {
+                    "ada": { "lovelace": 1_500_000 },
+                    "bb".repeat(28): { "cafe": 10, "": 1 }
+                },
+                "datum": "d87980"
+            }
+        ]))
+        .unwrap();
+
+        let utxo = AnyUtxoData::try_from(utxos.into_iter().next().unwrap()).unwrap();
+
+        let txo_ref = utxo.txo_ref.unwrap();
+        assert_eq!(txo_ref.hash.to_vec(), vec![0xaa; 32]);
+        assert_eq!(txo_ref.index, 1);
+
+        let Some(ParsedState::Cardano(output)) = utxo.parsed_state else {
+            unreachable!()
+        };
+        assert_eq!(output.address.to_vec(), address.to_vec());
+        assert_eq!(crate::utils::format_bigint_opt(&output.coin), "1500000");
+        assert_eq!(output.assets.len(), 1);
+        assert_eq!(output.assets[0].policy_id.to_vec(), vec![0xbb; 28]);
+        assert_eq!(output.assets[0].assets.len(), 2);
+
+        let datum = output.datum.unwrap();
+        assert_eq!(datum.original_cbor.to_vec(), vec![0xd8, 0x79, 0x80]);
+        assert_eq!(
+            datum.hash.to_vec(),
+            Hasher::<256>::hash(&[0xd8, 0x79, 0x80]).to_vec()
+        );
+    }
+
+    #[test]
+    fn maps_chain_sync_steps() {
+        let forward: NextBlock = serde_json::from_value(json!({
+            "direction": "forward",
+            "tip": { "slot": 12, "id": "cc".repeat(32), "height": 3 },
+            "block": {
+                "type": "praos",
+                "id": "cc".repeat(32),
+                "slot": 12,
+                "height": 3,
+                "transactions": [{}, {}]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            ChainEvent::try_from(forward).unwrap(),
+            ChainEvent::Forward {
+                slot: 12,
+                hash: vec![0xcc; 32],
+                height: 3,
+                tx_count: 2,
+            }
+        );
+
+        let backward: NextBlock = serde_json::from_value(json!({
+            "direction": "backward",
+            "tip": "origin",
+            "point": "origin"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            ChainEvent::try_from(backward).unwrap(),
+            ChainEvent::Backward { slot: 0 }
+        );
+    }
+}
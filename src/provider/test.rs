@@ -1,5 +1,16 @@
-use anyhow::bail;
+use std::{future::Future, time::Instant};
+
+use anyhow::{bail, Result};
 use clap::Parser;
+use pallas::ledger::addresses::{
+    Address, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::output::{OutputFormatter, TableStyle};
+
+use super::types::{AddressMatch, Provider};
 
 #[derive(Parser)]
 pub struct Args {
@@ -7,6 +18,187 @@ pub struct Args {
     /// default.
     #[arg(long)]
     name: Option<String>,
+
+    /// Only check the TRP endpoint, resolving an empty tx to validate its
+    /// url and credentials.
+    #[arg(long)]
+    trp: bool,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Failed,
+    Skipped,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Failed => write!(f, "failed"),
+            Status::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Check {
+    capability: &'static str,
+    status: Status,
+    latency_ms: Option<u128>,
+    detail: String,
+}
+
+impl Check {
+    async fn run<T, Fut>(capability: &'static str, probe: Fut) -> (Self, Option<T>)
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let start = Instant::now();
+        let result = probe.await;
+        let latency_ms = Some(start.elapsed().as_millis());
+
+        match result {
+            Ok(value) => (
+                Self {
+                    capability,
+                    status: Status::Ok,
+                    latency_ms,
+                    detail: String::new(),
+                },
+                Some(value),
+            ),
+            Err(err) => (
+                Self {
+                    capability,
+                    status: Status::Failed,
+                    latency_ms,
+                    detail: format!("{err:#}"),
+                },
+                None,
+            ),
+        }
+    }
+
+    fn skipped(capability: &'static str, detail: &str) -> Self {
+        Self {
+            capability,
+            status: Status::Skipped,
+            latency_ms: None,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+/// Outcome of probing each capability of a provider, so a misconfigured
+/// endpoint (e.g. a wrong TRP url) is told apart from the others.
+struct HealthReport {
+    provider: String,
+    checks: Vec<Check>,
+}
+
+impl HealthReport {
+    fn failed(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|x| x.status == Status::Failed)
+            .count()
+    }
+}
+
+impl OutputFormatter for HealthReport {
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
+
+        table.set_header(vec!["Capability", "Status", "Latency", "Detail"]);
+        for check in &self.checks {
+            table.add_row(vec![
+                check.capability.to_string(),
+                check.status.to_string(),
+                check
+                    .latency_ms
+                    .map_or("-".to_string(), |x| format!("{x} ms")),
+                check.detail.clone(),
+            ]);
+        }
+
+        println!("{table}");
+    }
+
+    fn to_json(&self) -> Result<Value> {
+        Ok(json!({
+            "provider": self.provider,
+            "healthy": self.failed() == 0,
+            "checks": self.checks,
+        }))
+    }
+}
+
+/// An address no one can spend from, to query UTxOs without leaking which
+/// wallets the user has.
+fn probe_address(is_testnet: bool) -> Address {
+    let network = match is_testnet {
+        true => Network::Testnet,
+        false => Network::Mainnet,
+    };
+
+    ShelleyAddress::new(
+        network,
+        ShelleyPaymentPart::key_hash([0; 28].into()),
+        ShelleyDelegationPart::Null,
+    )
+    .into()
+}
+
+/// Probe of the TRP endpoint alone.
+async fn trp_report(provider: &Provider) -> Result<HealthReport> {
+    if provider.trp_url.is_none() {
+        bail!("provider '{}' has no TRP url configured", provider.name);
+    }
+
+    Ok(HealthReport {
+        provider: provider.name(),
+        checks: vec![Check::run("trp resolve", provider.probe_trp()).await.0],
+    })
+}
+
+async fn health_report(provider: &Provider) -> HealthReport {
+    let mut checks = vec![];
+
+    let (mut check, tip) = Check::run("sync tip", provider.read_tip()).await;
+    if let Some(Some(tip)) = tip {
+        check.detail = format!("slot {}, hash {}", tip.slot, hex::encode(tip.hash));
+    }
+    checks.push(check);
+
+    let address = probe_address(provider.is_testnet());
+    let (mut check, utxos) = Check::run(
+        "query utxos",
+        provider.fetch_utxos(&address, AddressMatch::Full),
+    )
+    .await;
+    if let Some(utxos) = utxos {
+        check.detail = format!("{} utxos at the probe address", utxos.len());
+    }
+    checks.push(check);
+
+    let (mut check, _) = Check::run("submit invalid tx", provider.submit_invalid_tx()).await;
+    if check.status == Status::Ok {
+        check.detail = "invalid tx rejected as expected".to_string();
+    }
+    checks.push(check);
+
+    match provider.trp_url {
+        Some(_) => checks.push(Check::run("trp resolve", provider.probe_trp()).await.0),
+        None => checks.push(Check::skipped("trp resolve", "no TRP url configured")),
+    }
+
+    HealthReport {
+        provider: provider.name(),
+        checks,
+    }
 }
 
 pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
@@ -15,8 +207,76 @@ pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
         None => ctx.store.default_provider(),
     };
 
-    match provider {
-        Some(provider) => provider.test().await,
-        None => bail!("Provider not found, and no default provider configured."),
+    let Some(provider) = provider else {
+        bail!("Provider not found, and no default provider configured.")
+    };
+
+    // probe the endpoint itself, not whichever fallback answers
+    let provider = Provider {
+        failover: vec![],
+        ..provider.clone()
+    };
+
+    let report = match args.trp {
+        true => trp_report(&provider).await?,
+        false => health_report(&provider).await,
+    };
+    report.output(&ctx.output_format, ctx.output_select())?;
+
+    match report.failed() {
+        0 => Ok(()),
+        failed => bail!("{failed} of {} checks failed", report.checks.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn unreachable() -> Provider {
+        Provider {
+            url: "http://127.0.0.1:1".into(),
+            ..Provider::test("down")
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_unreachable_provider() {
+        let provider = unreachable();
+
+        let report = health_report(&provider).await;
+
+        let statuses: Vec<_> = report.checks.iter().map(|x| x.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Status::Failed,
+                Status::Failed,
+                Status::Failed,
+                Status::Skipped
+            ]
+        );
+        assert_eq!(report.failed(), 3);
+
+        let json = report.to_json().unwrap();
+        assert_eq!(json["healthy"], false);
+        assert_eq!(json["checks"][3]["status"], "skipped");
+        assert_eq!(json["checks"][3]["latency_ms"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn checks_trp_alone() {
+        assert!(trp_report(&unreachable()).await.is_err());
+
+        let provider = Provider {
+            trp_url: Some("http://127.0.0.1:1".into()),
+            ..unreachable()
+        };
+
+        let report = trp_report(&provider).await.unwrap();
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(report.checks[0].capability, "trp resolve");
+        assert_eq!(report.checks[0].status, Status::Failed);
     }
 }
@@ -1,32 +1,590 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, future::Future, time::Duration};
 
 use anyhow::{anyhow, bail, Context, Result};
-use comfy_table::Table;
+use backoff::{backoff::Backoff, ExponentialBackoff, ExponentialBackoffBuilder};
+use clap::ValueEnum;
 use pallas::ledger::addresses::Address;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use utxorpc::{
-    spec::{query::any_utxo_pattern::UtxoPattern, sync::BlockRef},
-    CardanoQueryClient, CardanoSubmitClient, CardanoSyncClient, ChainBlock, ChainTx, ClientBuilder,
-    InnerService,
+    spec::{
+        cardano::TxOutput,
+        query::{any_utxo_data::ParsedState, any_utxo_pattern::UtxoPattern, AnyUtxoData},
+        sync::BlockRef,
+    },
+    Cardano, CardanoQueryClient, CardanoSubmitClient, CardanoSyncClient, ChainBlock, ChainTx,
+    ClientBuilder, InnerService, LiveTip,
 };
 
+use super::{cache, maestro, metrics::track, node, ogmios};
 use crate::{
-    output::OutputFormatter,
-    types::{Asset, Balance, BalanceAsset, Datum, DetailedBalance, UTxO},
-    utils::Name,
+    output::{OutputFormatter, TableStyle},
+    types::{aggregate_assets, Asset, Balance, BalanceAsset, Datum, DetailedBalance, UTxO},
+    utils::{expand_env_vars, Name},
 };
 
+/// How output addresses are matched against a wallet address when querying
+/// UTxOs.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AddressMatch {
+    /// Only outputs locked by the exact same address
+    #[default]
+    Full,
+    /// Any output locked by an address sharing the payment part, regardless
+    /// of its delegation part
+    Payment,
+}
+
+/// Protocol spoken by the provider at its url.
+#[derive(ValueEnum, Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// UTxO RPC over gRPC
+    #[default]
+    Utxorpc,
+    /// Ogmios JSON-RPC over a websocket. Blocks, txs and protocol parameters
+    /// can't be fetched through it.
+    Ogmios,
+    /// Node-to-client mini protocols of a local cardano-node, the url being
+    /// the path to its socket. Same limitations as Ogmios.
+    Node,
+    /// Maestro REST API, authenticated with an `api-key` header. Can't
+    /// follow the chain nor fetch protocol parameters.
+    Maestro,
+}
+
+impl std::fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProviderKind::Utxorpc => write!(f, "utxorpc"),
+            ProviderKind::Ogmios => write!(f, "ogmios"),
+            ProviderKind::Node => write!(f, "node"),
+            ProviderKind::Maestro => write!(f, "maestro"),
+        }
+    }
+}
+
+/// Public network a provider serves, to pick the default provider of.
+#[derive(ValueEnum, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkName {
+    Mainnet,
+    Preprod,
+    Preview,
+}
+
+impl std::fmt::Display for NetworkName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkName::Mainnet => write!(f, "mainnet"),
+            NetworkName::Preprod => write!(f, "preprod"),
+            NetworkName::Preview => write!(f, "preview"),
+        }
+    }
+}
+
+/// Chain sync step reported by providers that aren't UTxO RPC ones.
+#[derive(Debug, PartialEq)]
+pub enum ChainEvent {
+    Forward {
+        slot: u64,
+        hash: Vec<u8>,
+        height: u64,
+        tx_count: usize,
+    },
+    /// Rollback to the given slot, 0 being the origin.
+    Backward { slot: u64 },
+}
+
+/// Chain tip as reported by a provider.
+#[derive(Debug, PartialEq)]
+pub struct Tip {
+    pub slot: u64,
+    pub hash: Vec<u8>,
+    /// Block number of the tip, when the provider reports it.
+    pub height: Option<u64>,
+}
+
+/// Chain sync session with a provider that isn't a UTxO RPC one, starting
+/// at the tip.
+pub enum ChainFollower {
+    Ogmios(ogmios::Client),
+    Node(Box<node::Client>),
+}
+
+impl ChainFollower {
+    pub async fn next_block(&mut self) -> Result<ChainEvent> {
+        match self {
+            ChainFollower::Ogmios(client) => client.next_block().await,
+            ChainFollower::Node(client) => client.next_block().await,
+        }
+    }
+}
+
+const MAINNET_MAGIC: u64 = 764824073;
+
+/// The endpoint answered, but refused what was asked, e.g. a tx that doesn't
+/// validate. Tells these apart from failing to reach the endpoint at all.
+#[derive(Debug)]
+pub struct Rejected(pub String);
+
+impl std::fmt::Display for Rejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+/// gRPC codes (invalid argument, already exists, failed precondition,
+/// aborted, out of range) a UTxO RPC server answers invalid txs with.
+const REJECTION_CODES: [i32; 5] = [3, 6, 9, 10, 11];
+
+/// gRPC codes (deadline exceeded, unavailable) of a server that couldn't be
+/// reached or didn't answer in time.
+const TRANSPORT_CODES: [i32; 2] = [4, 14];
+
+/// Whether the request failed before reaching an endpoint able to answer it,
+/// as opposed to the endpoint answering with an error.
+pub fn is_transport_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if cause.is::<std::io::Error>() {
+            return true;
+        }
+
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            return err.is_connect() || err.is_timeout();
+        }
+
+        match cause.downcast_ref::<utxorpc::Error>() {
+            Some(utxorpc::Error::TransportError(_)) => return true,
+            Some(utxorpc::Error::GrpcError(status)) => {
+                return TRANSPORT_CODES.contains(&i32::from(status.code()))
+            }
+            _ => (),
+        }
+
+        matches!(
+            cause.downcast_ref::<jsonrpsee::core::ClientError>(),
+            Some(
+                jsonrpsee::core::ClientError::Transport(_)
+                    | jsonrpsee::core::ClientError::RestartNeeded(_)
+                    | jsonrpsee::core::ClientError::RequestTimeout
+            )
+        ) || matches!(
+            cause.downcast_ref::<tx3_sdk::trp::Error>(),
+            Some(tx3_sdk::trp::Error::NetworkError(_))
+        )
+    })
+}
+
+/// JSON-RPC codes reserved by the spec, including the server error range.
+/// TRP servers answer with codes outside of it when refusing a tx.
+const RESERVED_RPC_CODES: std::ops::RangeInclusive<i32> = -32768..=-32000;
+
+/// Whether the endpoint refused the tx itself, e.g. because the ledger
+/// doesn't accept it, which any other endpoint would refuse too.
+pub fn is_tx_rejection(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if cause.is::<Rejected>() {
+            return true;
+        }
+
+        match cause.downcast_ref::<tx3_sdk::trp::Error>() {
+            Some(tx3_sdk::trp::Error::TxScriptFailure(_)) => true,
+            Some(tx3_sdk::trp::Error::GenericRpcError(code, ..)) => {
+                !RESERVED_RPC_CODES.contains(code)
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Providers a provider fails over to, following `fallback` names through
+/// `providers`. Unknown names, loops and providers of another kind end the
+/// chain.
+pub fn fallback_chain(providers: &[Provider], provider: &Provider) -> Vec<Provider> {
+    let mut chain: Vec<Provider> = vec![];
+    let mut next = provider.fallback.as_deref();
+
+    while let Some(name) = next {
+        if name == provider.name() || chain.iter().any(|x| x.name() == name) {
+            break;
+        }
+
+        let Some(fallback) = providers.iter().find(|x| x.name() == name) else {
+            tracing::warn!("fallback provider '{name}' not found");
+            break;
+        };
+
+        if fallback.kind() != provider.kind() {
+            tracing::warn!(
+                "fallback provider '{name}' is an {} provider, not {}",
+                fallback.kind(),
+                provider.kind()
+            );
+            break;
+        }
+
+        chain.push(Provider {
+            failover: vec![],
+            ..fallback.clone()
+        });
+        next = fallback.fallback.as_deref();
+    }
+
+    chain
+}
+
+/// Status code of a failed request: the gRPC code of UTxO RPC errors, the
+/// HTTP status of REST and TRP ones.
+fn status_code(err: &anyhow::Error) -> Option<i32> {
+    err.chain().find_map(|cause| {
+        if let Some(utxorpc::Error::GrpcError(status)) = cause.downcast_ref::<utxorpc::Error>() {
+            return Some(i32::from(status.code()));
+        }
+
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            return err.status().map(|x| i32::from(x.as_u16()));
+        }
+
+        match cause.downcast_ref::<tx3_sdk::trp::Error>() {
+            Some(tx3_sdk::trp::Error::HttpError(status, _)) => Some(i32::from(*status)),
+            _ => None,
+        }
+    })
+}
+
+/// gRPC resource exhausted and unavailable, HTTP too many requests and
+/// service unavailable.
+const DEFAULT_RETRY_CODES: [i32; 4] = [8, 14, 429, 503];
+
+fn default_retry_codes() -> Vec<i32> {
+    DEFAULT_RETRY_CODES.to_vec()
+}
+
+/// How requests to a provider are retried on transient errors, before
+/// failing over to its fallback.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct RetryPolicy {
+    /// Attempts of each request, the first one included.
+    pub max_attempts: u32,
+    /// Delay before the first retry, roughly doubling on each of the next.
+    pub base_delay_ms: u64,
+    /// gRPC codes and HTTP statuses to retry on. Requests that can't reach
+    /// the provider are always retried.
+    #[serde(default = "default_retry_codes")]
+    pub retry_on: Vec<i32>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            retry_on: default_retry_codes(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The policy with the given settings replaced, starting from the
+    /// defaults when there's none yet. `None` if nothing is set at all.
+    pub fn update(
+        current: Option<RetryPolicy>,
+        max_attempts: Option<u32>,
+        base_delay_ms: Option<u64>,
+        retry_on: Option<Vec<i32>>,
+    ) -> Option<RetryPolicy> {
+        if current.is_none()
+            && max_attempts.is_none()
+            && base_delay_ms.is_none()
+            && retry_on.is_none()
+        {
+            return None;
+        }
+
+        let current = current.unwrap_or_default();
+
+        Some(RetryPolicy {
+            max_attempts: max_attempts.unwrap_or(current.max_attempts),
+            base_delay_ms: base_delay_ms.unwrap_or(current.base_delay_ms),
+            retry_on: retry_on.unwrap_or(current.retry_on),
+        })
+    }
+
+    fn retries(&self, err: &anyhow::Error) -> bool {
+        is_transport_error(err) || status_code(err).is_some_and(|x| self.retry_on.contains(&x))
+    }
+
+    fn backoff(&self) -> ExponentialBackoff {
+        ExponentialBackoffBuilder::new()
+            .with_initial_interval(Duration::from_millis(self.base_delay_ms))
+            .with_multiplier(2.0)
+            .with_max_elapsed_time(None)
+            .build()
+    }
+}
+
+/// Walks a provider and its fallbacks while requests fail to reach them,
+/// retrying each one as its retry policy allows.
+struct Failover<'a> {
+    method: &'static str,
+    /// Errors that move the request on to the next provider.
+    fails_over: fn(&anyhow::Error) -> bool,
+    providers: Vec<&'a Provider>,
+    current: usize,
+    attempt: u32,
+    backoff: Option<ExponentialBackoff>,
+}
+
+impl<'a> Failover<'a> {
+    fn new(provider: &'a Provider, method: &'static str) -> Self {
+        Self {
+            method,
+            fails_over: is_transport_error,
+            providers: std::iter::once(provider)
+                .chain(&provider.failover)
+                .collect(),
+            current: 0,
+            attempt: 1,
+            backoff: provider.retry.as_ref().map(RetryPolicy::backoff),
+        }
+    }
+
+    /// Moves on to the next provider on the errors `fails_over` accepts,
+    /// rather than on transport errors only.
+    fn failing_over_on(self, fails_over: fn(&anyhow::Error) -> bool) -> Self {
+        Self { fails_over, ..self }
+    }
+
+    /// Delay before retrying the current provider, if its policy allows
+    /// another attempt after this error.
+    fn retry_delay(&mut self, err: &anyhow::Error) -> Option<Duration> {
+        let policy = self.provider().retry.as_ref()?;
+
+        if self.attempt >= policy.max_attempts || !policy.retries(err) {
+            return None;
+        }
+
+        self.attempt += 1;
+        self.backoff.as_mut()?.next_backoff()
+    }
+
+    /// Provider the next attempt goes to.
+    fn provider(&self) -> &'a Provider {
+        self.providers[self.current]
+    }
+
+    /// The outcome of the attempt, or `None` when the request should be
+    /// tried again, on the same provider after a delay or on the next one.
+    async fn settle<T>(&mut self, result: Result<T>) -> Option<Result<T>> {
+        let provider = self.provider();
+
+        if let Err(err) = &result {
+            if let Some(delay) = self.retry_delay(err) {
+                tracing::warn!(
+                    "{} failed on provider '{}', retrying in {delay:?}: {err:#}",
+                    self.method,
+                    provider.name
+                );
+                tokio::time::sleep(delay).await;
+                return None;
+            }
+        }
+
+        match result {
+            Ok(_) if self.current > 0 => tracing::info!(
+                "{} served by fallback provider '{}'",
+                self.method,
+                provider.name
+            ),
+            Err(err) if (self.fails_over)(&err) && self.current + 1 < self.providers.len() => {
+                self.current += 1;
+                self.attempt = 1;
+                self.backoff = self.provider().retry.as_ref().map(RetryPolicy::backoff);
+                tracing::warn!(
+                    "{} failed on provider '{}', trying '{}': {err:#}",
+                    self.method,
+                    provider.name,
+                    self.provider().name
+                );
+                return None;
+            }
+            _ => (),
+        }
+
+        Some(result)
+    }
+}
+
+/// `[{0: [[h'aa..', 1]], 1: [[h'60 bb..', 2000000]], 2: 170000}, {}, true, null]`,
+/// a well-formed tx without witnesses spending an output that doesn't exist.
+pub(crate) fn probe_tx() -> Vec<u8> {
+    let tx = [
+        "84a3008182",
+        &format!("5820{}", "aa".repeat(32)),
+        "01",
+        "018182",
+        &format!("581d60{}", "bb".repeat(28)),
+        "1a001e8480",
+        "021a00029810",
+        "a0f5f6",
+    ]
+    .concat();
+
+    hex::decode(tx).unwrap()
+}
+
+fn utxo_predicate(
+    address: &Address,
+    matching: AddressMatch,
+) -> Result<utxorpc::spec::query::UtxoPredicate> {
+    let pattern = match matching {
+        AddressMatch::Full => utxorpc::spec::cardano::AddressPattern {
+            exact_address: address.to_vec().into(),
+            ..Default::default()
+        },
+        AddressMatch::Payment => {
+            let Address::Shelley(shelley) = address else {
+                bail!("payment matching requires a shelley address");
+            };
+
+            utxorpc::spec::cardano::AddressPattern {
+                payment_part: shelley.payment().to_vec().into(),
+                ..Default::default()
+            }
+        }
+    };
+
+    Ok(utxorpc::spec::query::UtxoPredicate {
+        r#match: Some(utxorpc::spec::query::AnyUtxoPattern {
+            utxo_pattern: Some(UtxoPattern::Cardano(
+                utxorpc::spec::cardano::TxOutputPattern {
+                    address: Some(pattern),
+                    ..Default::default()
+                },
+            )),
+        }),
+        ..Default::default()
+    })
+}
+
+fn expand_headers(headers: &HashMap<String, String>) -> Result<HashMap<String, String>> {
+    headers
+        .iter()
+        .map(|(key, value)| {
+            let value =
+                expand_env_vars(value).with_context(|| format!("expanding header {key}"))?;
+            Ok((key.clone(), value))
+        })
+        .collect()
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(tag = "type")]
 pub struct Provider {
     pub name: Name,
+    /// Defaults to UTxO RPC, which providers predating other kinds speak.
+    pub kind: Option<ProviderKind>,
     pub url: String,
     pub headers: Option<HashMap<String, String>>,
     pub is_default: Option<bool>,
     pub is_testnet: Option<bool>,
+    /// Network the provider is the default of when flagged as default.
+    /// Guessed from the url of testnet providers unless set.
+    pub network: Option<NetworkName>,
     pub trp_url: Option<String>,
     pub trp_headers: Option<HashMap<String, String>>,
+    pub trp_inherit_headers: Option<bool>,
+    pub explorer_url: Option<String>,
+    /// Seconds UTxO searches are reused for within a process, e.g. while the
+    /// explorer polls balances. Searches aren't cached unless set.
+    pub utxo_cache_ttl: Option<u64>,
+    /// Network magic for the node handshake. Only needed by node providers
+    /// on testnets.
+    pub network_magic: Option<u64>,
+    /// Retries of requests that fail with transient errors. Requests aren't
+    /// retried unless set.
+    pub retry: Option<RetryPolicy>,
+    /// Provider to retry against when this one can't be reached.
+    pub fallback: Option<String>,
+    /// Providers `fallback` leads to, in order, as linked by the store.
+    #[serde(skip)]
+    pub failover: Vec<Provider>,
+}
+
+const MAINNET_EXPLORER_URL: &str = "https://cardanoscan.io/transaction/{hash}";
+const PREVIEW_EXPLORER_URL: &str = "https://preview.cardanoscan.io/transaction/{hash}";
+const PREPROD_EXPLORER_URL: &str = "https://preprod.cardanoscan.io/transaction/{hash}";
+
+/// Checks that a block-explorer url template has a `{hash}` placeholder.
+pub fn validate_explorer_url(template: &str) -> Result<()> {
+    if !template.contains("{hash}") {
+        bail!("explorer url must contain a {{hash}} placeholder");
+    }
+
+    Ok(())
+}
+
+/// Parses a `key:value` header, as passed with `--trp-header`.
+pub fn parse_header(raw: &str) -> Result<(String, String)> {
+    match raw.split_once(':') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => bail!("invalid header '{raw}', expected key:value"),
+    }
+}
+
+/// Checks that the fallback of a provider names another provider of the same
+/// kind, on the same network, and doesn't lead back to the one falling over
+/// to it.
+pub fn validate_fallback(providers: &[Provider], provider: &Provider) -> Result<()> {
+    let Some(fallback) = &provider.fallback else {
+        return Ok(());
+    };
+
+    let name = provider.name();
+
+    let Some(target) = providers.iter().find(|x| x.name() == *fallback) else {
+        bail!("fallback provider '{fallback}' not found");
+    };
+
+    if *fallback == name
+        || fallback_chain(providers, target)
+            .iter()
+            .any(|x| x.name() == name)
+    {
+        bail!("fallback provider '{fallback}' falls back to '{name}'");
+    }
+
+    check_fallback(provider, target)
+}
+
+/// Checks that a provider can stand in for another one: same network and
+/// same kind, so that any request goes through it the same way.
+pub fn check_fallback(provider: &Provider, fallback: &Provider) -> Result<()> {
+    let (name, fallback_name) = (provider.name(), fallback.name());
+
+    let same_network = match (provider.network(), fallback.network()) {
+        (Some(a), Some(b)) => a == b,
+        _ => provider.is_testnet() == fallback.is_testnet(),
+    };
+
+    if !same_network {
+        bail!("fallback provider '{fallback_name}' is on a different network than '{name}'");
+    }
+
+    if fallback.kind() != provider.kind() {
+        bail!(
+            "fallback provider '{fallback_name}' is an {} provider, not {} like '{name}'",
+            fallback.kind(),
+            provider.kind()
+        );
+    }
+
+    Ok(())
 }
 
 impl Provider {
@@ -34,11 +592,25 @@ impl Provider {
         self.name.to_string()
     }
 
+    pub fn kind(&self) -> ProviderKind {
+        self.kind.unwrap_or_default()
+    }
+
     pub fn parameters(&self) -> Option<Value> {
-        Some(json!({
+        let mut parameters = json!({
             "url": self.url,
             "headers": self.headers
-        }))
+        });
+
+        if let Some(kind) = self.kind {
+            parameters["kind"] = json!(kind);
+        }
+
+        if let Some(fallback) = &self.fallback {
+            parameters["fallback"] = json!(fallback);
+        }
+
+        Some(parameters)
     }
     pub fn is_default(&self) -> bool {
         self.is_default.unwrap_or(false)
@@ -48,66 +620,337 @@ impl Provider {
         self.is_testnet.unwrap_or(false)
     }
 
+    /// Network the provider serves, if known. Other testnets (e.g. local
+    /// devnets) have none.
+    pub fn network(&self) -> Option<NetworkName> {
+        match self.network {
+            Some(network) => Some(network),
+            None if !self.is_testnet() => Some(NetworkName::Mainnet),
+            None if self.url.contains("preprod") => Some(NetworkName::Preprod),
+            None if self.url.contains("preview") => Some(NetworkName::Preview),
+            None => None,
+        }
+    }
+
+    /// Link to the tx on a block explorer. Unless configured, the template is
+    /// picked from the network, guessing the testnet from the provider url.
+    /// Other testnets (e.g. local devnets) have no default template.
+    pub fn explorer_link(&self, hash: &str) -> Option<String> {
+        let template = match (&self.explorer_url, self.network()) {
+            (Some(template), _) => template.as_str(),
+            (None, Some(NetworkName::Mainnet)) => MAINNET_EXPLORER_URL,
+            (None, Some(NetworkName::Preprod)) => PREPROD_EXPLORER_URL,
+            (None, Some(NetworkName::Preview)) => PREVIEW_EXPLORER_URL,
+            (None, None) => return None,
+        };
+
+        Some(template.replace("{hash}", hash))
+    }
+
+    pub fn trp_inherit_headers(&self) -> bool {
+        self.trp_inherit_headers.unwrap_or(false)
+    }
+
+    /// UTxORPC headers with `${VAR}` references expanded.
+    pub fn resolved_headers(&self) -> Result<Option<HashMap<String, String>>> {
+        self.headers.as_ref().map(expand_headers).transpose()
+    }
+
+    /// TRP headers with `${VAR}` references expanded. When inheriting, the
+    /// UTxORPC headers are used as a base and TRP headers take precedence.
+    pub fn resolved_trp_headers(&self) -> Result<Option<HashMap<String, String>>> {
+        let mut headers = match (&self.headers, self.trp_inherit_headers()) {
+            (Some(headers), true) => headers.clone(),
+            _ => HashMap::new(),
+        };
+
+        if let Some(trp_headers) = &self.trp_headers {
+            headers.extend(trp_headers.clone());
+        }
+
+        if headers.is_empty() {
+            return Ok(None);
+        }
+
+        expand_headers(&headers).map(Some)
+    }
+
+    /// UTxO RPC client. Requests only UTxO RPC serves (blocks, txs,
+    /// parameters) fail for other kinds of providers.
     pub async fn client<T>(&self) -> Result<T>
     where
         T: From<InnerService>,
     {
+        if self.kind() != ProviderKind::Utxorpc {
+            bail!(
+                "provider '{}' is an {} provider, which doesn't support this request",
+                self.name,
+                self.kind()
+            );
+        }
+
         let mut client_builder = ClientBuilder::new().uri(self.url.clone())?;
 
-        if let Some(headers) = &self.headers {
+        if let Some(headers) = &self.resolved_headers()? {
             for (k, v) in headers {
                 client_builder = client_builder.metadata(k, v)?;
             }
         }
         Ok(client_builder.build::<T>().await)
     }
-    pub async fn test(&self) -> Result<()> {
-        println!("Building client...");
+    pub async fn ogmios_client(&self) -> Result<ogmios::Client> {
+        ogmios::Client::connect(&self.url, self.resolved_headers()?.as_ref()).await
+    }
+
+    pub fn network_magic(&self) -> Result<u64> {
+        match (self.network_magic, self.is_testnet()) {
+            (Some(magic), _) => Ok(magic),
+            (None, false) => Ok(MAINNET_MAGIC),
+            (None, true) => bail!("provider '{}' requires a network magic", self.name),
+        }
+    }
+
+    pub async fn node_client(&self) -> Result<node::Client> {
+        node::Client::connect(&self.url, self.network_magic()?).await
+    }
+
+    pub fn maestro_client(&self) -> Result<maestro::Client> {
+        maestro::Client::new(&self.url, self.resolved_headers()?)
+    }
+
+    /// Follows the chain tip of a UTxO RPC provider. Connecting moves on to
+    /// the fallbacks, but a stream that drops later on ends there.
+    pub async fn follow_tip(&self) -> Result<LiveTip<Cardano>> {
+        self.with_failover("follow_tip", |provider| provider.follow_tip_once())
+            .await
+    }
+
+    async fn follow_tip_once(&self) -> Result<LiveTip<Cardano>> {
         let mut client: CardanoSyncClient = self.client().await?;
+        Ok(client.follow_tip(vec![]).await?)
+    }
 
-        println!("Executing ReadTip method...");
-        let result = client.read_tip().await?;
-        match result {
-            Some(blockref) => {
-                println!(
-                    "Successfull request, block tip at slot {} and hash {}.",
-                    blockref.slot,
-                    hex::encode(blockref.hash)
-                )
+    /// Follows the chain from its tip, for providers that aren't UTxO RPC
+    /// ones. Like [`Provider::follow_tip`], only connecting moves on to the
+    /// fallbacks.
+    pub async fn follow_chain(&self) -> Result<ChainFollower> {
+        self.with_failover("follow_chain", |provider| provider.follow_chain_once())
+            .await
+    }
+
+    async fn follow_chain_once(&self) -> Result<ChainFollower> {
+        match self.kind() {
+            ProviderKind::Utxorpc => bail!("utxorpc providers follow the tip through their client"),
+            ProviderKind::Maestro => bail!("maestro providers can't follow the chain"),
+            ProviderKind::Ogmios => {
+                let client = self.ogmios_client().await?;
+                client.intersect_tip().await?;
+                Ok(ChainFollower::Ogmios(client))
+            }
+            ProviderKind::Node => {
+                let mut client = self.node_client().await?;
+                client.intersect_tip().await?;
+                Ok(ChainFollower::Node(Box::new(client)))
             }
-            None => println!("Successfull request"),
         }
+    }
 
-        Ok(())
+    /// Runs a request on the provider, moving on to its fallbacks while it
+    /// fails to reach them.
+    pub(crate) async fn with_failover<'a, T, F, Fut>(
+        &'a self,
+        method: &'static str,
+        request: F,
+    ) -> Result<T>
+    where
+        F: FnMut(&'a Provider) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.run_failover(Failover::new(self, method), request)
+            .await
     }
 
-    pub async fn get_balance(&self, address: &Address) -> Result<Balance> {
+    /// Runs a request through the providers of a failover walk until it
+    /// settles.
+    async fn run_failover<'a, T, F, Fut>(
+        &'a self,
+        mut failover: Failover<'a>,
+        mut request: F,
+    ) -> Result<T>
+    where
+        F: FnMut(&'a Provider) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        loop {
+            let result = request(failover.provider()).await;
+            if let Some(result) = failover.settle(result).await {
+                return result;
+            }
+        }
+    }
+
+    /// Chain tip, or `None` when the chain is empty.
+    pub async fn read_tip(&self) -> Result<Option<Tip>> {
+        self.with_failover("read_tip", |provider| provider.read_tip_once())
+            .await
+    }
+
+    async fn read_tip_once(&self) -> Result<Option<Tip>> {
+        match self.kind() {
+            ProviderKind::Utxorpc => {
+                let mut client: CardanoSyncClient = self.client().await?;
+                let tip = track(&self.name, "read_tip", client.read_tip()).await?;
+                Ok(tip.map(|x| Tip {
+                    slot: x.slot,
+                    hash: x.hash.to_vec(),
+                    height: Some(x.height),
+                }))
+            }
+            ProviderKind::Ogmios => {
+                let client = self.ogmios_client().await?;
+                track(&self.name, "read_tip", client.tip()).await
+            }
+            ProviderKind::Node => {
+                let mut client = self.node_client().await?;
+                track(&self.name, "read_tip", client.tip()).await
+            }
+            ProviderKind::Maestro => {
+                let client = self.maestro_client()?;
+                track(&self.name, "read_tip", client.tip()).await
+            }
+        }
+    }
+
+    /// Submits a tx spending an output that doesn't exist, which a working
+    /// endpoint rejects. Only failing to get an answer fails the probe.
+    pub async fn submit_invalid_tx(&self) -> Result<()> {
+        match self.submit(&probe_tx()).await {
+            Ok(_) => bail!("the endpoint accepted a tx that can't be valid"),
+            Err(err) if err.downcast_ref::<Rejected>().is_some() => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Resolves an empty tx template, which a working TRP server refuses to
+    /// decode.
+    pub async fn probe_trp(&self) -> Result<()> {
+        let request = tx3_sdk::trp::ResolveParams {
+            args: Default::default(),
+            tir: tx3_sdk::core::TirEnvelope {
+                content: String::new(),
+                encoding: tx3_sdk::core::TirEncoding::Hex,
+                version: "v1beta0".to_string(),
+            },
+            env: None,
+        };
+
+        let Err(err) = self.trp_resolve(request).await else {
+            return Ok(());
+        };
+
+        match err.downcast_ref::<tx3_sdk::trp::Error>() {
+            Some(
+                tx3_sdk::trp::Error::NetworkError(_)
+                | tx3_sdk::trp::Error::HttpError(..)
+                | tx3_sdk::trp::Error::DeserializationError(_)
+                | tx3_sdk::trp::Error::UnknownError(_),
+            )
+            | None => Err(err),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// UTxOs matching the address, reused from a previous search while it
+    /// is fresher than `utxo_cache_ttl`.
+    async fn search_utxos(
+        &self,
+        address: &Address,
+        matching: AddressMatch,
+    ) -> Result<Vec<AnyUtxoData>> {
+        let key = (self.name(), address.to_vec(), matching);
+        let ttl = self.utxo_cache_ttl.map(Duration::from_secs);
+
+        cache::cached(key, ttl, || self.fetch_utxos(address, matching)).await
+    }
+
+    /// UTxOs matching the address, always asking the endpoint.
+    pub async fn fetch_utxos(
+        &self,
+        address: &Address,
+        matching: AddressMatch,
+    ) -> Result<Vec<AnyUtxoData>> {
+        self.with_failover("search_utxos", |provider| {
+            provider.fetch_utxos_once(address, matching)
+        })
+        .await
+    }
+
+    async fn fetch_utxos_once(
+        &self,
+        address: &Address,
+        matching: AddressMatch,
+    ) -> Result<Vec<AnyUtxoData>> {
+        match self.kind() {
+            ProviderKind::Utxorpc => (),
+            _ if matching == AddressMatch::Payment => {
+                bail!(
+                    "{} providers only support full address matching",
+                    self.kind()
+                )
+            }
+            ProviderKind::Ogmios => {
+                let client = self.ogmios_client().await?;
+                return track(&self.name, "search_utxos", client.utxos(address))
+                    .await
+                    .context("failed to query utxos");
+            }
+            ProviderKind::Node => {
+                let mut client = self.node_client().await?;
+                return track(&self.name, "search_utxos", client.utxos(address))
+                    .await
+                    .context("failed to query utxos");
+            }
+            ProviderKind::Maestro => {
+                let client = self.maestro_client()?;
+                return track(&self.name, "search_utxos", client.utxos(address))
+                    .await
+                    .context("failed to query utxos");
+            }
+        }
+
         let mut client: CardanoQueryClient = self.client().await?;
 
-        let predicate = utxorpc::spec::query::UtxoPredicate {
-            r#match: Some(utxorpc::spec::query::AnyUtxoPattern {
-                utxo_pattern: Some(UtxoPattern::Cardano(
-                    utxorpc::spec::cardano::TxOutputPattern {
-                        address: Some(utxorpc::spec::cardano::AddressPattern {
-                            exact_address: address.to_vec().into(),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                )),
-            }),
-            ..Default::default()
+        let request = utxorpc::spec::query::SearchUtxosRequest {
+            predicate: Some(utxo_predicate(address, matching)?),
+            field_mask: None,
+            start_token: String::new(),
+            max_items: u32::MAX as i32,
         };
-        let utxos = client
-            .search_utxos(predicate, None, u32::MAX)
-            .await
-            .context("failed to query utxos")?;
 
-        let coin: u64 = utxos
-            .items
-            .clone()
+        // the raw response, unlike the sdk page, keeps the block of each
+        // utxo
+        let response = track(
+            &self.name,
+            "search_utxos",
+            client.inner.search_utxos(request),
+        )
+        .await
+        .context("failed to query utxos")?;
+
+        Ok(response.into_inner().items)
+    }
+
+    pub async fn get_balance(&self, address: &Address, matching: AddressMatch) -> Result<Balance> {
+        let outputs: Vec<TxOutput> = self
+            .search_utxos(address, matching)
+            .await?
             .into_iter()
-            .filter_map(|x| x.parsed.and_then(|p| p.coin))
+            .filter_map(|x| x.parsed_state.map(|ParsedState::Cardano(output)| output))
+            .collect();
+
+        let coin: u64 = outputs
+            .iter()
+            .filter_map(|x| x.coin.as_ref())
             .filter_map(|c| {
                 c.big_int.as_ref().and_then(|bi| match bi {
                     utxorpc::spec::cardano::big_int::BigInt::Int(i) => Some(*i as u64),
@@ -116,14 +959,10 @@ impl Provider {
             })
             .sum();
 
-        let assets = utxos
-            .items
-            .clone()
-            .into_iter()
+        let assets: Vec<BalanceAsset> = outputs
+            .iter()
             .flat_map(|x| {
-                x.parsed
-                    .unwrap()
-                    .assets
+                x.assets
                     .iter()
                     .map(|asset| BalanceAsset {
                         policy_id: asset.policy_id.to_vec(),
@@ -140,11 +979,9 @@ impl Provider {
             })
             .collect();
 
-        let datums = utxos
-            .items
-            .clone()
-            .into_iter()
-            .flat_map(|x| match x.parsed.unwrap().datum {
+        let datums = outputs
+            .iter()
+            .flat_map(|x| match &x.datum {
                 Some(datum) => {
                     if datum.hash.is_empty() {
                         None
@@ -161,114 +998,67 @@ impl Provider {
         Ok(Balance {
             coin: coin.to_string(),
             address: address.to_string(),
-            assets,
+            assets: aggregate_assets(assets)?,
             datums,
+            metadata: Default::default(),
         })
     }
 
     pub async fn get_wallet_utxos(
         &self,
         address: &Address,
-    ) -> Result<Vec<utxorpc::spec::query::AnyUtxoData>> {
-        let mut client: CardanoQueryClient = self.client().await?;
-
-        let predicate = utxorpc::spec::query::UtxoPredicate {
-            r#match: Some(utxorpc::spec::query::AnyUtxoPattern {
-                utxo_pattern: Some(UtxoPattern::Cardano(
-                    utxorpc::spec::cardano::TxOutputPattern {
-                        address: Some(utxorpc::spec::cardano::AddressPattern {
-                            exact_address: address.to_vec().into(),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                )),
-            }),
-            ..Default::default()
-        };
-
-        let response = client
-            .search_utxos(predicate, None, u32::MAX)
-            .await
-            .context("failed to query utxos")?;
-
-        let utxos = response
-            .items
-            .into_iter()
-            .map(|utxo| utxorpc::spec::query::AnyUtxoData {
-                native_bytes: utxo.native,
-                txo_ref: utxo.txo_ref,
-                parsed_state: utxo
-                    .parsed
-                    .map(utxorpc::spec::query::any_utxo_data::ParsedState::Cardano),
-                block_ref: None,
-            })
-            .collect();
-
-        Ok(utxos)
+        matching: AddressMatch,
+    ) -> Result<Vec<AnyUtxoData>> {
+        self.search_utxos(address, matching).await
     }
 
-    pub async fn get_detailed_balance(&self, address: &Address) -> Result<DetailedBalance> {
-        let mut client: CardanoQueryClient = self.client().await?;
-
-        let predicate = utxorpc::spec::query::UtxoPredicate {
-            r#match: Some(utxorpc::spec::query::AnyUtxoPattern {
-                utxo_pattern: Some(UtxoPattern::Cardano(
-                    utxorpc::spec::cardano::TxOutputPattern {
-                        address: Some(utxorpc::spec::cardano::AddressPattern {
-                            exact_address: address.to_vec().into(),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                )),
-            }),
-            ..Default::default()
-        };
-        let utxos = client
-            .search_utxos(predicate, None, u32::MAX)
-            .await
-            .context("failed to query utxos")?;
+    pub async fn get_detailed_balance(
+        &self,
+        address: &Address,
+        matching: AddressMatch,
+    ) -> Result<DetailedBalance> {
+        let utxos = self.search_utxos(address, matching).await?;
 
         let mut result: DetailedBalance = utxos
-            .items
             .into_iter()
-            .map(|utxo| {
-                let txoref = utxo.txo_ref.unwrap();
-                let utxo = utxo.parsed.unwrap();
-                UTxO {
-                    tx: txoref.hash.to_vec(),
-                    tx_index: txoref.index as u64,
-                    address: address.to_string(),
-                    coin: crate::utils::format_bigint_opt(&utxo.coin),
-                    assets: utxo
-                        .assets
-                        .iter()
-                        .map(|asset| BalanceAsset {
-                            policy_id: asset.policy_id.to_vec(),
-                            assets: asset
-                                .assets
-                                .iter()
-                                .map(|inner| Asset {
-                                    name: inner.name.to_vec(),
-                                    quantity: crate::utils::format_asset_quantity(&inner.quantity),
-                                })
-                                .collect::<Vec<Asset>>(),
-                        })
-                        .collect::<Vec<BalanceAsset>>(),
-                    datum: match utxo.datum {
-                        Some(datum) => {
-                            if datum.hash.is_empty() {
-                                None
-                            } else {
-                                Some(Datum {
-                                    hash: datum.hash.to_vec(),
-                                })
-                            }
+            .filter_map(|utxo| match (utxo.txo_ref, utxo.parsed_state) {
+                (Some(txoref), Some(ParsedState::Cardano(output))) => Some((txoref, output)),
+                _ => None,
+            })
+            .map(|(txoref, utxo)| UTxO {
+                tx: txoref.hash.to_vec(),
+                tx_index: txoref.index as u64,
+                address: Address::from_bytes(&utxo.address)
+                    .map(|x| x.to_string())
+                    .unwrap_or_else(|_| address.to_string()),
+                coin: crate::utils::format_bigint_opt(&utxo.coin),
+                assets: utxo
+                    .assets
+                    .iter()
+                    .map(|asset| BalanceAsset {
+                        policy_id: asset.policy_id.to_vec(),
+                        assets: asset
+                            .assets
+                            .iter()
+                            .map(|inner| Asset {
+                                name: inner.name.to_vec(),
+                                quantity: crate::utils::format_asset_quantity(&inner.quantity),
+                            })
+                            .collect::<Vec<Asset>>(),
+                    })
+                    .collect::<Vec<BalanceAsset>>(),
+                datum: match utxo.datum {
+                    Some(datum) => {
+                        if datum.hash.is_empty() {
+                            None
+                        } else {
+                            Some(Datum {
+                                hash: datum.hash.to_vec(),
+                            })
                         }
-                        None => None,
-                    },
-                }
+                    }
+                    None => None,
+                },
             })
             .collect();
 
@@ -278,28 +1068,77 @@ impl Provider {
     }
 
     pub async fn submit(&self, tx: &[u8]) -> Result<Vec<u8>> {
+        let hash = self
+            .with_failover("submit_tx", |provider| provider.submit_once(tx))
+            .await?;
+
+        cache::invalidate(&self.name());
+        Ok(hash)
+    }
+
+    async fn submit_once(&self, tx: &[u8]) -> Result<Vec<u8>> {
+        match self.kind() {
+            ProviderKind::Utxorpc => (),
+            ProviderKind::Ogmios => {
+                let client = self.ogmios_client().await?;
+                return track(&self.name, "submit_tx", client.submit(tx))
+                    .await
+                    .context("Transaction submission failed");
+            }
+            ProviderKind::Node => {
+                let mut client = self.node_client().await?;
+                return track(&self.name, "submit_tx", client.submit(tx))
+                    .await
+                    .context("Transaction submission failed");
+            }
+            ProviderKind::Maestro => {
+                let client = self.maestro_client()?;
+                return track(&self.name, "submit_tx", client.submit(tx))
+                    .await
+                    .context("Transaction submission failed");
+            }
+        }
+
         let mut client: CardanoSubmitClient = self.client().await?;
 
-        match client.submit_tx(tx.to_vec()).await {
+        match track(&self.name, "submit_tx", client.submit_tx(tx.to_vec())).await {
             Ok(response) => Ok(response.to_vec()),
-            Err(err) => {
-                match err {
-                    utxorpc::Error::TransportError(e) => {
-                        Err(anyhow!(e).context("Network error while submitting transaction"))
-                    }
-                    utxorpc::Error::GrpcError(status) => Err(anyhow!(status.message().to_string())
-                        .context("Transaction submission failed")),
-                    utxorpc::Error::ParseError(e) => {
-                        Err(anyhow!(e).context("Failed to parse transaction"))
-                    }
+            Err(err) => match err {
+                utxorpc::Error::TransportError(_) => {
+                    Err(anyhow!(err).context("Network error while submitting transaction"))
                 }
-            }
+                utxorpc::Error::GrpcError(status)
+                    if REJECTION_CODES.contains(&i32::from(status.code())) =>
+                {
+                    Err(anyhow!(Rejected(status.message().to_string()))
+                        .context("Transaction submission failed"))
+                }
+                utxorpc::Error::GrpcError(ref status) => {
+                    let message = status.message().to_string();
+                    Err(anyhow!(err)
+                        .context(message)
+                        .context("Transaction submission failed"))
+                }
+                utxorpc::Error::ParseError(e) => {
+                    Err(anyhow!(e).context("Failed to parse transaction"))
+                }
+            },
         }
     }
 
     pub async fn trp_resolve(
         &self,
         request: tx3_sdk::trp::ResolveParams,
+    ) -> Result<tx3_sdk::trp::TxEnvelope> {
+        self.with_failover("trp_resolve", |provider| {
+            provider.trp_resolve_once(request.clone())
+        })
+        .await
+    }
+
+    async fn trp_resolve_once(
+        &self,
+        request: tx3_sdk::trp::ResolveParams,
     ) -> Result<tx3_sdk::trp::TxEnvelope> {
         let Some(trp_url) = &self.trp_url else {
             bail!("missing TRP configuration for this provider")
@@ -307,17 +1146,39 @@ impl Provider {
 
         let client = tx3_sdk::trp::Client::new(tx3_sdk::trp::ClientOptions {
             endpoint: trp_url.clone(),
-            headers: self.trp_headers.clone(),
+            headers: self.resolved_trp_headers()?,
         });
 
-        let result = client.resolve(request).await?;
+        let result = track(&self.name, "trp_resolve", client.resolve(request)).await?;
 
         Ok(result)
     }
 
+    /// Submits through the provider or its fallbacks, returning the one that
+    /// accepted the tx along with its response. Any error but the tx being
+    /// refused moves on to the next one, so a flaky endpoint doesn't lose an
+    /// already signed tx.
     pub async fn trp_submit(
         &self,
         request: tx3_sdk::trp::SubmitParams,
+    ) -> Result<(&Provider, tx3_sdk::trp::SubmitResponse)> {
+        let failover =
+            Failover::new(self, "trp_submit").failing_over_on(|err| !is_tx_rejection(err));
+
+        let submitted = self
+            .run_failover(failover, |provider| {
+                let request = request.clone();
+                async move { Ok((provider, provider.trp_submit_once(request).await?)) }
+            })
+            .await?;
+
+        cache::invalidate(&self.name());
+        Ok(submitted)
+    }
+
+    async fn trp_submit_once(
+        &self,
+        request: tx3_sdk::trp::SubmitParams,
     ) -> Result<tx3_sdk::trp::SubmitResponse> {
         let Some(trp_url) = &self.trp_url else {
             bail!("missing TRP configuration for this provider")
@@ -325,16 +1186,41 @@ impl Provider {
 
         let client = tx3_sdk::trp::Client::new(tx3_sdk::trp::ClientOptions {
             endpoint: trp_url.clone(),
-            headers: self.trp_headers.clone(),
+            headers: self.resolved_trp_headers()?,
         });
 
-        Ok(client.submit(request).await?)
+        Ok(track(&self.name, "trp_submit", client.submit(request)).await?)
     }
 
     pub async fn fetch_block(
         &self,
         refs: Vec<(Vec<u8>, u64)>,
     ) -> Result<Vec<ChainBlock<utxorpc::spec::cardano::Block>>> {
+        self.with_failover("fetch_block", |provider| {
+            provider.fetch_block_once(refs.clone())
+        })
+        .await
+    }
+
+    async fn fetch_block_once(
+        &self,
+        refs: Vec<(Vec<u8>, u64)>,
+    ) -> Result<Vec<ChainBlock<utxorpc::spec::cardano::Block>>> {
+        if self.kind() == ProviderKind::Maestro {
+            let client = self.maestro_client()?;
+
+            let mut blocks = vec![];
+            for (hash, _) in &refs {
+                if let Some(block) =
+                    track(&self.name, "fetch_block", client.fetch_block(hash)).await?
+                {
+                    blocks.push(block);
+                }
+            }
+
+            return Ok(blocks);
+        }
+
         let mut client: utxorpc::CardanoSyncClient = self.client().await?;
 
         let refs = refs
@@ -346,7 +1232,7 @@ impl Provider {
             })
             .collect();
 
-        let response = client.fetch_block(refs).await?;
+        let response = track(&self.name, "fetch_block", client.fetch_block(refs)).await?;
 
         Ok(response)
     }
@@ -355,17 +1241,47 @@ impl Provider {
         &self,
         hash: Vec<u8>,
     ) -> Result<Option<ChainTx<utxorpc::spec::cardano::Tx>>> {
+        self.with_failover("read_tx", |provider| provider.fetch_tx_once(hash.clone()))
+            .await
+    }
+
+    async fn fetch_tx_once(
+        &self,
+        hash: Vec<u8>,
+    ) -> Result<Option<ChainTx<utxorpc::spec::cardano::Tx>>> {
+        if self.kind() == ProviderKind::Maestro {
+            let client = self.maestro_client()?;
+            return track(&self.name, "read_tx", client.fetch_tx(&hash)).await;
+        }
+
         let mut client: utxorpc::CardanoQueryClient = self.client().await?;
 
-        let response = client.read_tx(hash.into()).await?;
+        let response = track(&self.name, "read_tx", client.read_tx(hash.into())).await?;
 
         Ok(response)
     }
+
+    /// Protocol parameters currently in effect on the chain.
+    pub async fn read_params(&self) -> Result<utxorpc::spec::cardano::PParams> {
+        self.with_failover("read_params", |provider| provider.read_params_once())
+            .await
+    }
+
+    async fn read_params_once(&self) -> Result<utxorpc::spec::cardano::PParams> {
+        let mut client: utxorpc::CardanoQueryClient = self.client().await?;
+
+        let response = track(&self.name, "read_params", client.read_params()).await?;
+
+        match response.params {
+            Some(utxorpc::spec::query::any_chain_params::Params::Cardano(params)) => Ok(params),
+            None => bail!("provider returned no protocol parameters"),
+        }
+    }
 }
 
 impl OutputFormatter for Provider {
-    fn to_table(&self) {
-        let mut table = Table::new();
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
 
         table.set_header(vec!["Name", "is testnet?", "Is default?", "Parameters"]);
         table.add_row(vec![
@@ -381,29 +1297,348 @@ impl OutputFormatter for Provider {
         println!("{table}");
     }
 
-    fn to_json(&self) {
-        println!("{}", serde_json::to_string_pretty(self).unwrap());
+    fn to_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
     }
 }
 
+fn providers_table(providers: &[Provider], style: TableStyle) -> comfy_table::Table {
+    let mut table = crate::output::table(style);
+    table.set_header(vec!["Name", "Is default?", "Parameters"]);
+    for provider in providers {
+        table.add_row(vec![
+            provider.name(),
+            provider.is_default().to_string(),
+            match provider.parameters() {
+                Some(value) => serde_json::to_string(&value).unwrap(),
+                None => "".to_string(),
+            },
+        ]);
+    }
+    table
+}
+
 impl OutputFormatter for &Vec<Provider> {
-    fn to_table(&self) {
-        let mut table = Table::new();
-        table.set_header(vec!["Name", "Is default?", "Parameters"]);
-        for provider in *self {
-            table.add_row(vec![
-                provider.name(),
-                provider.is_default().to_string(),
-                match provider.parameters() {
-                    Some(value) => serde_json::to_string(&value).unwrap(),
-                    None => "".to_string(),
-                },
-            ]);
+    fn to_table(&self, style: TableStyle) {
+        println!("{}", providers_table(self, style));
+    }
+
+    fn to_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+#[cfg(test)]
+impl Provider {
+    /// A testnet utxorpc provider at `http://{name}:50051`, for tests to
+    /// override the fields they care about.
+    pub fn test(name: &str) -> Self {
+        Self {
+            name: Name::try_from(name).unwrap(),
+            kind: None,
+            url: format!("http://{name}:50051"),
+            headers: None,
+            is_default: None,
+            is_testnet: Some(true),
+            network: None,
+            trp_url: None,
+            trp_headers: None,
+            trp_inherit_headers: None,
+            explorer_url: None,
+            utxo_cache_ttl: None,
+            network_magic: None,
+            retry: None,
+            fallback: None,
+            failover: vec![],
         }
-        println!("{table}");
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::addresses::{
+        Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+    };
+
+    use super::*;
+
+    fn address_pattern(address: &Address, matching: AddressMatch) -> Vec<u8> {
+        let predicate = utxo_predicate(address, matching).unwrap();
+        let Some(UtxoPattern::Cardano(pattern)) = predicate.r#match.unwrap().utxo_pattern else {
+            unreachable!()
+        };
+        let pattern = pattern.address.unwrap();
+
+        [
+            pattern.exact_address.to_vec(),
+            pattern.payment_part.to_vec(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn trp_headers_expansion_and_inheritance() {
+        std::env::set_var("CSHELL_TEST_TRP_KEY", "dmtr_trp");
+        std::env::set_var("CSHELL_TEST_U5C_KEY", "dmtr_u5c");
+
+        let mut provider = Provider {
+            url: "https://localhost".into(),
+            headers: Some(HashMap::from([
+                ("dmtr-api-key".into(), "${CSHELL_TEST_U5C_KEY}".into()),
+                ("x-shared".into(), "u5c".into()),
+            ])),
+            is_testnet: None,
+            trp_url: Some("https://localhost".into()),
+            ..Provider::test("demeter")
+        };
+
+        assert_eq!(
+            provider.resolved_headers().unwrap().unwrap()["dmtr-api-key"],
+            "dmtr_u5c"
+        );
+        assert_eq!(provider.resolved_trp_headers().unwrap(), None);
+
+        provider.trp_inherit_headers = Some(true);
+        let headers = provider.resolved_trp_headers().unwrap().unwrap();
+        assert_eq!(headers["dmtr-api-key"], "dmtr_u5c");
+        assert_eq!(headers["x-shared"], "u5c");
+
+        // Explicit TRP headers take precedence over inherited ones
+        provider.trp_headers = Some(HashMap::from([(
+            "dmtr-api-key".into(),
+            "${CSHELL_TEST_TRP_KEY}".into(),
+        )]));
+        let headers = provider.resolved_trp_headers().unwrap().unwrap();
+        assert_eq!(headers["dmtr-api-key"], "dmtr_trp");
+        assert_eq!(headers["x-shared"], "u5c");
+
+        provider.trp_inherit_headers = Some(false);
+        let headers = provider.resolved_trp_headers().unwrap().unwrap();
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn explorer_links() {
+        let mut provider = Provider {
+            url: "https://preprod.utxorpc-v0.demeter.run".into(),
+            ..Provider::test("preprod")
+        };
+
+        assert_eq!(
+            provider.explorer_link("abcd").unwrap(),
+            "https://preprod.cardanoscan.io/transaction/abcd"
+        );
+
+        provider.url = "http://localhost:50051".into();
+        assert_eq!(provider.explorer_link("abcd"), None);
+
+        provider.is_testnet = Some(false);
+        assert_eq!(
+            provider.explorer_link("abcd").unwrap(),
+            "https://cardanoscan.io/transaction/abcd"
+        );
+
+        provider.explorer_url = Some("https://cexplorer.io/tx/{hash}".into());
+        assert_eq!(
+            provider.explorer_link("abcd").unwrap(),
+            "https://cexplorer.io/tx/abcd"
+        );
+
+        assert!(validate_explorer_url("https://cexplorer.io/tx/{hash}").is_ok());
+        assert!(validate_explorer_url("https://cexplorer.io/tx/").is_err());
+    }
+
+    #[test]
+    fn payment_matching_ignores_delegation_part() {
+        let payment = ShelleyPaymentPart::key_hash([1; 28].into());
+
+        let enterprise: Address = ShelleyAddress::new(
+            Network::Testnet,
+            payment.clone(),
+            ShelleyDelegationPart::Null,
+        )
+        .into();
+        let base: Address = ShelleyAddress::new(
+            Network::Testnet,
+            payment,
+            ShelleyDelegationPart::key_hash([2; 28].into()),
+        )
+        .into();
+
+        assert_ne!(
+            address_pattern(&enterprise, AddressMatch::Full),
+            address_pattern(&base, AddressMatch::Full)
+        );
+        assert_eq!(
+            address_pattern(&enterprise, AddressMatch::Payment),
+            address_pattern(&base, AddressMatch::Payment)
+        );
+        assert_eq!(address_pattern(&base, AddressMatch::Payment), vec![1; 28]);
+    }
+
+    #[test]
+    fn providers_table_as_markdown() {
+        let provider = |name: &str, is_default| Provider {
+            is_default: Some(is_default),
+            ..Provider::test(name)
+        };
+
+        let table = providers_table(
+            &[provider("local", true), provider("preview", false)],
+            TableStyle::Markdown,
+        );
+
+        assert_eq!(
+            table.to_string(),
+            [
+                r#"| Name    | Is default? | Parameters                                    |"#,
+                r#"|---------|-------------|-----------------------------------------------|"#,
+                r#"| local   | true        | {"headers":null,"url":"http://local:50051"}   |"#,
+                r#"| preview | false       | {"headers":null,"url":"http://preview:50051"} |"#,
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn parses_headers() {
+        assert_eq!(
+            parse_header("dmtr-api-key: dmtr_abc").unwrap(),
+            ("dmtr-api-key".to_string(), "dmtr_abc".to_string())
+        );
+        assert_eq!(
+            parse_header("x-url:http://a:1").unwrap().1,
+            "http://a:1".to_string()
+        );
+        assert!(parse_header("no-value").is_err());
+        assert!(parse_header(":value").is_err());
+    }
+
+    #[test]
+    fn keeps_fallback_chains_to_one_kind() {
+        let provider = |name: &str, kind: ProviderKind, fallback: Option<&str>| Provider {
+            kind: Some(kind),
+            fallback: fallback.map(String::from),
+            ..Provider::test(name)
+        };
+
+        let providers = vec![
+            provider("preview", ProviderKind::Utxorpc, Some("backup")),
+            provider("backup", ProviderKind::Utxorpc, Some("ogmios")),
+            provider("ogmios", ProviderKind::Ogmios, Some("last")),
+            provider("last", ProviderKind::Utxorpc, None),
+        ];
+
+        let names: Vec<_> = fallback_chain(&providers, &providers[0])
+            .iter()
+            .map(|x| x.name())
+            .collect();
+        assert_eq!(names, vec!["backup"]);
+
+        assert!(validate_fallback(&providers, &providers[0]).is_ok());
+        assert!(validate_fallback(&providers, &providers[1]).is_err());
+        assert!(validate_fallback(&providers, &providers[2]).is_err());
+
+        // fallbacks given on the command line go through the same checks
+        assert!(check_fallback(&providers[0], &providers[3]).is_ok());
+        assert!(check_fallback(&providers[0], &providers[2]).is_err());
+
+        let mainnet = Provider {
+            is_testnet: Some(false),
+            ..providers[3].clone()
+        };
+        assert!(check_fallback(&providers[0], &mainnet).is_err());
+    }
+
+    #[tokio::test]
+    async fn fails_over_on_transport_errors_only() {
+        let provider = |name: &str, fallback: Option<&str>| Provider {
+            fallback: fallback.map(String::from),
+            ..Provider::test(name)
+        };
+
+        let providers = vec![
+            provider("preview", Some("backup")),
+            provider("backup", Some("last")),
+            provider("last", Some("preview")),
+        ];
+
+        let mut primary = providers[0].clone();
+        primary.failover = fallback_chain(&providers, &primary);
+
+        let names: Vec<_> = primary.failover.iter().map(|x| x.name()).collect();
+        assert_eq!(names, vec!["backup", "last"]);
+        assert!(validate_fallback(&providers, &provider("preview", Some("backup"))).is_err());
+        assert!(validate_fallback(&providers[1..], &provider("backup", Some("last"))).is_ok());
+        assert!(validate_fallback(&providers, &provider("preview", Some("missing"))).is_err());
+
+        let mut mainnet = provider("mainnet", Some("last"));
+        mainnet.is_testnet = Some(false);
+        assert!(validate_fallback(&providers[1..], &mainnet).is_err());
+
+        let unreachable = || -> Result<()> {
+            Err(
+                anyhow!(std::io::Error::from(std::io::ErrorKind::ConnectionRefused))
+                    .context("failed to connect"),
+            )
+        };
+
+        let mut failover = Failover::new(&primary, "read_tip");
+        assert!(failover.settle(unreachable()).await.is_none());
+        assert_eq!(failover.provider().name(), "backup");
+
+        let rejected = Err::<(), _>(anyhow!(Rejected("bad tx".into())));
+        assert!(failover.settle(rejected).await.unwrap().is_err());
+        assert_eq!(failover.provider().name(), "backup");
+
+        assert!(failover.settle(unreachable()).await.is_none());
+        assert_eq!(failover.provider().name(), "last");
+        assert!(failover.settle(unreachable()).await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_before_failing_over() {
+        let mut primary = Provider {
+            retry: RetryPolicy::update(None, Some(3), Some(1), None),
+            fallback: Some("backup".into()),
+            ..Provider::test("preview")
+        };
+        primary.failover = vec![Provider::test("backup")];
+
+        let rate_limited = || -> Result<()> {
+            Err(anyhow!(tx3_sdk::trp::Error::HttpError(
+                429,
+                "slow down".into()
+            )))
+        };
+        let bad_request =
+            || -> Result<()> { Err(anyhow!(tx3_sdk::trp::Error::HttpError(400, "bad".into()))) };
+
+        let mut failover = Failover::new(&primary, "trp_resolve");
+        assert!(failover.settle(bad_request()).await.is_some());
+
+        let mut failover = Failover::new(&primary, "trp_resolve");
+        assert!(failover.settle(rate_limited()).await.is_none());
+        assert!(failover.settle(rate_limited()).await.is_none());
+        assert_eq!(failover.provider().name(), "preview");
+
+        // out of attempts, and a rate limit isn't worth failing over for
+        assert!(failover.settle(rate_limited()).await.unwrap().is_err());
+
+        let unreachable = || -> Result<()> {
+            Err(anyhow!(std::io::Error::from(
+                std::io::ErrorKind::ConnectionRefused
+            )))
+        };
+
+        let mut failover = Failover::new(&primary, "read_tip");
+        assert!(failover.settle(unreachable()).await.is_none());
+        assert!(failover.settle(unreachable()).await.is_none());
+        assert_eq!(failover.provider().name(), "preview");
+        assert!(failover.settle(unreachable()).await.is_none());
+        assert_eq!(failover.provider().name(), "backup");
 
-    fn to_json(&self) {
-        println!("{}", serde_json::to_string_pretty(self).unwrap());
+        // the fallback has no retry policy of its own
+        assert!(failover.settle(unreachable()).await.unwrap().is_err());
     }
 }
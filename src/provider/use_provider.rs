@@ -0,0 +1,27 @@
+use clap::Parser;
+use tracing::instrument;
+
+use crate::output::OutputFormatter;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the provider to use by default
+    name: String,
+
+    /// Only replace the default provider of the same network, keeping the
+    /// defaults of other networks.
+    #[arg(long)]
+    keep_other_networks: bool,
+}
+
+#[instrument("use", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
+    let provider = ctx
+        .store
+        .set_default_provider(&args.name, args.keep_other_networks)?
+        .clone();
+
+    // Log, print, and finish
+    provider.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
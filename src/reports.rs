@@ -168,6 +168,16 @@ impl From<tx3_sdk::trp::Error> for ErrorReport {
     }
 }
 
+impl From<crate::tx::common::InsufficientFunds> for ErrorReport {
+    fn from(error: crate::tx::common::InsufficientFunds) -> Self {
+        ErrorReport::new(error.to_string(), "funds".to_string())
+            .with_detail("address", error.missing.address)
+            .with_detail("needed", error.missing.needed.to_string())
+            .with_detail("available", error.available.to_string())
+            .with_help("Fund the wallet or lower the amounts of the transaction.")
+    }
+}
+
 impl From<utxorpc::Error> for ErrorReport {
     fn from(error: utxorpc::Error) -> Self {
         match error {
@@ -191,6 +201,16 @@ impl From<String> for ErrorReport {
 impl From<anyhow::Error> for ErrorReport {
     fn from(error: anyhow::Error) -> Self {
         // Try to downcast to specific error types first
+        if error
+            .downcast_ref::<crate::tx::common::InsufficientFunds>()
+            .is_some()
+        {
+            let error = error
+                .downcast::<crate::tx::common::InsufficientFunds>()
+                .unwrap();
+            return ErrorReport::from(error);
+        }
+
         if error.downcast_ref::<tx3_sdk::trp::Error>().is_some() {
             let error = error.downcast::<tx3_sdk::trp::Error>().unwrap();
             return ErrorReport::from(error);
@@ -47,7 +47,7 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
         .collect::<Result<Vec<(Vec<u8>, u64)>>>()?;
 
     let blocks = provider.fetch_block(refs).await?;
-    blocks.output(&ctx.output_format);
+    blocks.output(&ctx.output_format, ctx.output_select())?;
 
     Ok(())
 }
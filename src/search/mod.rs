@@ -1,6 +1,7 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use comfy_table::Table;
+use serde_json::Value;
 use tracing::instrument;
 use utxorpc::{
     spec::{
@@ -10,9 +11,10 @@ use utxorpc::{
     ChainBlock,
 };
 
-use crate::output::OutputFormatter;
+use crate::output::{OutputFormatter, TableStyle};
 
 mod block;
+mod pparams;
 mod transaction;
 
 #[derive(Parser)]
@@ -28,6 +30,9 @@ enum Commands {
 
     /// fetch transaction
     Transaction(transaction::Args),
+
+    /// fetch current protocol parameters
+    Pparams(pparams::Args),
 }
 
 #[instrument("search", skip_all)]
@@ -35,11 +40,12 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
     match args.command {
         Commands::Block(args) => block::run(args, ctx).await,
         Commands::Transaction(args) => transaction::run(args, ctx).await,
+        Commands::Pparams(args) => pparams::run(args, ctx).await,
     }
 }
 
-fn cardano_tx_table(block_hash: Option<Vec<u8>>, tx: &[Tx]) -> Table {
-    let mut table = Table::new();
+fn cardano_tx_table(block_hash: Option<Vec<u8>>, tx: &[Tx], style: TableStyle) -> Table {
+    let mut table = crate::output::table(style);
     table.set_header(vec![
         "Block",
         "",
@@ -90,7 +96,7 @@ fn cardano_tx_table(block_hash: Option<Vec<u8>>, tx: &[Tx]) -> Table {
 }
 
 impl OutputFormatter for Vec<ChainBlock<utxorpc::spec::cardano::Block>> {
-    fn to_table(&self) {
+    fn to_table(&self, style: TableStyle) {
         for block in self {
             if let Some(block) = &block.parsed {
                 if block.header.is_none() {
@@ -100,34 +106,25 @@ impl OutputFormatter for Vec<ChainBlock<utxorpc::spec::cardano::Block>> {
                 let header = block.header.as_ref().unwrap();
 
                 if let Some(body) = &block.body {
-                    let table = cardano_tx_table(Some(header.hash.clone().into()), &body.tx);
+                    let table = cardano_tx_table(Some(header.hash.clone().into()), &body.tx, style);
                     println!("{table}");
                 }
             }
         }
     }
 
-    fn to_json(&self) {
+    fn to_json(&self) -> Result<Value> {
         let blocks = self
             .iter()
             .flat_map(|x| x.parsed.as_ref())
             .collect::<Vec<_>>();
 
-        let result = serde_json::to_value(blocks);
-        if let Err(err) = result {
-            eprintln!("{err}");
-            return;
-        }
-
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result.unwrap()).unwrap()
-        );
+        serde_json::to_value(blocks).context("serializing the blocks")
     }
 }
 
 impl OutputFormatter for Vec<query::AnyChainBlock> {
-    fn to_table(&self) {
+    fn to_table(&self, style: TableStyle) {
         for block in self {
             if let Some(chain) = &block.chain {
                 match chain {
@@ -138,7 +135,7 @@ impl OutputFormatter for Vec<query::AnyChainBlock> {
                         let header = block.header.as_ref().unwrap();
                         if let Some(body) = &block.body {
                             let table =
-                                cardano_tx_table(Some(header.hash.clone().into()), &body.tx);
+                                cardano_tx_table(Some(header.hash.clone().into()), &body.tx, style);
                             println!("{table}");
                         }
                     }
@@ -147,44 +144,27 @@ impl OutputFormatter for Vec<query::AnyChainBlock> {
         }
     }
 
-    fn to_json(&self) {
-        let result = serde_json::to_value(self);
-        if let Err(err) = result {
-            eprintln!("{err}");
-            return;
-        }
-
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result.unwrap()).unwrap()
-        );
+    fn to_json(&self) -> Result<Value> {
+        serde_json::to_value(self).context("serializing the blocks")
     }
 }
 
 impl OutputFormatter for utxorpc::ChainTx<utxorpc::spec::cardano::Tx> {
-    fn to_table(&self) {
+    fn to_table(&self, style: TableStyle) {
         if let Some(parsed) = &self.parsed {
             let table = cardano_tx_table(
                 self.block_ref.as_ref().map(|b| b.hash.clone().into()),
                 std::slice::from_ref(parsed),
+                style,
             );
             println!("{table}");
         }
     }
 
-    fn to_json(&self) {
-        if let Some(tx) = &self.parsed {
-            let result = serde_json::to_value(tx);
-
-            if let Err(err) = result {
-                eprintln!("{err}");
-                return;
-            }
-
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&result.unwrap()).unwrap()
-            );
+    fn to_json(&self) -> Result<Value> {
+        match &self.parsed {
+            Some(tx) => serde_json::to_value(tx).context("serializing the tx"),
+            None => Ok(Value::Null),
         }
     }
 }
@@ -0,0 +1,154 @@
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use serde_json::Value;
+use tracing::instrument;
+use utxorpc::spec::cardano::{PParams, RationalNumber};
+
+use crate::{
+    output::{OutputFormatter, TableStyle},
+    utils::format_bigint_opt,
+};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the provider to use. If undefined, will use default
+    #[arg(long, help = "Name of the provider to use")]
+    provider: Option<String>,
+}
+
+#[instrument(skip_all, name = "pparams")]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
+    let provider = match args.provider {
+        Some(name) => ctx.store.find_provider(&name),
+        None => ctx.store.default_provider(),
+    };
+
+    let Some(provider) = provider else {
+        bail!("Provider not found")
+    };
+
+    let params = provider.read_params().await?;
+    params.output(&ctx.output_format, ctx.output_select())?;
+
+    Ok(())
+}
+
+fn rational(value: &Option<RationalNumber>) -> String {
+    match value {
+        Some(x) => format!("{}/{}", x.numerator, x.denominator),
+        None => "-".to_string(),
+    }
+}
+
+/// The parameters that go into fees and deposits, in the order they are
+/// usually looked up.
+fn rows(params: &PParams) -> Vec<(&'static str, String)> {
+    let prices = params.prices.clone().unwrap_or_default();
+
+    vec![
+        (
+            "Protocol Version",
+            params
+                .protocol_version
+                .as_ref()
+                .map_or("-".to_string(), |x| format!("{}.{}", x.major, x.minor)),
+        ),
+        (
+            "Min Fee Coefficient",
+            format_bigint_opt(&params.min_fee_coefficient),
+        ),
+        (
+            "Min Fee Constant",
+            format_bigint_opt(&params.min_fee_constant),
+        ),
+        (
+            "Min Fee Script Ref (per byte)",
+            rational(&params.min_fee_script_ref_cost_per_byte),
+        ),
+        ("Price Memory", rational(&prices.memory)),
+        ("Price Steps", rational(&prices.steps)),
+        (
+            "Coins Per UTxO Byte",
+            format_bigint_opt(&params.coins_per_utxo_byte),
+        ),
+        ("Max Tx Size", params.max_tx_size.to_string()),
+        ("Max Value Size", params.max_value_size.to_string()),
+        (
+            "Collateral Percentage",
+            params.collateral_percentage.to_string(),
+        ),
+        (
+            "Stake Key Deposit",
+            format_bigint_opt(&params.stake_key_deposit),
+        ),
+        ("Pool Deposit", format_bigint_opt(&params.pool_deposit)),
+        ("DRep Deposit", format_bigint_opt(&params.drep_deposit)),
+        (
+            "Governance Action Deposit",
+            format_bigint_opt(&params.governance_action_deposit),
+        ),
+    ]
+}
+
+impl OutputFormatter for PParams {
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
+
+        table.set_header(vec!["Parameter", "Value"]);
+        for (name, value) in rows(self) {
+            table.add_row(vec![name.to_string(), value]);
+        }
+
+        println!("{table}");
+    }
+
+    fn to_json(&self) -> Result<Value> {
+        serde_json::to_value(self).context("serializing the pparams")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utxorpc::spec::cardano::{big_int, BigInt, ExPrices, ProtocolVersion};
+
+    use super::*;
+
+    #[test]
+    fn lists_fee_and_deposit_params() {
+        let int = |x: i64| {
+            Some(BigInt {
+                big_int: Some(big_int::BigInt::Int(x)),
+            })
+        };
+
+        let params = PParams {
+            min_fee_coefficient: int(44),
+            min_fee_constant: int(155_381),
+            stake_key_deposit: int(2_000_000),
+            prices: Some(ExPrices {
+                memory: Some(RationalNumber {
+                    numerator: 577,
+                    denominator: 10_000,
+                }),
+                steps: None,
+            }),
+            protocol_version: Some(ProtocolVersion {
+                major: 10,
+                minor: 0,
+            }),
+            max_tx_size: 16_384,
+            ..Default::default()
+        };
+
+        let rows = rows(&params);
+        let value = |name: &str| rows.iter().find(|(x, _)| *x == name).unwrap().1.clone();
+
+        assert_eq!(value("Protocol Version"), "10.0");
+        assert_eq!(value("Min Fee Coefficient"), "44");
+        assert_eq!(value("Min Fee Constant"), "155381");
+        assert_eq!(value("Price Memory"), "577/10000");
+        assert_eq!(value("Price Steps"), "-");
+        assert_eq!(value("Stake Key Deposit"), "2000000");
+        assert_eq!(value("Max Tx Size"), "16384");
+    }
+}
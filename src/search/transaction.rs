@@ -30,7 +30,7 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
 
     match provider.fetch_tx(hash).await? {
         Some(v) => {
-            v.output(&ctx.output_format);
+            v.output(&ctx.output_format, ctx.output_select())?;
         }
         None => bail!("transaction hash not found"),
     }
@@ -0,0 +1,316 @@
+use std::{marker::PhantomData, net::IpAddr};
+
+use anyhow::{bail, Context as _, Result};
+use clap::Parser;
+use http::{header::AUTHORIZATION, HeaderValue, Request, Response, StatusCode};
+use jsonrpsee::{
+    server::Server,
+    types::{ErrorObject, ErrorObjectOwned},
+    RpcModule,
+};
+use pallas::ledger::addresses::Address;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tower_http::validate_request::{ValidateRequest, ValidateRequestHeaderLayer};
+use tracing::{info, instrument};
+
+use crate::{
+    provider::{
+        metrics,
+        types::{AddressMatch, Provider},
+    },
+    store::Store,
+};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Port to listen on
+    #[arg(long, default_value_t = 8164)]
+    port: u16,
+
+    /// Address to bind the server to
+    #[arg(long, default_value = "127.0.0.1")]
+    host: IpAddr,
+
+    /// Name of the provider to use. If undefined, will use default
+    #[arg(long)]
+    provider: Option<String>,
+
+    /// Token required as `Authorization: Bearer <token>` on every request
+    #[arg(long, env = "CSHELL_SERVE_TOKEN")]
+    token: Option<String>,
+}
+
+/// Rejects HTTP requests that don't carry the expected bearer token.
+struct BearerToken<ResBody> {
+    expected: HeaderValue,
+    _body: PhantomData<fn() -> ResBody>,
+}
+
+impl<ResBody> BearerToken<ResBody> {
+    fn new(token: &str) -> Result<Self> {
+        let expected = HeaderValue::from_str(&format!("Bearer {token}"))
+            .context("invalid characters in token")?;
+
+        Ok(Self {
+            expected,
+            _body: PhantomData,
+        })
+    }
+}
+
+impl<ResBody> Clone for BearerToken<ResBody> {
+    fn clone(&self) -> Self {
+        Self {
+            expected: self.expected.clone(),
+            _body: PhantomData,
+        }
+    }
+}
+
+impl<B, ResBody: Default> ValidateRequest<B> for BearerToken<ResBody> {
+    type ResponseBody = ResBody;
+
+    fn validate(&mut self, request: &mut Request<B>) -> Result<(), Response<Self::ResponseBody>> {
+        if request.headers().get(AUTHORIZATION) == Some(&self.expected) {
+            return Ok(());
+        }
+
+        let mut response = Response::new(ResBody::default());
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+
+        Err(response)
+    }
+}
+
+struct ServeContext {
+    provider: Provider,
+    store: Store,
+}
+
+#[derive(Deserialize, Default)]
+struct AddressParams {
+    wallet: Option<String>,
+    address: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SubmitParams {
+    cbor: String,
+}
+
+const INVALID_PARAMS_CODE: i32 = -32602;
+const SERVER_ERROR_CODE: i32 = -32000;
+
+fn invalid_params(message: impl ToString) -> ErrorObjectOwned {
+    ErrorObject::owned(INVALID_PARAMS_CODE, message.to_string(), None::<()>)
+}
+
+fn server_error(err: anyhow::Error) -> ErrorObjectOwned {
+    ErrorObject::owned(SERVER_ERROR_CODE, format!("{err:#}"), None::<()>)
+}
+
+impl ServeContext {
+    /// Picks the address a request refers to: an explicit bech32 address, the
+    /// address of a named wallet or, when neither is given, the address of the
+    /// default wallet. Wallets are picked as the CLI commands pick them.
+    fn resolve_address(&self, params: &AddressParams) -> Result<Address, ErrorObjectOwned> {
+        let is_testnet = self.provider.is_testnet();
+
+        match (&params.address, &params.wallet) {
+            (Some(_), Some(_)) => Err(invalid_params("use either `address` or `wallet`")),
+            (Some(address), None) => {
+                Address::from_bech32(address).map_err(|_| invalid_params("invalid bech32 address"))
+            }
+            (None, Some(name)) => self
+                .store
+                .find_wallet(name)
+                .ok_or_else(|| invalid_params(format!("wallet '{name}' not found")))?
+                .address(is_testnet)
+                .map_err(server_error),
+            (None, None) => self
+                .store
+                .default_wallet()
+                .ok_or_else(|| invalid_params("no default wallet configured"))?
+                .address(is_testnet)
+                .map_err(server_error),
+        }
+    }
+
+    async fn tip(&self) -> Result<Value> {
+        let tip = self.provider.read_tip().await?;
+
+        Ok(match tip {
+            Some(tip) => json!({
+                "slot": tip.slot,
+                "hash": hex::encode(tip.hash),
+                "height": tip.height,
+            }),
+            None => Value::Null,
+        })
+    }
+}
+
+fn build_module(context: ServeContext) -> Result<RpcModule<ServeContext>> {
+    let mut module = RpcModule::new(context);
+
+    module.register_async_method("balance", |params, ctx, _| async move {
+        let params: Option<AddressParams> = params.parse()?;
+        let address = ctx.resolve_address(&params.unwrap_or_default())?;
+
+        let balance = ctx
+            .provider
+            .get_balance(&address, AddressMatch::Full)
+            .await
+            .map_err(server_error)?;
+
+        serde_json::to_value(balance).map_err(|err| server_error(err.into()))
+    })?;
+
+    module.register_async_method("utxos", |params, ctx, _| async move {
+        let params: Option<AddressParams> = params.parse()?;
+        let address = ctx.resolve_address(&params.unwrap_or_default())?;
+
+        let utxos = ctx
+            .provider
+            .get_detailed_balance(&address, AddressMatch::Full)
+            .await
+            .map_err(server_error)?;
+
+        serde_json::to_value(utxos).map_err(|err| server_error(err.into()))
+    })?;
+
+    module.register_async_method("tip", |_, ctx, _| async move {
+        ctx.tip().await.map_err(server_error)
+    })?;
+
+    module.register_async_method("submit", |params, ctx, _| async move {
+        let params: SubmitParams = params.parse()?;
+        let cbor = hex::decode(&params.cbor).map_err(|_| invalid_params("invalid cbor hex"))?;
+
+        let hash = ctx.provider.submit(&cbor).await.map_err(server_error)?;
+
+        Ok::<_, ErrorObjectOwned>(json!({ "hash": hex::encode(hash) }))
+    })?;
+
+    module.register_method("metrics", |_, _, _| {
+        serde_json::to_value(metrics::snapshot()).map_err(|err| server_error(err.into()))
+    })?;
+
+    Ok(module)
+}
+
+#[instrument("serve", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
+    let provider = match args.provider {
+        Some(name) => ctx.store.find_provider(&name),
+        None => ctx.store.default_provider(),
+    };
+
+    let Some(provider) = provider else {
+        bail!("Provider not found")
+    };
+
+    let module = build_module(ServeContext {
+        provider: provider.clone(),
+        store: ctx.store.clone(),
+    })?;
+
+    let auth = args
+        .token
+        .as_deref()
+        .map(BearerToken::new)
+        .transpose()?
+        .map(ValidateRequestHeaderLayer::custom);
+
+    let http_middleware = tower::ServiceBuilder::new().option_layer(auth);
+
+    let server = Server::builder()
+        .set_http_middleware(http_middleware)
+        .build((args.host, args.port))
+        .await
+        .context("starting json-rpc server")?;
+
+    info!("listening on {}", server.local_addr()?);
+
+    let handle = server.start(module);
+    handle.stopped().await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::types::{Derivation, Wallet};
+
+    fn context() -> ServeContext {
+        let (_, mut alice) =
+            Wallet::try_from("alice", "", true, true, Derivation::Root, None).unwrap();
+        let (_, bob) = Wallet::try_from("bob", "", false, true, Derivation::Root, None).unwrap();
+        alice.is_default = true;
+
+        ServeContext {
+            provider: Provider {
+                url: "http://localhost:50051".into(),
+                is_default: Some(true),
+                ..Provider::test("local")
+            },
+            store: Store::in_memory(vec![alice, bob], vec![]),
+        }
+    }
+
+    #[test]
+    fn resolves_request_addresses() {
+        let ctx = context();
+        let wallets = ctx.store.wallets();
+        let alice = wallets[0].address(true).unwrap();
+        let bob = wallets[1].address(true).unwrap();
+
+        let resolve = |wallet: Option<&str>, address: Option<&str>| {
+            ctx.resolve_address(&AddressParams {
+                wallet: wallet.map(String::from),
+                address: address.map(String::from),
+            })
+        };
+
+        assert_eq!(resolve(None, None).unwrap(), alice);
+        assert_eq!(resolve(Some("bob"), None).unwrap(), bob);
+        assert_eq!(resolve(None, Some(&bob.to_string())).unwrap(), bob);
+
+        assert!(resolve(Some("carol"), None).is_err());
+        assert!(resolve(None, Some("addr_invalid")).is_err());
+        assert!(resolve(Some("bob"), Some(&bob.to_string())).is_err());
+    }
+
+    #[test]
+    fn bearer_token_guards_requests() {
+        let mut auth = BearerToken::<String>::new("secret").unwrap();
+
+        let mut request = |header: Option<&str>| {
+            let mut builder = Request::builder();
+            if let Some(header) = header {
+                builder = builder.header(AUTHORIZATION, header);
+            }
+            match auth.validate(&mut builder.body(()).unwrap()) {
+                Ok(()) => StatusCode::OK,
+                Err(response) => response.status(),
+            }
+        };
+
+        assert_eq!(request(Some("Bearer secret")), StatusCode::OK);
+
+        for header in [None, Some("Bearer other"), Some("secret")] {
+            assert_eq!(request(header), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_submit_params() {
+        let module = build_module(context()).unwrap();
+
+        let result: Result<Value, _> = module.call("submit", [json!({ "cbor": "zz" })]).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+use super::StoreInner;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Only report what would change, without rewriting the store
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[instrument("migrate", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
+    let path = ctx.store.path();
+
+    let changes = match std::fs::read_to_string(path) {
+        Ok(original) => {
+            let (migrated, changes) = migrate(&original)?;
+
+            if !changes.is_empty() && !args.dry_run {
+                std::fs::copy(path, backup_path(path)).context("backing up store")?;
+                std::fs::write(path, migrated).context("writing migrated store")?;
+            }
+
+            changes
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => vec![],
+        Err(err) => return Err(err).context("reading store"),
+    };
+
+    let backup = (!changes.is_empty() && !args.dry_run).then(|| backup_path(path));
+
+    match ctx.output_format {
+        OutputFormat::Json => {
+            crate::output::print_json(
+                &json!({
+                    "path": path,
+                    "changes": changes,
+                    "backup": backup,
+                }),
+                ctx.output_select(),
+            )?;
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            if changes.is_empty() {
+                println!("Store at {} is already up to date.", path.display());
+                return Ok(());
+            }
+
+            for change in &changes {
+                println!("  * {change}");
+            }
+
+            match backup {
+                Some(backup) => println!("Store migrated, original saved at {}.", backup.display()),
+                None => println!("Dry run, store left untouched."),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    backup.into()
+}
+
+/// Loads the store contents filling in defaults for missing fields, and
+/// returns them serialized in the current format along with a description of
+/// every field that changed on the way.
+pub fn migrate(contents: &str) -> Result<(String, Vec<String>)> {
+    let store: StoreInner = toml::from_str(contents).context("parsing store")?;
+    let migrated = toml::to_string(&store)?;
+
+    let before: Table = toml::from_str(contents)?;
+    let after: Table = toml::from_str(&migrated)?;
+
+    let mut changes = vec![];
+    diff_entries("wallet", &before, &after, "wallets", &mut changes);
+    diff_entries("provider", &before, &after, "providers", &mut changes);
+
+    Ok((migrated, changes))
+}
+
+fn diff_entries(kind: &str, before: &Table, after: &Table, key: &str, changes: &mut Vec<String>) {
+    let entries = |table: &Table| match table.get(key) {
+        Some(Value::Array(entries)) => entries.clone(),
+        _ => vec![],
+    };
+
+    for (old, new) in entries(before).iter().zip(entries(after).iter()) {
+        let (Some(old), Some(new)) = (old.as_table(), new.as_table()) else {
+            continue;
+        };
+
+        let name = new.get("name").and_then(Value::as_str).unwrap_or_default();
+
+        for (field, value) in new {
+            match old.get(field) {
+                None => changes.push(format!("{kind} '{name}': added `{field}` = {value}")),
+                Some(previous) if previous != value => changes.push(format!(
+                    "{kind} '{name}': updated `{field}` from {previous} to {value}"
+                )),
+                _ => {}
+            }
+        }
+
+        for field in old.keys().filter(|field| !new.contains_key(*field)) {
+            changes.push(format!("{kind} '{name}': dropped `{field}`"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_STORE: &str = r#"
+wallets = []
+
+[[providers]]
+name = "preview"
+url = "https://preview.utxorpc-v0.demeter.run"
+is_default = true
+is_testnet = true
+"#;
+
+    #[test]
+    fn migrates_old_provider() {
+        let (migrated, changes) = migrate(OLD_STORE).unwrap();
+
+        assert_eq!(
+            changes,
+            vec![r#"provider 'preview': added `type` = "Provider""#.to_string()]
+        );
+
+        let store: StoreInner = toml::from_str(&migrated).unwrap();
+        assert_eq!(store.providers[0].name(), "preview");
+        assert!(store.providers[0].trp_url.is_none());
+
+        // Migrating again is a no-op
+        let (remigrated, changes) = migrate(&migrated).unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(remigrated, migrated);
+    }
+}
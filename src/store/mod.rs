@@ -0,0 +1,577 @@
+use anyhow::bail;
+use chrono::Local;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::instrument;
+
+use crate::{
+    provider::types::{fallback_chain, NetworkName, Provider},
+    utils::{read_toml, write_toml, Name},
+    wallet::types::Wallet,
+};
+
+mod migrate;
+
+/// Environment variable naming a wallet to use instead of the default one.
+pub const WALLET_ENV: &str = "CSHELL_WALLET";
+
+#[derive(Parser)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Rewrite the store in the current config format
+    Migrate(migrate::Args),
+}
+
+#[instrument("store", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    match args.command {
+        Commands::Migrate(args) => migrate::run(args, ctx).await,
+    }
+}
+
+#[derive(Clone)]
+pub struct Store {
+    path: PathBuf,
+    inner: StoreInner,
+    /// Network of the default provider, as chosen with `--network`.
+    network: Option<NetworkName>,
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct StoreInner {
+    pub wallets: Vec<Wallet>,
+    pub providers: Vec<Provider>,
+}
+
+impl Store {
+    pub fn open(path: Option<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.unwrap_or({
+            // Get the home directory.  This is platform-dependent.
+            let home_dir = match std::env::var("HOME") {
+                Ok(path) => PathBuf::from(path),
+                Err(_) => match std::env::var("USERPROFILE") {
+                    Ok(path) => PathBuf::from(path),
+                    Err(_) => {
+                        bail!("Could not determine home directory");
+                    }
+                },
+            };
+
+            // Create the full path to the file.
+            home_dir.join("cshell.toml")
+        });
+        let inner = read_toml(&path)?.unwrap_or_default();
+
+        let mut store = Self {
+            path,
+            inner,
+            network: None,
+        };
+        store.link_fallbacks();
+
+        Ok(store)
+    }
+
+    /// Resolves the failover chain of every provider.
+    fn link_fallbacks(&mut self) {
+        let providers = self.inner.providers.clone();
+
+        for provider in self.inner.providers.iter_mut() {
+            provider.failover = fallback_chain(&providers, provider);
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write(&self) -> anyhow::Result<()> {
+        write_toml(&self.path, &self.inner)
+    }
+
+    /// The wallet commands use when none is named: the one `CSHELL_WALLET`
+    /// names if set, the wallet flagged as default otherwise.
+    pub fn default_wallet(&self) -> Option<&Wallet> {
+        self.overridden_default_wallet(Self::wallet_override())
+    }
+
+    fn overridden_default_wallet(&self, name: Option<String>) -> Option<&Wallet> {
+        match name {
+            Some(name) => self.find_wallet(&name),
+            None => self.flagged_default_wallet(),
+        }
+    }
+
+    /// The wallet flagged as default in the store, whatever `CSHELL_WALLET`
+    /// says. This is what decides how new wallets are stored.
+    pub fn flagged_default_wallet(&self) -> Option<&Wallet> {
+        self.inner.wallets.iter().find(|wallet| wallet.is_default)
+    }
+
+    /// The wallet name `CSHELL_WALLET` holds, if any.
+    pub fn wallet_override() -> Option<String> {
+        std::env::var(WALLET_ENV).ok().filter(|x| !x.is_empty())
+    }
+
+    /// Flags a wallet as the default one, clearing the flag of the rest.
+    pub fn set_default_wallet(&mut self, name: &str) -> anyhow::Result<&Wallet> {
+        let Some(idx) = self
+            .inner
+            .wallets
+            .iter()
+            .position(|w| w.name.to_string() == name)
+        else {
+            bail!("Wallet not found.")
+        };
+
+        for (i, wallet) in self.inner.wallets.iter_mut().enumerate() {
+            if wallet.is_default != (i == idx) {
+                wallet.is_default = i == idx;
+                wallet.modified = Local::now();
+            }
+        }
+
+        self.write()?;
+
+        Ok(&self.inner.wallets[idx])
+    }
+
+    pub fn add_wallet(&mut self, wallet: &Wallet) -> anyhow::Result<()> {
+        self.inner.wallets.push(wallet.clone());
+        self.write()
+    }
+
+    pub fn remove_wallet(&mut self, wallet: Wallet) -> anyhow::Result<()> {
+        match self.inner.wallets.iter().position(|x| *x == wallet) {
+            Some(idx) => {
+                self.inner.wallets.remove(idx);
+                self.write()
+            }
+            None => bail!("Wallet not on store."),
+        }
+    }
+
+    /// Renames a wallet in place, so it keeps its keys, position and default
+    /// flag, writing the store only once.
+    pub fn rename_wallet(&mut self, name: &str, new_name: Name) -> anyhow::Result<&Wallet> {
+        let Some(idx) = self
+            .inner
+            .wallets
+            .iter()
+            .position(|w| w.name.to_string() == name)
+        else {
+            bail!("Wallet not found.")
+        };
+
+        let conflict = self
+            .inner
+            .wallets
+            .iter()
+            .enumerate()
+            .any(|(i, w)| i != idx && w.name.normalized() == new_name.normalized());
+
+        if conflict {
+            bail!(
+                "Wallet with the same or conflicting name '{}' already exists.",
+                new_name
+            )
+        }
+
+        let wallet = &mut self.inner.wallets[idx];
+        wallet.name = new_name;
+        wallet.modified = Local::now();
+
+        self.write()?;
+
+        Ok(&self.inner.wallets[idx])
+    }
+
+    /// Replaces the wallet with the same name, writing the store only once.
+    pub fn update_wallet(&mut self, wallet: Wallet) -> anyhow::Result<()> {
+        match self
+            .inner
+            .wallets
+            .iter()
+            .position(|x| x.name == wallet.name)
+        {
+            Some(idx) => {
+                self.inner.wallets[idx] = wallet;
+                self.write()
+            }
+            None => bail!("Wallet not on store."),
+        }
+    }
+
+    pub fn find_wallet(&self, name: &str) -> Option<&Wallet> {
+        self.inner
+            .wallets
+            .iter()
+            .find(|w| w.name.to_string() == name)
+    }
+
+    pub fn wallets(&self) -> &Vec<Wallet> {
+        &self.inner.wallets
+    }
+
+    /// Makes default providers be picked among the ones of this network.
+    pub fn select_network(&mut self, network: Option<NetworkName>) {
+        self.network = network;
+    }
+
+    /// The provider commands use when none is named: the default of the
+    /// selected network if any, the first provider flagged as default
+    /// otherwise.
+    pub fn default_provider(&self) -> Option<&Provider> {
+        match self.network {
+            Some(network) => self.network_default_provider(network),
+            None => self
+                .inner
+                .providers
+                .iter()
+                .find(|provider| provider.is_default()),
+        }
+    }
+
+    /// The provider to use with a wallet when none is named, picked from
+    /// the network of the wallet unless `--network` selects another.
+    pub fn wallet_provider(&self, wallet: Option<&Wallet>) -> Option<&Provider> {
+        match self.network.or(wallet.and_then(|x| x.network)) {
+            Some(network) => self.network_default_provider(network),
+            None => self.default_provider(),
+        }
+    }
+
+    /// The provider flagged as default among the ones of a network, or its
+    /// only provider.
+    pub fn network_default_provider(&self, network: NetworkName) -> Option<&Provider> {
+        let providers: Vec<_> = self
+            .inner
+            .providers
+            .iter()
+            .filter(|provider| provider.network() == Some(network))
+            .collect();
+
+        match providers.iter().find(|provider| provider.is_default()) {
+            Some(provider) => Some(provider),
+            None if providers.len() == 1 => Some(providers[0]),
+            None => None,
+        }
+    }
+
+    /// Flags a provider as the default one, clearing the flag of the rest,
+    /// or only of those on the same network when `per_network` is set.
+    pub fn set_default_provider(
+        &mut self,
+        name: &str,
+        per_network: bool,
+    ) -> anyhow::Result<&Provider> {
+        let Some(idx) = self.inner.providers.iter().position(|p| p.name() == name) else {
+            bail!("Provider not found.")
+        };
+
+        let network = self.inner.providers[idx].network();
+
+        for (i, provider) in self.inner.providers.iter_mut().enumerate() {
+            if i == idx {
+                provider.is_default = Some(true);
+            } else if !per_network || provider.network() == network {
+                provider.is_default = Some(false);
+            }
+        }
+
+        self.write()?;
+
+        Ok(&self.inner.providers[idx])
+    }
+
+    pub fn providers(&self) -> &Vec<Provider> {
+        &self.inner.providers
+    }
+
+    pub fn add_provider(&mut self, provider: &Provider) -> anyhow::Result<()> {
+        self.inner.providers.push(provider.clone());
+        self.link_fallbacks();
+        self.write()
+    }
+
+    pub fn find_provider(&self, name: &str) -> Option<&Provider> {
+        self.inner.providers.iter().find(|p| p.name() == name)
+    }
+
+    /// Names of the providers falling back to the named one.
+    pub fn fallback_referrers(&self, name: &str) -> Vec<String> {
+        self.inner
+            .providers
+            .iter()
+            .filter(|x| x.fallback.as_deref() == Some(name))
+            .map(|x| x.name())
+            .collect()
+    }
+
+    pub fn remove_provider(&mut self, provider: Provider) -> anyhow::Result<()> {
+        match self.inner.providers.iter().position(|x| *x == provider) {
+            Some(idx) => {
+                self.inner.providers.remove(idx);
+                self.link_fallbacks();
+                self.write()
+            }
+            None => bail!("Provider not on store."),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Store {
+    /// A store that isn't backed by any file, for tests to look wallets and
+    /// providers up in.
+    pub fn in_memory(wallets: Vec<Wallet>, providers: Vec<Provider>) -> Self {
+        let mut store = Self {
+            path: PathBuf::new(),
+            inner: StoreInner { wallets, providers },
+            network: None,
+        };
+        store.link_fallbacks();
+
+        store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::types::Derivation;
+
+    /// A store directory under the system temp dir that is removed when
+    /// dropped, so failing tests don't leave it behind.
+    struct TempStore(PathBuf);
+
+    impl TempStore {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("cshell-{name}-{}", std::process::id())))
+        }
+
+        fn path(&self) -> PathBuf {
+            self.0.join("cshell.toml")
+        }
+    }
+
+    impl Drop for TempStore {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn provider(name: &str, url: &str, is_default: bool) -> Provider {
+        Provider {
+            url: url.into(),
+            is_default: Some(is_default),
+            is_testnet: Some(!url.contains("mainnet")),
+            ..Provider::test(name)
+        }
+    }
+
+    #[test]
+    fn renames_wallets_in_place() {
+        let dir = TempStore::new("rename");
+        let path = dir.path();
+
+        let mut store = Store::open(Some(path.clone())).unwrap();
+        for (name, is_default) in [("alice", true), ("bob", false)] {
+            let (_, wallet) =
+                Wallet::try_from(name, "", is_default, true, Derivation::Root, None).unwrap();
+            store.add_wallet(&wallet).unwrap();
+        }
+        let alice = store.find_wallet("alice").unwrap().clone();
+
+        assert!(store
+            .rename_wallet("alice", "Bob".try_into().unwrap())
+            .is_err());
+        assert!(store
+            .rename_wallet("carol", "dave".try_into().unwrap())
+            .is_err());
+
+        store
+            .rename_wallet("alice", "carol".try_into().unwrap())
+            .unwrap();
+
+        let store = Store::open(Some(path)).unwrap();
+        let carol = store.default_wallet().unwrap();
+        assert_eq!(carol.name.to_string(), "carol");
+        assert_eq!(carol.private_key, alice.private_key);
+        assert_eq!(store.wallets()[0].name, carol.name);
+        assert!(store.find_wallet("alice").is_none());
+    }
+
+    #[test]
+    fn switches_default_wallet() {
+        let dir = TempStore::new("use");
+        let path = dir.path();
+
+        let mut store = Store::open(Some(path.clone())).unwrap();
+        for (name, is_default) in [("alice", true), ("bob", false)] {
+            let (_, wallet) =
+                Wallet::try_from(name, "", is_default, true, Derivation::Root, None).unwrap();
+            store.add_wallet(&wallet).unwrap();
+        }
+
+        assert!(store.set_default_wallet("carol").is_err());
+        store.set_default_wallet("bob").unwrap();
+
+        let store = Store::open(Some(path)).unwrap();
+        assert!(store.find_wallet("bob").unwrap().is_default);
+        assert!(!store.find_wallet("alice").unwrap().is_default);
+    }
+
+    #[test]
+    fn wallet_override_doesnt_change_the_stored_default() {
+        let wallet = |name, is_default| {
+            Wallet::try_from(name, "", is_default, true, Derivation::Root, None)
+                .unwrap()
+                .1
+        };
+
+        let store = Store {
+            path: PathBuf::new(),
+            inner: StoreInner {
+                wallets: vec![wallet("alice", true), wallet("bob", false)],
+                providers: vec![],
+            },
+            network: None,
+        };
+
+        let name = |wallet: Option<&Wallet>| wallet.map(|x| x.name.to_string());
+
+        assert_eq!(
+            name(store.overridden_default_wallet(None)),
+            Some("alice".into())
+        );
+        assert_eq!(
+            name(store.overridden_default_wallet(Some("bob".into()))),
+            Some("bob".into())
+        );
+
+        // naming a missing wallet leaves no default in use, but there still
+        // is one in the store
+        assert_eq!(
+            name(store.overridden_default_wallet(Some("carol".into()))),
+            None
+        );
+        assert_eq!(name(store.flagged_default_wallet()), Some("alice".into()));
+    }
+
+    #[test]
+    fn picks_default_provider_per_network() {
+        let mut store = Store {
+            path: PathBuf::new(),
+            inner: StoreInner {
+                wallets: vec![],
+                providers: vec![
+                    provider("main", "https://mainnet.example", true),
+                    provider("preview", "https://preview.example", true),
+                    provider("preview-backup", "https://preview-2.example", false),
+                    provider("preprod", "https://preprod.example", false),
+                ],
+            },
+            network: None,
+        };
+
+        let name = |provider: Option<&Provider>| provider.map(|x| x.name());
+        assert_eq!(name(store.default_provider()), Some("main".into()));
+
+        let (_, mut wallet) =
+            Wallet::try_from("alice", "", true, true, Derivation::Root, None).unwrap();
+        assert_eq!(
+            name(store.wallet_provider(Some(&wallet))),
+            Some("main".into())
+        );
+
+        wallet.network = Some(NetworkName::Preprod);
+        assert_eq!(
+            name(store.wallet_provider(Some(&wallet))),
+            Some("preprod".into())
+        );
+
+        store.select_network(Some(NetworkName::Preview));
+        assert_eq!(name(store.default_provider()), Some("preview".into()));
+        assert_eq!(
+            name(store.wallet_provider(Some(&wallet))),
+            Some("preview".into())
+        );
+
+        store.inner.providers[1].is_default = Some(false);
+        assert_eq!(name(store.default_provider()), None);
+    }
+
+    #[test]
+    fn switches_default_provider() {
+        let dir = TempStore::new("use-provider");
+        let path = dir.path();
+
+        let mut store = Store::open(Some(path.clone())).unwrap();
+        for provider in [
+            provider("main", "https://mainnet.example", true),
+            provider("preview", "https://preview.example", true),
+            provider("preview-backup", "https://preview-2.example", false),
+        ] {
+            store.add_provider(&provider).unwrap();
+        }
+
+        assert!(store.set_default_provider("missing", false).is_err());
+
+        store.set_default_provider("preview-backup", true).unwrap();
+        let defaults = |store: &Store| -> Vec<_> {
+            store
+                .providers()
+                .iter()
+                .filter(|x| x.is_default())
+                .map(|x| x.name())
+                .collect()
+        };
+        assert_eq!(defaults(&store), vec!["main", "preview-backup"]);
+
+        store.set_default_provider("preview", false).unwrap();
+
+        let store = Store::open(Some(path)).unwrap();
+        assert_eq!(defaults(&store), vec!["preview"]);
+    }
+
+    #[test]
+    fn relinks_fallbacks_on_removal() {
+        let dir = TempStore::new("remove-provider");
+        let path = dir.path();
+
+        let mut store = Store::open(Some(path)).unwrap();
+        for (name, fallback) in [
+            ("preview", Some("backup")),
+            ("backup", Some("last")),
+            ("last", None),
+        ] {
+            let provider = Provider {
+                fallback: fallback.map(String::from),
+                ..Provider::test(name)
+            };
+            store.add_provider(&provider).unwrap();
+        }
+
+        assert_eq!(store.fallback_referrers("last"), vec!["backup"]);
+        assert!(store.fallback_referrers("preview").is_empty());
+
+        let last = store.find_provider("last").unwrap().clone();
+        store.remove_provider(last).unwrap();
+
+        let failover: Vec<_> = store
+            .find_provider("preview")
+            .unwrap()
+            .failover
+            .iter()
+            .map(|x| x.name())
+            .collect();
+        assert_eq!(failover, vec!["backup"]);
+    }
+}
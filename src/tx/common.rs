@@ -1,15 +1,23 @@
 use anyhow::{bail, Context as _, Result};
 use inquire::{Confirm, MultiSelect};
-use pallas::ledger::addresses::Address;
+use pallas::{
+    codec::minicbor,
+    ledger::{addresses::Address, primitives::conway::Tx, traverse::MultiEraTx},
+};
 use serde_json::{json, Value};
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 
 use tx3_sdk::{
     tii::{Invocation, ParamType},
     trp::TxEnvelope,
 };
+use utxorpc::spec::cardano::{big_int, PParams};
 
-use crate::provider::types::Provider;
+use crate::{
+    output::OutputFormat,
+    provider::types::{AddressMatch, Provider},
+    wallet::types::Wallet,
+};
 
 pub fn load_args(
     invocation: &mut Invocation,
@@ -100,7 +108,7 @@ fn inquire_address(ctx: &crate::Context, provider: &Provider, param_key: &str) -
             .iter()
             .find(|x| x.name.to_string() == wallet)
             .unwrap()
-            .address(provider.is_testnet());
+            .address(provider.is_testnet())?;
 
         Ok(value)
     }
@@ -177,38 +185,292 @@ pub fn define_args(
     Ok(())
 }
 
+/// Prints the resolve request (TIR envelope and encoded args) that would be
+/// sent to the TRP server. Args are printed verbatim, so this only runs when
+/// explicitly requested.
+pub fn print_resolve_request(invocation: Invocation, ctx: &crate::Context) -> Result<()> {
+    let request = invocation.into_resolve_request()?;
+
+    match ctx.output_format {
+        OutputFormat::Json => {
+            crate::output::print_json(
+                &json!({
+                    "tir": request.tir,
+                    "args": request.args,
+                }),
+                ctx.output_select(),
+            )?;
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            println!("TIR version: {}", request.tir.version);
+            println!("TIR encoding: {:?}", request.tir.encoding);
+            println!("TIR content: {}", request.tir.content);
+            println!("Args: {}", serde_json::to_string_pretty(&request.args)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Lovelace required by a tx input that the TRP server could not resolve.
+#[derive(Debug, PartialEq)]
+pub struct MissingFunds {
+    pub input: String,
+    pub address: String,
+    pub needed: u64,
+}
+
+impl MissingFunds {
+    /// Extracts the lovelace requirement from an unresolved input error.
+    pub fn detect(err: &anyhow::Error) -> Option<Self> {
+        let Some(tx3_sdk::trp::Error::InputNotResolved(diagnostic)) = err.downcast_ref() else {
+            return None;
+        };
+
+        let needed = diagnostic
+            .query
+            .min_amount
+            .iter()
+            .find(|(asset, _)| asset.is_empty() || asset.eq_ignore_ascii_case("lovelace"))
+            .and_then(|(_, amount)| amount.parse().ok())?;
+
+        Some(Self {
+            input: diagnostic.name.clone(),
+            address: diagnostic.query.address.clone()?,
+            needed,
+        })
+    }
+
+    pub fn with_available(self, available: u64) -> InsufficientFunds {
+        InsufficientFunds {
+            missing: self,
+            available,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InsufficientFunds {
+    pub missing: MissingFunds,
+    pub available: u64,
+}
+
+impl InsufficientFunds {
+    pub fn short(&self) -> u64 {
+        self.missing.needed.saturating_sub(self.available)
+    }
+}
+
+impl std::fmt::Display for InsufficientFunds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "insufficient funds for input `{}`: needed {} lovelace, available {}, short by {}",
+            self.missing.input,
+            self.missing.needed,
+            self.available,
+            self.short(),
+        )
+    }
+}
+
+impl std::error::Error for InsufficientFunds {}
+
 pub async fn resolve_tx(invocation: Invocation, provider: &Provider) -> Result<TxEnvelope> {
     let request = invocation.into_resolve_request()?;
 
-    provider.trp_resolve(request).await
+    let err = match provider.trp_resolve(request).await {
+        Ok(envelope) => return Ok(envelope),
+        Err(err) => err,
+    };
+
+    let Some(missing) = MissingFunds::detect(&err) else {
+        return Err(err);
+    };
+
+    let Ok(address) = Address::from_bech32(&missing.address) else {
+        return Err(err);
+    };
+
+    let Ok(balance) = provider.get_balance(&address, AddressMatch::Full).await else {
+        return Err(err);
+    };
+
+    let available: u64 = balance.coin.parse().unwrap_or_default();
+
+    // enough lovelace means the input failed for other reasons (eg: assets)
+    if available >= missing.needed {
+        return Err(err);
+    }
+
+    Err(missing.with_available(available).into())
 }
 
+/// Bails if the fee of the tx exceeds the given cap (in lovelace).
+pub fn check_max_fee(cbor: &[u8], max_fee: Option<u64>) -> Result<()> {
+    let Some(max_fee) = max_fee else {
+        return Ok(());
+    };
+
+    let tx: Tx = minicbor::decode(cbor).context("decoding resolved tx")?;
+    let fee = tx.transaction_body.fee;
+
+    if fee > max_fee {
+        bail!("tx fee of {fee} lovelace exceeds the max fee of {max_fee} lovelace");
+    }
+
+    Ok(())
+}
+
+/// Bytes a vkey witness adds to a tx: the 32 byte key and the 64 byte
+/// signature, along with their cbor headers.
+const VKEY_WITNESS_SIZE: u64 = 101;
+
+/// Bails if the fee of the tx doesn't cover the linear min fee of the tx
+/// once it holds `missing_witnesses` more signatures, e.g. after attaching a
+/// script that the fee was resolved without.
+pub fn check_min_fee(cbor: &[u8], params: &PParams, missing_witnesses: u64) -> Result<()> {
+    let int = |value: &Option<utxorpc::spec::cardano::BigInt>| match value
+        .as_ref()
+        .and_then(|x| x.big_int.as_ref())
+    {
+        Some(big_int::BigInt::Int(i)) => Ok(*i as u64),
+        _ => bail!("the provider doesn't report the min fee params"),
+    };
+
+    let coefficient = int(&params.min_fee_coefficient)?;
+    let constant = int(&params.min_fee_constant)?;
+
+    let tx: Tx = minicbor::decode(cbor).context("decoding tx")?;
+    let fee = tx.transaction_body.fee;
+
+    let size = cbor.len() as u64 + missing_witnesses * VKEY_WITNESS_SIZE;
+    let min_fee = coefficient * size + constant;
+
+    if fee < min_fee {
+        bail!(
+            "tx fee of {fee} lovelace is below the {min_fee} lovelace it needs once fully signed, resolve it again with a higher fee"
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the `hash#index` refs of the tx outputs paying back to any of the
+/// given addresses, in output order.
+pub fn change_refs(cbor: &[u8], hash: &str, addresses: &[Address]) -> Result<Vec<String>> {
+    let tx = MultiEraTx::decode(cbor).context("decoding submitted tx")?;
+
+    let addresses: Vec<_> = addresses.iter().map(Address::to_vec).collect();
+
+    let refs = tx
+        .outputs()
+        .iter()
+        .enumerate()
+        .filter(|(_, output)| {
+            output
+                .address()
+                .is_ok_and(|address| addresses.contains(&address.to_vec()))
+        })
+        .map(|(index, _)| format!("{hash}#{index}"))
+        .collect();
+
+    Ok(refs)
+}
+
+/// Loads a JSON object mapping wallet names to their spending passwords.
+pub fn load_signer_passwords(path: Option<&Path>) -> Result<HashMap<String, String>> {
+    let Some(path) = path else {
+        return Ok(HashMap::new());
+    };
+
+    let content = std::fs::read_to_string(path).context("reading signer passwords file")?;
+
+    serde_json::from_str(&content)
+        .context("signer passwords file must be a json object of wallet name to password")
+}
+
+fn prompt_password(wallet: &Wallet) -> Result<String> {
+    let password = inquire::Password::new("Password:")
+        .with_help_message(&format!(
+            "The spending password for '{}' wallet:",
+            wallet.name
+        ))
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .prompt()?;
+
+    Ok(password)
+}
+
+/// Gathers the password of every signer before any key gets decrypted, taking
+/// them from the known passwords and prompting only for the missing ones.
+fn collect_passwords(
+    wallets: &[&Wallet],
+    known: &HashMap<String, String>,
+    mut prompt: impl FnMut(&Wallet) -> Result<String>,
+) -> Result<Vec<Option<String>>> {
+    wallets
+        .iter()
+        .map(|wallet| {
+            if wallet.is_unsafe {
+                return Ok(None);
+            }
+
+            match known.get(&wallet.name.to_string()) {
+                Some(password) => Ok(Some(password.clone())),
+                None => prompt(wallet).map(Some),
+            }
+        })
+        .collect()
+}
+
+/// Returns the given signers, or asks which wallets to sign with when none
+/// were given.
+pub fn select_signers(ctx: &crate::Context, signers: Vec<String>) -> Result<Vec<String>> {
+    if !signers.is_empty() {
+        return Ok(signers);
+    }
+
+    let wallet_names: Vec<String> = ctx
+        .store
+        .wallets()
+        .iter()
+        .filter(|wallet| !wallet.is_watch_only())
+        .map(|wallet| wallet.name.to_string())
+        .collect();
+
+    // the default wallet comes selected
+    let default: Vec<usize> = ctx
+        .store
+        .default_wallet()
+        .and_then(|x| {
+            wallet_names
+                .iter()
+                .position(|name| *name == x.name.to_string())
+        })
+        .into_iter()
+        .collect();
+
+    let selected = MultiSelect::new(
+        "What wallet should be used to sign the transaction?",
+        wallet_names,
+    )
+    .with_default(&default)
+    .prompt()?;
+
+    Ok(selected)
+}
+
+/// Signs the tx with the given wallets, as picked by `select_signers`.
 pub async fn sign_tx(
     cbor: &[u8],
     ctx: &crate::Context,
-    signers: Vec<String>,
+    signers: &[String],
     allow_unsafe: bool,
+    passwords: &HashMap<String, String>,
 ) -> Result<Vec<u8>> {
     let mut cbor = cbor.to_vec();
 
-    let signers = if signers.is_empty() {
-        let wallet_names: Vec<String> = ctx
-            .store
-            .wallets()
-            .iter()
-            .map(|wallet| wallet.name.to_string())
-            .collect();
-
-        MultiSelect::new(
-            "What wallet should be used to sign the transaction?",
-            wallet_names,
-        )
-        .prompt()
-        .unwrap_or_default()
-    } else {
-        signers.clone()
-    };
-
     let wallets = signers
         .iter()
         .map(|signer| {
@@ -222,6 +484,10 @@ pub async fn sign_tx(
                 bail!("invalid signer wallet '{signer}'")
             };
 
+            if wallet.is_watch_only() {
+                bail!("wallet '{signer}' is watch-only and can't sign transactions")
+            }
+
             if wallet.is_unsafe && !allow_unsafe {
                 let confirm = Confirm::new(&format!("wallet '{signer}' is unsafe, confirm sign?"))
                     .with_default(false)
@@ -239,20 +505,9 @@ pub async fn sign_tx(
         })
         .collect::<Result<Vec<_>, _>>()?;
 
-    for wallet in wallets {
-        let password = match wallet.is_unsafe {
-            true => None,
-            false => Some(
-                inquire::Password::new("Password:")
-                    .with_help_message(&format!(
-                        "The spending password for '{}' wallet:",
-                        wallet.name
-                    ))
-                    .with_display_mode(inquire::PasswordDisplayMode::Masked)
-                    .prompt()?,
-            ),
-        };
+    let passwords = collect_passwords(&wallets, passwords, prompt_password)?;
 
+    for (wallet, password) in wallets.into_iter().zip(passwords) {
         cbor = wallet.sign(cbor, &password)?;
     }
 
@@ -262,6 +517,217 @@ pub async fn sign_tx(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::wallet::types::Derivation;
+
+    #[test]
+    fn change_refs_match_signer_outputs() {
+        let own = format!("60{}", "bb".repeat(28));
+        let other = format!("60{}", "cc".repeat(28));
+
+        // [{0: [], 1: [[own, 1000000], [other, 2000000], [own, 3000000]], 2: 0}, {}, true, null]
+        let cbor = [
+            "84a300800183",
+            &format!("82581d{own}1a000f4240"),
+            &format!("82581d{other}1a001e8480"),
+            &format!("82581d{own}1a002dc6c0"),
+            "0200a0f5f6",
+        ]
+        .concat();
+        let cbor = hex::decode(cbor).unwrap();
+
+        let own = Address::from_bytes(&hex::decode(own).unwrap()).unwrap();
+
+        assert_eq!(
+            change_refs(&cbor, "ab", &[own]).unwrap(),
+            vec!["ab#0".to_string(), "ab#2".to_string()]
+        );
+        assert!(change_refs(&cbor, "ab", &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn missing_funds_from_unresolved_input() {
+        let diagnostic = serde_json::from_value(json!({
+            "name": "source",
+            "query": {
+                "address": "addr_test1vzqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq",
+                "collateral": false,
+                "minAmount": { "lovelace": "5000000" },
+                "refs": [],
+                "supportMany": true,
+            },
+            "search_space": { "matched": [] },
+        }))
+        .unwrap();
+
+        let err = anyhow::Error::from(tx3_sdk::trp::Error::InputNotResolved(Box::new(diagnostic)));
+
+        let missing = MissingFunds::detect(&err).unwrap();
+        assert_eq!(missing.input, "source");
+        assert_eq!(missing.needed, 5_000_000);
+
+        let shortage = missing.with_available(3_500_000);
+        assert_eq!(shortage.short(), 1_500_000);
+        assert_eq!(
+            shortage.to_string(),
+            "insufficient funds for input `source`: needed 5000000 lovelace, available 3500000, short by 1500000"
+        );
+
+        let err = anyhow::Error::from(tx3_sdk::trp::Error::InvalidTirEnvelope);
+        assert_eq!(MissingFunds::detect(&err), None);
+    }
+
+    /// A TRP endpoint answering every submit the same way, counting the
+    /// submits it got.
+    async fn trp_server(
+        answer: Result<Value, jsonrpsee::types::ErrorObjectOwned>,
+    ) -> (
+        String,
+        jsonrpsee::server::ServerHandle,
+        std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    ) {
+        use std::sync::{atomic::Ordering, Arc};
+
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let mut module = jsonrpsee::RpcModule::new(calls.clone());
+        module
+            .register_method("trp.submit", move |_, calls, _| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                answer.clone()
+            })
+            .unwrap();
+
+        let server = jsonrpsee::server::Server::builder()
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+
+        (url, server.start(module), calls)
+    }
+
+    #[tokio::test]
+    async fn submit_falls_back_to_next_provider() {
+        use jsonrpsee::types::ErrorObject;
+        use std::sync::atomic::Ordering;
+
+        let hash = "ab".repeat(32);
+        let internal_error = Err(ErrorObject::owned(-32603, "node unavailable", None::<()>));
+        let refused = Err(ErrorObject::owned(1, "tx is invalid", None::<()>));
+
+        let (flaky, _flaky, flaky_calls) = trp_server(internal_error).await;
+        let (refusing, _refusing, _) = trp_server(refused).await;
+        let (working, _working, working_calls) = trp_server(Ok(json!({ "hash": hash }))).await;
+        let (spare, _spare, spare_calls) = trp_server(Ok(json!({ "hash": hash }))).await;
+
+        let provider = |name: &str, trp_url: &str| Provider {
+            trp_url: Some(trp_url.to_string()),
+            ..Provider::test(name)
+        };
+
+        let request = tx3_sdk::trp::SubmitParams {
+            tx: tx3_sdk::core::BytesEnvelope {
+                content: "84a0a0f5f6".to_string(),
+                content_type: "hex".to_string(),
+            },
+            witnesses: vec![],
+        };
+
+        let primary = Provider {
+            failover: vec![provider("fallback", &working), provider("spare", &spare)],
+            ..provider("primary", &flaky)
+        };
+
+        let (accepted, response) = primary.trp_submit(request.clone()).await.unwrap();
+        assert_eq!(accepted.name(), "fallback");
+        assert_eq!(response.hash, hash);
+        assert_eq!(flaky_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(working_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(spare_calls.load(Ordering::SeqCst), 0);
+
+        // a tx the endpoint refuses isn't sent to the fallbacks
+        let primary = Provider {
+            failover: vec![provider("fallback", &working)],
+            ..provider("primary", &refusing)
+        };
+
+        let err = primary.trp_submit(request).await.unwrap_err();
+        assert!(format!("{err:#}").contains("tx is invalid"));
+        assert_eq!(working_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn max_fee_guard() {
+        // [{0: [], 1: [], 2: 1_000_000_000_000}, {}, true, null]
+        let cbor = hex::decode("84a300800180021b000000e8d4a51000a0f5f6").unwrap();
+
+        assert!(check_max_fee(&cbor, None).is_ok());
+        assert!(check_max_fee(&cbor, Some(1_000_000_000_000)).is_ok());
+
+        let err = check_max_fee(&cbor, Some(2_000_000)).unwrap_err();
+        assert!(err.to_string().contains("exceeds the max fee"));
+    }
+
+    #[test]
+    fn min_fee_counts_missing_signatures() {
+        let int = |x: i64| {
+            Some(utxorpc::spec::cardano::BigInt {
+                big_int: Some(big_int::BigInt::Int(x)),
+            })
+        };
+
+        let params = PParams {
+            min_fee_coefficient: int(44),
+            min_fee_constant: int(155_381),
+            ..Default::default()
+        };
+
+        // [{0: [], 1: [], 2: 160_000}, {}, true, null], 15 bytes
+        let cbor = hex::decode("84a300800180021a00027100a0f5f6").unwrap();
+
+        // 44 * 15 + 155_381 = 156_041
+        assert!(check_min_fee(&cbor, &params, 0).is_ok());
+
+        // 44 * (15 + 2 * 101) + 155_381 = 164_929
+        let err = check_min_fee(&cbor, &params, 2).unwrap_err();
+        assert!(err.to_string().contains("below the 164929 lovelace"));
+
+        assert!(check_min_fee(&cbor, &PParams::default(), 0).is_err());
+    }
+
+    #[test]
+    fn signer_passwords_fall_back_to_prompt() {
+        let (_, alice) =
+            Wallet::try_from("alice", "alice-pass", false, false, Derivation::Root, None).unwrap();
+        let (_, bob) =
+            Wallet::try_from("bob", "bob-pass", false, false, Derivation::Root, None).unwrap();
+        let (_, carol) =
+            Wallet::try_from("carol", "", false, true, Derivation::Root, None).unwrap();
+
+        let known = HashMap::from([("alice".to_string(), "alice-pass".to_string())]);
+
+        let mut prompted = vec![];
+        let passwords = collect_passwords(&[&alice, &bob, &carol], &known, |wallet| {
+            prompted.push(wallet.name.to_string());
+            Ok("bob-pass".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(
+            passwords,
+            vec![
+                Some("alice-pass".to_string()),
+                Some("bob-pass".to_string()),
+                None
+            ]
+        );
+        assert_eq!(prompted, vec!["bob".to_string()]);
+
+        let failed = collect_passwords(&[&alice, &bob], &HashMap::new(), |_| {
+            bail!("no tty available")
+        });
+        assert!(failed.is_err());
+    }
 
     // The invoke glue cshell owns: `prepare_invocation` (load the .tii + select
     // the tx) → `load_args` (parse `--args-json` → `set_args`) →
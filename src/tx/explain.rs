@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use pallas::ledger::traverse::MultiEraTx;
+use serde_json::{json, Value};
+
+use crate::output::OutputFormat;
+
+/// Narrates the steps of a tx lifecycle. In table mode each step is printed
+/// as soon as it happens; in json mode steps are collected and printed as an
+/// ordered array at the end.
+pub struct Explain {
+    enabled: bool,
+    format: OutputFormat,
+    steps: Vec<Value>,
+}
+
+impl Explain {
+    pub fn new(enabled: bool, format: &OutputFormat) -> Self {
+        Self {
+            enabled,
+            format: format.clone(),
+            steps: vec![],
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn step(&mut self, step: &str, summary: String, detail: Value) {
+        if !self.enabled {
+            return;
+        }
+
+        match self.format {
+            OutputFormat::Table | OutputFormat::Markdown => {
+                println!("[{}] {step}: {summary}", self.steps.len() + 1)
+            }
+            OutputFormat::Json => {}
+        }
+
+        self.steps.push(json!({
+            "step": step,
+            "summary": summary,
+            "detail": detail,
+        }));
+    }
+
+    pub fn into_steps(self) -> Vec<Value> {
+        self.steps
+    }
+}
+
+/// Describes the inputs, outputs and fee of a tx.
+pub fn describe_tx(cbor: &[u8]) -> Result<Value> {
+    let tx = MultiEraTx::decode(cbor).context("decoding tx")?;
+
+    let inputs: Vec<_> = tx
+        .inputs()
+        .iter()
+        .map(|input| format!("{}#{}", input.hash(), input.index()))
+        .collect();
+
+    let outputs: Vec<_> = tx
+        .outputs()
+        .iter()
+        .map(|output| {
+            json!({
+                "address": output.address().map(|x| x.to_string()).ok(),
+                "coin": output.value().coin(),
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "inputs": inputs,
+        "outputs": outputs,
+        "fee": tx.fee(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::types::probe_tx;
+
+    #[test]
+    fn describes_resolved_tx() {
+        let detail = describe_tx(&probe_tx()).unwrap();
+
+        assert_eq!(detail["inputs"], json!([format!("{}#1", "aa".repeat(32))]));
+        assert_eq!(detail["outputs"][0]["coin"], json!(2_000_000));
+        assert!(detail["outputs"][0]["address"]
+            .as_str()
+            .unwrap()
+            .starts_with("addr_test1"));
+        assert_eq!(detail["fee"], json!(170_000));
+    }
+}
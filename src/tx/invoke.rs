@@ -8,13 +8,22 @@ use tx3_sdk::{
     trp::{SubmitParams, TxEnvelope},
 };
 
-use crate::output::OutputFormat;
+use super::{
+    explain::{describe_tx, Explain},
+    spec::InvokeSpec,
+};
+use crate::{output::OutputFormat, provider::types::check_fallback};
 
 #[derive(Parser, Clone)]
 pub struct Args {
     /// Path for TII file describing transaction invoke interface
     #[arg(long)]
-    tii_file: PathBuf,
+    tii_file: Option<PathBuf>,
+
+    /// Path for a json file bundling the TII file, tx template, args, signers
+    /// and provider. Flags take precedence over the values in the file
+    #[arg(long)]
+    spec: Option<PathBuf>,
 
     /// Profile to use for the transaction (as defined in the TII file)
     #[arg(long)]
@@ -36,6 +45,34 @@ pub struct Args {
     #[arg(long)]
     signers: Vec<String>,
 
+    /// Path for a json file mapping signer wallet names to their passwords.
+    /// Signers missing from the file are prompted for interactively
+    #[arg(long, env = "CSHELL_SIGNER_PASSWORDS")]
+    signer_passwords: Option<PathBuf>,
+
+    /// Abort before signing if the resolved tx fee exceeds this amount of
+    /// lovelace
+    #[arg(long)]
+    max_fee: Option<u64>,
+
+    /// Print the TIR and args sent to the TRP server instead of resolving the
+    /// transaction
+    #[arg(long)]
+    print_tir: bool,
+
+    /// Narrate each step of the transaction lifecycle
+    #[arg(long)]
+    explain: bool,
+
+    /// Only print the tx hash
+    #[arg(long)]
+    quiet: bool,
+
+    /// Print the refs of the outputs paying back to the signers, so they can
+    /// be chained into a follow-up transaction
+    #[arg(long)]
+    output_ref: bool,
+
     /// Skip submitting
     #[arg(long)]
     skip_submit: bool,
@@ -47,25 +84,93 @@ pub struct Args {
     /// Name of the provider to use. If undefined, will use default
     #[arg(long)]
     provider: Option<String>,
+
+    /// Providers to submit through, in order, when the previous one fails
+    /// to submit the transaction
+    #[arg(long = "fallback-provider")]
+    fallback_providers: Vec<String>,
 }
 
 #[instrument("invoke", skip_all)]
 pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
-    let provider = match args.provider {
+    let spec = match &args.spec {
+        Some(path) => InvokeSpec::from_file(path)?,
+        None => InvokeSpec::default(),
+    };
+
+    let Some(tii_file) = args.tii_file.or(spec.tii_file) else {
+        bail!("missing TII file, use --tii-file or set it in the spec")
+    };
+
+    let signers = match args.signers.is_empty() {
+        true => spec.signers,
+        false => args.signers,
+    };
+
+    // the network comes from the first signer, or else the default wallet
+    let wallet = signers
+        .first()
+        .and_then(|name| ctx.store.find_wallet(name))
+        .or(ctx.store.default_wallet());
+
+    let provider = match args.provider.or(spec.provider) {
         Some(name) => ctx.store.find_provider(&name),
-        None => ctx.store.default_provider(),
+        None => ctx.store.wallet_provider(wallet),
     };
 
     let Some(provider) = provider else {
         bail!("Provider not found")
     };
 
+    // the given fallbacks take the place of the configured ones for the submit
+    let mut submit_provider = provider.clone();
+    if !args.fallback_providers.is_empty() {
+        submit_provider.failover = args
+            .fallback_providers
+            .iter()
+            .map(|name| {
+                let Some(fallback) = ctx.store.find_provider(name) else {
+                    bail!("Fallback provider '{name}' not found");
+                };
+
+                check_fallback(provider, fallback)?;
+
+                if fallback.trp_url.is_none() {
+                    bail!("fallback provider '{name}' has no TRP url to submit through");
+                }
+
+                Ok(fallback.clone())
+            })
+            .collect::<Result<_>>()?;
+    }
+
+    let passwords = super::common::load_signer_passwords(args.signer_passwords.as_deref())?;
+
+    let mut explain = Explain::new(args.explain, &ctx.output_format);
+
+    explain.step(
+        "provider",
+        format!(
+            "using provider '{}' with TRP endpoint {}",
+            provider.name(),
+            provider.trp_url.as_deref().unwrap_or("-"),
+        ),
+        json!({
+            "name": provider.name(),
+            "trp_url": provider.trp_url,
+        }),
+    );
+
     let mut invocation = super::common::prepare_invocation(
-        &args.tii_file,
-        args.tx_template.as_deref(),
-        args.profile.as_deref(),
+        &tii_file,
+        args.tx_template.or(spec.tx_template).as_deref(),
+        args.profile.or(spec.profile).as_deref(),
     )?;
 
+    if let Some(spec_args) = spec.args {
+        invocation.set_args(spec_args);
+    }
+
     super::common::define_args(
         &mut invocation,
         args.args_json.as_deref(),
@@ -74,39 +179,140 @@ pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
         provider,
     )?;
 
+    if args.print_tir {
+        return super::common::print_resolve_request(invocation, ctx);
+    }
+
     let TxEnvelope { tx, hash } = super::common::resolve_tx(invocation, provider).await?;
 
     let cbor = hex::decode(tx).unwrap();
 
-    let cbor = super::common::sign_tx(&cbor, ctx, args.signers, args.r#unsafe).await?;
+    if explain.is_enabled() {
+        let detail = describe_tx(&cbor)?;
+        explain.step(
+            "resolve",
+            format!(
+                "resolved tx {hash} with {} inputs and {} outputs, fee of {} lovelace",
+                detail["inputs"].as_array().map_or(0, Vec::len),
+                detail["outputs"].as_array().map_or(0, Vec::len),
+                detail["fee"],
+            ),
+            detail,
+        );
+    }
+
+    super::common::check_max_fee(&cbor, args.max_fee)?;
 
-    if !args.skip_submit {
-        provider
-            .trp_submit(SubmitParams {
-                tx: BytesEnvelope {
-                    content: hex::encode(&cbor),
-                    content_type: "hex".to_string(),
-                },
-                witnesses: vec![],
-            })
-            .await?;
+    let signers = super::common::select_signers(ctx, signers)?;
+
+    let cbor = super::common::sign_tx(&cbor, ctx, &signers, args.r#unsafe, &passwords).await?;
+
+    explain.step(
+        "sign",
+        format!("signed by {}", signers.join(", ")),
+        json!({ "signers": signers }),
+    );
+
+    let mut submitted_via = None;
+
+    if args.skip_submit {
+        explain.step(
+            "submit",
+            "skipped".to_string(),
+            json!({ "submitted": false }),
+        );
+    } else {
+        let request = SubmitParams {
+            tx: BytesEnvelope {
+                content: hex::encode(&cbor),
+                content_type: "hex".to_string(),
+            },
+            witnesses: vec![],
+        };
+
+        let (accepted, response) = submit_provider.trp_submit(request).await?;
+
+        // a different hash means the provider submitted some other tx, and
+        // the hash printed below would point nowhere
+        if !response.hash.eq_ignore_ascii_case(&hash) {
+            bail!(
+                "provider '{}' reported tx {} for the submitted tx {hash}",
+                accepted.name(),
+                response.hash
+            );
+        }
+
+        submitted_via = Some(accepted.name());
+
+        explain.step(
+            "submit",
+            format!("submitted tx {hash} through provider '{}'", accepted.name()),
+            json!({ "submitted": true, "provider": accepted.name() }),
+        );
+    }
+
+    let change_refs = match args.output_ref {
+        true => {
+            let addresses: Vec<_> = ctx
+                .store
+                .wallets()
+                .iter()
+                .filter(|wallet| signers.contains(&wallet.name.to_string()))
+                .map(|wallet| wallet.address(provider.is_testnet()))
+                .collect::<Result<_>>()?;
+
+            Some(super::common::change_refs(&cbor, &hash, &addresses)?)
+        }
+        false => None,
+    };
+
+    let explorer_url = match args.skip_submit {
+        true => None,
+        false => provider.explorer_link(&hash),
+    };
+
+    let mut result = json!({
+        "hash": hash,
+        "cbor": hex::encode(&cbor),
+        "explorer_url": explorer_url,
+    });
+
+    if !args.fallback_providers.is_empty() {
+        result["submitted_via"] = json!(submitted_via);
+    }
+
+    if let Some(change_refs) = &change_refs {
+        result["change_refs"] = json!(change_refs);
     }
 
     match ctx.output_format {
+        OutputFormat::Json if explain.is_enabled() => {
+            explain.step("result", format!("tx {hash}"), result.clone());
+
+            crate::output::print_json(&json!(explain.into_steps()), ctx.output_select())?;
+        }
         OutputFormat::Json => {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json!({
-                    "hash": hash,
-                    "cbor": hex::encode(&cbor),
-                }))
-                .unwrap()
-            );
+            crate::output::print_json(&result, ctx.output_select())?;
         }
 
-        OutputFormat::Table => {
+        OutputFormat::Table | OutputFormat::Markdown if args.quiet => {
+            println!("{hash}");
+            for change_ref in change_refs.iter().flatten() {
+                println!("{change_ref}");
+            }
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
             println!("Tx Hash: {}", &hash);
             println!("Tx CBOR: {}", hex::encode(&cbor));
+            if let Some(explorer_url) = explorer_url {
+                println!("Explorer: {explorer_url}");
+            }
+            if let Some(name) = submitted_via.filter(|_| !args.fallback_providers.is_empty()) {
+                println!("Submitted via: {name}");
+            }
+            if let Some(change_refs) = change_refs {
+                println!("Change: {}", change_refs.join(", "));
+            }
         }
     }
 
@@ -1,11 +1,13 @@
 use clap::{Parser, Subcommand};
 use tracing::instrument;
 
-mod common;
+pub mod common;
+mod explain;
 
 mod invoke;
 mod resolve;
 mod sign;
+mod spec;
 mod submit;
 
 #[derive(Parser)]
@@ -39,7 +39,7 @@ pub struct Args {
 pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
     let provider = match args.provider {
         Some(name) => ctx.store.find_provider(&name),
-        None => ctx.store.default_provider(),
+        None => ctx.store.wallet_provider(ctx.store.default_wallet()),
     };
 
     let Some(provider) = provider else {
@@ -66,16 +66,15 @@ pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
 
     match ctx.output_format {
         OutputFormat::Json => {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json!({
+            crate::output::print_json(
+                &json!({
                     "hash": hash,
                     "cbor": hex::encode(&cbor),
-                }))
-                .unwrap()
-            );
+                }),
+                ctx.output_select(),
+            )?;
         }
-        OutputFormat::Table => println!("{}", hex::encode(&cbor)),
+        OutputFormat::Table | OutputFormat::Markdown => println!("{}", hex::encode(&cbor)),
     }
 
     Ok(())
@@ -1,6 +1,5 @@
 use anyhow::{bail, Context, Result};
 use clap::Parser;
-use inquire::MultiSelect;
 use serde_json::json;
 use tracing::instrument;
 
@@ -17,32 +16,77 @@ pub struct Args {
 
     #[arg(long, help = "Wallets that will sign the transaction")]
     signer: Vec<String>,
+
+    /// Name of the provider to check the fee of multisig txs against. If
+    /// undefined, will use the one of the multisig wallet network
+    #[arg(long)]
+    provider: Option<String>,
 }
 
 #[instrument("sign", skip_all)]
 pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
     let mut cbor = hex::decode(args.cbor).context("invalid cbor")?;
 
-    let signers = if args.signer.is_empty() {
-        let wallet_names: Vec<String> = ctx
-            .store
-            .wallets()
-            .iter()
-            .map(|wallet| wallet.name.to_string())
-            .collect();
-
-        MultiSelect::new(
-            "What wallet should be used to sign the transaction?",
-            wallet_names,
-        )
-        .prompt()
-        .unwrap_or_default()
-    } else {
-        args.signer.clone()
-    };
-
-    let wallets = signers
+    let signers = super::common::select_signers(ctx, args.signer.clone())?;
+
+    // multisig wallets attach their script and sign with the members kept
+    // in this store, the rest of the witnesses can be added later on
+    let mut multisigs = vec![];
+    let mut key_signers = vec![];
+
+    for signer in &signers {
+        match ctx.store.find_wallet(signer) {
+            Some(wallet) if wallet.is_multisig() => {
+                wallet.check_unlocked(&cbor)?;
+                cbor = wallet.attach_script(cbor)?;
+
+                for member in wallet.multisig_members(ctx.store.wallets())? {
+                    let member = member.name.to_string();
+                    if !key_signers.contains(&member) {
+                        key_signers.push(member);
+                    }
+                }
+
+                multisigs.push(wallet);
+            }
+            _ => {
+                if !key_signers.contains(signer) {
+                    key_signers.push(signer.clone());
+                }
+            }
+        }
+    }
+
+    // the fee was resolved without the scripts and the signatures they still
+    // need, so it has to be checked again before signing
+    if let Some(multisig) = multisigs.first() {
+        let provider = match &args.provider {
+            Some(name) => ctx.store.find_provider(name),
+            None => ctx.store.wallet_provider(Some(multisig)),
+        };
+
+        let Some(provider) = provider else {
+            bail!("Provider not found")
+        };
+
+        let mut missing = 0;
+        for wallet in &multisigs {
+            let (collected, required) = wallet.multisig_signatures(&cbor)?;
+            missing += (required as u64).saturating_sub(collected as u64);
+        }
+
+        let params = provider.read_params().await?;
+        super::common::check_min_fee(&cbor, &params, missing)?;
+    }
+
+    let wallets = key_signers
         .iter()
+        .filter(|signer| {
+            // members imported from a public key belong to other signers
+            !ctx.store
+                .find_wallet(signer)
+                .is_some_and(|wallet| wallet.is_watch_only() && !signers.contains(signer))
+        })
         .map(|signer| {
             let wallet = ctx
                 .store
@@ -81,17 +125,37 @@ pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
         cbor = wallet.sign(cbor, &password)?;
     }
 
+    let multisigs = multisigs
+        .into_iter()
+        .map(|wallet| {
+            let (collected, required) = wallet.multisig_signatures(&cbor)?;
+            Ok((wallet.name.to_string(), collected, required))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
     match ctx.output_format {
         OutputFormat::Json => {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json!({
+            crate::output::print_json(
+                &json!({
                     "cbor": hex::encode(&cbor),
-                }))
-                .unwrap()
-            );
+                    "multisig": multisigs
+                        .iter()
+                        .map(|(name, collected, required)| json!({
+                            "wallet": name,
+                            "signatures": collected,
+                            "required": required,
+                        }))
+                        .collect::<Vec<_>>(),
+                }),
+                ctx.output_select(),
+            )?;
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            for (name, collected, required) in &multisigs {
+                eprintln!("{name}: {collected} of {required} required signatures");
+            }
+            println!("{}", hex::encode(&cbor))
         }
-        OutputFormat::Table => println!("{}", hex::encode(&cbor)),
     }
 
     Ok(())
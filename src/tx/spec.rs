@@ -0,0 +1,92 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// A single document describing a tx invocation, as an alternative to
+/// passing every flag. Flags given on the command line take precedence.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct InvokeSpec {
+    /// Path of the TII file, relative to the spec file
+    pub tii_file: Option<PathBuf>,
+    pub tx_template: Option<String>,
+    pub profile: Option<String>,
+    pub args: Option<Map<String, Value>>,
+    #[serde(default)]
+    pub signers: Vec<String>,
+    pub provider: Option<String>,
+}
+
+impl InvokeSpec {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).context("reading spec file")?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        Self::parse(&content, base_dir)
+    }
+
+    /// Parses a spec, reporting the path of the offending field on errors.
+    pub fn parse(content: &str, base_dir: &Path) -> Result<Self> {
+        let deserializer = &mut serde_json::Deserializer::from_str(content);
+
+        let mut spec: Self = serde_path_to_error::deserialize(deserializer).map_err(|err| {
+            let path = err.path().to_string();
+            anyhow::anyhow!("invalid spec at `{path}`: {}", err.into_inner())
+        })?;
+
+        spec.tii_file = spec.tii_file.map(|file| base_dir.join(file));
+
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_complete_spec() {
+        let content = r#"{
+            "tii_file": "./invoke.tii",
+            "tx_template": "transfer",
+            "profile": "preview",
+            "args": { "quantity": 2000000, "urgent": true },
+            "signers": ["alice", "bob"],
+            "provider": "demeter"
+        }"#;
+
+        let spec = InvokeSpec::parse(content, Path::new("specs")).unwrap();
+
+        assert_eq!(
+            spec,
+            InvokeSpec {
+                tii_file: Some(PathBuf::from("specs/./invoke.tii")),
+                tx_template: Some("transfer".into()),
+                profile: Some("preview".into()),
+                args: json!({ "quantity": 2000000, "urgent": true })
+                    .as_object()
+                    .cloned(),
+                signers: vec!["alice".into(), "bob".into()],
+                provider: Some("demeter".into()),
+            }
+        );
+
+        assert_eq!(
+            InvokeSpec::parse("{}", Path::new("")).unwrap(),
+            InvokeSpec::default()
+        );
+    }
+
+    #[test]
+    fn reports_invalid_fields() {
+        let err = InvokeSpec::parse(r#"{ "signers": "alice" }"#, Path::new("")).unwrap_err();
+        assert!(err.to_string().starts_with("invalid spec at `signers`"));
+
+        let err = InvokeSpec::parse(r#"{ "args": { "x": 1 }, "signer": [] }"#, Path::new(""))
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown field `signer`"));
+    }
+}
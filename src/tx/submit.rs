@@ -24,7 +24,7 @@ pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
 
     let provider = match args.provider {
         Some(name) => ctx.store.find_provider(&name),
-        None => ctx.store.default_provider(),
+        None => ctx.store.wallet_provider(ctx.store.default_wallet()),
     };
 
     let Some(provider) = provider else {
@@ -35,15 +35,14 @@ pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
 
     match ctx.output_format {
         OutputFormat::Json => {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json!({
+            crate::output::print_json(
+                &json!({
                     "hash": hex::encode(&txhash)
-                }))
-                .unwrap()
-            );
+                }),
+                ctx.output_select(),
+            )?;
         }
-        OutputFormat::Table => {
+        OutputFormat::Table | OutputFormat::Markdown => {
             println!("Submitted TX: {}", args.cbor);
             println!("TX Hash: {}", hex::encode(&txhash));
         }
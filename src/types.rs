@@ -1,7 +1,10 @@
-use comfy_table::Table;
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::output::OutputFormatter;
+use crate::output::{OutputFormatter, TableStyle};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Asset {
@@ -34,34 +37,192 @@ pub struct UTxO {
     pub datum: Option<Datum>,
 }
 
+impl UTxO {
+    /// The UTxO reference formatted as `hash#index`.
+    pub fn ref_string(&self) -> String {
+        format!("{}#{}", hex::encode(&self.tx), self.tx_index)
+    }
+}
+
+/// Display details of a token, as published in the Cardano token registry.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct TokenMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Balance {
     pub address: String,
     pub coin: String, // To avoid overflow
     pub assets: Vec<BalanceAsset>,
     pub datums: Vec<Datum>,
+    /// Registry metadata of the assets, keyed by policy id and asset name
+    /// in hex.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, TokenMetadata>,
+}
+
+/// The registry subject of an asset: its policy id and name, in hex.
+pub fn asset_subject(policy_id: &[u8], name: &[u8]) -> String {
+    format!("{}{}", hex::encode(policy_id), hex::encode(name))
+}
+
+/// Merges the assets of several UTxOs, adding up the quantities of each
+/// (policy, asset name) pair. Policies and names come out sorted.
+pub fn aggregate_assets(
+    assets: impl IntoIterator<Item = BalanceAsset>,
+) -> Result<Vec<BalanceAsset>> {
+    let mut totals: BTreeMap<Vec<u8>, BTreeMap<Vec<u8>, u128>> = BTreeMap::new();
+
+    for entry in assets {
+        let policy = totals.entry(entry.policy_id.clone()).or_default();
+        for asset in entry.assets {
+            let subject = asset_subject(&entry.policy_id, &asset.name);
+            let quantity: u128 = asset.quantity.parse().with_context(|| {
+                format!("invalid quantity {} of asset {subject}", asset.quantity)
+            })?;
+            let total = policy.entry(asset.name).or_default();
+            *total = total
+                .checked_add(quantity)
+                .with_context(|| format!("quantity of asset {subject} overflows"))?;
+        }
+    }
+
+    let assets = totals
+        .into_iter()
+        .map(|(policy_id, assets)| BalanceAsset {
+            policy_id,
+            assets: assets
+                .into_iter()
+                .map(|(name, quantity)| Asset {
+                    name,
+                    quantity: quantity.to_string(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(assets)
+}
+
+/// CIP-67 labels of the CIP-68 reference, NFT and fungible tokens.
+const CIP68_LABELS: [[u8; 4]; 3] = [
+    [0x00, 0x06, 0x43, 0xb0],
+    [0x00, 0x0d, 0xe1, 0x40],
+    [0x00, 0x14, 0xdf, 0x10],
+];
+
+/// An asset name as text when it's printable UTF-8, dropping CIP-68 labels,
+/// and as `0x`-prefixed hex otherwise, so hex names can't pass for text.
+pub fn asset_display_name(name: &[u8]) -> String {
+    let unlabeled = match name.get(..4) {
+        Some(label) if CIP68_LABELS.iter().any(|x| x == label) => &name[4..],
+        _ => name,
+    };
+
+    match std::str::from_utf8(unlabeled) {
+        Ok(text) if !text.is_empty() && !text.chars().any(char::is_control) => text.to_string(),
+        _ if name.is_empty() => String::new(),
+        _ => format!("0x{}", hex::encode(name)),
+    }
+}
+
+/// Places the decimal point of a quantity given in the smallest unit.
+pub fn format_decimals(quantity: &str, decimals: u8) -> String {
+    let decimals = decimals as usize;
+
+    if decimals == 0 || !quantity.bytes().all(|x| x.is_ascii_digit()) {
+        return quantity.to_string();
+    }
+
+    let padded = format!("{quantity:0>width$}", width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+
+    format!("{whole}.{fraction}")
+}
+
+impl Balance {
+    /// Aggregates UTxOs the same way `get_balance` does for a single
+    /// address, used when a balance spans several addresses.
+    pub fn from_utxos(address: &str, utxos: &[UTxO]) -> Result<Self> {
+        Ok(Self {
+            address: address.to_string(),
+            coin: BalanceSummary::from_utxos(address, utxos).coin,
+            assets: aggregate_assets(utxos.iter().flat_map(|x| x.assets.clone()))?,
+            datums: utxos.iter().filter_map(|x| x.datum.clone()).collect(),
+            metadata: BTreeMap::new(),
+        })
+    }
 }
 
 pub type DetailedBalance = Vec<UTxO>;
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct BalanceSummary {
+    pub address: String,
+    pub coin: String, // To avoid overflow
+    pub assets: usize,
+    pub utxos: usize,
+}
+
+impl BalanceSummary {
+    /// Folds the UTxOs of an address into totals, counting each
+    /// (policy, asset name) pair only once.
+    pub fn from_utxos(address: &str, utxos: &[UTxO]) -> Self {
+        let mut coin: u128 = 0;
+        let mut assets = std::collections::HashSet::new();
+
+        for utxo in utxos {
+            coin += utxo.coin.parse::<u128>().unwrap_or_default();
+
+            for entry in &utxo.assets {
+                for asset in &entry.assets {
+                    assets.insert((&entry.policy_id, &asset.name));
+                }
+            }
+        }
+
+        Self {
+            address: address.to_string(),
+            coin: coin.to_string(),
+            assets: assets.len(),
+            utxos: utxos.len(),
+        }
+    }
+}
+
 impl OutputFormatter for Balance {
-    fn to_table(&self) {
+    fn to_table(&self, style: TableStyle) {
         println!("Balance for address: {}", self.address);
         println!("  Lovelace: {} ADA", self.coin);
         if !self.assets.is_empty() {
             println!();
             println!("Assets:");
 
-            let mut table = Table::new();
+            let mut table = crate::output::table(style);
             table.set_header(vec!["Policy", "Asset", "Quantity"]);
 
             for entry in &self.assets {
                 for asset in &entry.assets {
-                    table.add_row(vec![
-                        hex::encode(&entry.policy_id),
-                        hex::encode(&asset.name),
-                        asset.quantity.clone(),
-                    ]);
+                    let metadata = self
+                        .metadata
+                        .get(&asset_subject(&entry.policy_id, &asset.name));
+
+                    let name = metadata
+                        .and_then(|x| x.ticker.clone().or(x.name.clone()))
+                        .unwrap_or_else(|| asset_display_name(&asset.name));
+
+                    let quantity = match metadata.and_then(|x| x.decimals) {
+                        Some(decimals) => format_decimals(&asset.quantity, decimals),
+                        None => asset.quantity.clone(),
+                    };
+
+                    table.add_row(vec![hex::encode(&entry.policy_id), name, quantity]);
                 }
             }
             println!("{table}");
@@ -72,7 +233,7 @@ impl OutputFormatter for Balance {
             println!("{:?}", self.datums);
             println!("Datums:");
 
-            let mut table = Table::new();
+            let mut table = crate::output::table(style);
             table.set_header(vec!["Datum hash"]);
 
             for datum in &self.datums {
@@ -82,20 +243,20 @@ impl OutputFormatter for Balance {
         }
     }
 
-    fn to_json(&self) {
-        println!("{}", serde_json::to_string_pretty(self).unwrap());
+    fn to_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
     }
 }
 
 impl OutputFormatter for DetailedBalance {
-    fn to_table(&self) {
+    fn to_table(&self, style: TableStyle) {
         if !self.is_empty() {
             println!("UTxOs");
             println!("=====");
         }
         for utxo in self {
             println!();
-            println!("* {}#{}", hex::encode(&utxo.tx), utxo.tx_index);
+            println!("* {}", utxo.ref_string());
             println!("  * Lovelace: {}", utxo.coin);
 
             if let Some(datum) = &utxo.datum {
@@ -106,14 +267,14 @@ impl OutputFormatter for DetailedBalance {
                 println!();
                 println!("  * Assets:");
 
-                let mut table = Table::new();
+                let mut table = crate::output::table(style);
                 table.set_header(vec!["Policy", "Asset", "Quantity"]);
 
                 for entry in &utxo.assets {
                     for asset in &entry.assets {
                         table.add_row(vec![
                             hex::encode(&entry.policy_id),
-                            hex::encode(&asset.name),
+                            asset_display_name(&asset.name),
                             asset.quantity.clone(),
                         ]);
                     }
@@ -123,7 +284,123 @@ impl OutputFormatter for DetailedBalance {
         }
     }
 
-    fn to_json(&self) {
-        println!("{}", serde_json::to_string_pretty(self).unwrap());
+    fn to_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl OutputFormatter for BalanceSummary {
+    fn to_table(&self, _style: TableStyle) {
+        println!("Balance summary for address: {}", self.address);
+        println!("  Lovelace: {}", self.coin);
+        println!("  Assets: {}", self.assets);
+        println!("  UTxOs: {}", self.utxos);
+    }
+
+    fn to_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utxo(index: u64, coin: &str, assets: Vec<(u8, &str)>) -> UTxO {
+        UTxO {
+            tx: vec![0xaa; 32],
+            tx_index: index,
+            address: "addr_test1".to_string(),
+            coin: coin.to_string(),
+            assets: assets
+                .into_iter()
+                .map(|(policy, name)| BalanceAsset {
+                    policy_id: vec![policy; 28],
+                    assets: vec![Asset {
+                        name: name.as_bytes().to_vec(),
+                        quantity: "1".to_string(),
+                    }],
+                })
+                .collect(),
+            datum: None,
+        }
+    }
+
+    #[test]
+    fn ref_string_joins_hash_and_index() {
+        assert_eq!(
+            utxo(3, "1", vec![]).ref_string(),
+            format!("{}#3", "aa".repeat(32))
+        );
+    }
+
+    #[test]
+    fn summary_folds_utxos() {
+        let utxos = vec![
+            utxo(0, "2000000", vec![(1, "foo"), (1, "bar")]),
+            utxo(1, "1500000", vec![(1, "foo"), (2, "foo")]),
+            utxo(2, "18446744073709551615", vec![]),
+        ];
+
+        let summary = BalanceSummary::from_utxos("addr_test1", &utxos);
+        assert_eq!(summary.coin, "18446744073713051615");
+        assert_eq!(summary.assets, 3);
+        assert_eq!(summary.utxos, 3);
+
+        let empty = BalanceSummary::from_utxos("addr_test1", &[]);
+        assert_eq!(empty.coin, "0");
+        assert_eq!(empty.assets, 0);
+        assert_eq!(empty.utxos, 0);
+    }
+
+    #[test]
+    fn assets_are_aggregated() {
+        let utxos = vec![
+            utxo(0, "1", vec![(2, "foo"), (1, "bar")]),
+            utxo(1, "1", vec![(1, "bar"), (2, "foo"), (1, "baz")]),
+        ];
+
+        let balance = Balance::from_utxos("addr_test1", &utxos).unwrap();
+        let totals: Vec<_> = balance
+            .assets
+            .iter()
+            .flat_map(|entry| {
+                entry.assets.iter().map(|asset| {
+                    (
+                        entry.policy_id[0],
+                        asset_display_name(&asset.name),
+                        asset.quantity.as_str(),
+                    )
+                })
+            })
+            .collect();
+
+        assert_eq!(
+            totals,
+            vec![
+                (1, "bar".to_string(), "2"),
+                (1, "baz".to_string(), "1"),
+                (2, "foo".to_string(), "2"),
+            ]
+        );
+
+        let mut invalid = utxo(2, "1", vec![(1, "bar")]);
+        invalid.assets[0].assets[0].quantity = "many".to_string();
+        assert!(Balance::from_utxos("addr_test1", &[invalid]).is_err());
+    }
+
+    #[test]
+    fn asset_names_are_decoded() {
+        assert_eq!(asset_display_name(b"HOSKY"), "HOSKY");
+        assert_eq!(
+            asset_display_name(&[0x00, 0x14, 0xdf, 0x10, b'U', b'S', b'D']),
+            "USD"
+        );
+        assert_eq!(asset_display_name(&[0xca, 0xfe]), "0xcafe");
+        assert_eq!(asset_display_name(&[]), "");
+
+        assert_eq!(format_decimals("1234567", 6), "1.234567");
+        assert_eq!(format_decimals("5", 3), "0.005");
+        assert_eq!(format_decimals("42", 0), "42");
     }
 }
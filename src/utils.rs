@@ -100,10 +100,40 @@ where
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
-    std::fs::write(path, contents)?;
+
+    // write next to the target and rename, so readers never see a partial file
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)?;
     Ok(())
 }
 
+/// Replaces every `${VAR}` occurrence with the value of the `VAR` environment
+/// variable, failing if it isn't defined.
+pub fn expand_env_vars(value: &str) -> anyhow::Result<String> {
+    let pattern = regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+
+    let mut expanded = String::with_capacity(value.len());
+    let mut last = 0;
+
+    for captures in pattern.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let name = &captures[1];
+
+        let Ok(var) = std::env::var(name) else {
+            bail!("environment variable '{name}' is not defined");
+        };
+
+        expanded.push_str(&value[last..whole.start()]);
+        expanded.push_str(&var);
+        last = whole.end();
+    }
+
+    expanded.push_str(&value[last..]);
+
+    Ok(expanded)
+}
+
 pub fn clip(input: impl ToString, len: usize) -> String {
     let input = input.to_string();
     if input.len() <= len {
@@ -141,6 +171,32 @@ pub fn format_bigint_opt(bigint_opt: &Option<utxorpc::spec::cardano::BigInt>) ->
         .unwrap_or_default()
 }
 
+/// Wraps a quantity as a utxorpc BigInt, for providers that don't speak
+/// utxorpc themselves.
+pub fn to_bigint(value: u64) -> utxorpc::spec::cardano::BigInt {
+    use utxorpc::spec::cardano::big_int::BigInt;
+
+    let big_int = match i64::try_from(value) {
+        Ok(value) => BigInt::Int(value),
+        Err(_) => BigInt::BigUInt(value.to_be_bytes().to_vec().into()),
+    };
+
+    utxorpc::spec::cardano::BigInt {
+        big_int: Some(big_int),
+    }
+}
+
+/// Formats an optional lovelace BigInt as ADA with thousands separators.
+pub fn format_coin_opt(bigint_opt: &Option<utxorpc::spec::cardano::BigInt>) -> String {
+    use utxorpc::spec::cardano::big_int::BigInt;
+
+    match bigint_opt.as_ref().and_then(|bi| bi.big_int.as_ref()) {
+        Some(BigInt::Int(i)) if *i < 0 => format!("-{}", i.unsigned_abs().format_ada()),
+        Some(BigInt::Int(i)) => (*i as u64).format_ada(),
+        _ => format_bigint_opt(bigint_opt),
+    }
+}
+
 /// Formats an Asset quantity (which is a Quantity enum containing BigInt) into a displayable string.
 pub fn format_asset_quantity(
     quantity_opt: &Option<utxorpc::spec::cardano::asset::Quantity>,
@@ -203,6 +259,23 @@ pub mod option_hex_vec_u8 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_expand_env_vars() {
+        std::env::set_var("CSHELL_TEST_EXPAND_KEY", "dmtr_123");
+
+        assert_eq!(
+            expand_env_vars("${CSHELL_TEST_EXPAND_KEY}").unwrap(),
+            "dmtr_123"
+        );
+        assert_eq!(
+            expand_env_vars("Bearer ${CSHELL_TEST_EXPAND_KEY}!").unwrap(),
+            "Bearer dmtr_123!"
+        );
+        assert_eq!(expand_env_vars("plain").unwrap(), "plain");
+        assert_eq!(expand_env_vars("$NOT_EXPANDED").unwrap(), "$NOT_EXPANDED");
+        assert!(expand_env_vars("${CSHELL_TEST_EXPAND_MISSING}").is_err());
+    }
+
     #[test]
     fn test_clip() {
         assert_eq!(clip("hello", 10), "hello");
@@ -213,4 +286,19 @@ mod tests {
         assert_eq!(clip("short", 2), "sh");
         assert_eq!(clip("", 5), "");
     }
+
+    #[test]
+    fn test_format_coin_opt() {
+        use utxorpc::spec::cardano::{big_int::BigInt as Inner, BigInt};
+
+        let coin = |i| {
+            Some(BigInt {
+                big_int: Some(Inner::Int(i)),
+            })
+        };
+
+        assert_eq!(format_coin_opt(&coin(1_234_567_890)), "1,234.567890");
+        assert_eq!(format_coin_opt(&coin(-2_000_000)), "-2.000000");
+        assert_eq!(format_coin_opt(&None), "");
+    }
 }
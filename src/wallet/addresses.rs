@@ -0,0 +1,109 @@
+use anyhow::bail;
+use clap::Parser;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    output::{OutputFormatter, TableStyle},
+    provider::types::AddressMatch,
+    types::BalanceSummary,
+};
+
+use super::types::Role;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to list the addresses of. If undefined, will use
+    /// default
+    name: Option<String>,
+
+    /// Name of the provider to use. If undefined, will use default
+    provider: Option<String>,
+
+    /// Number of addresses to list, starting from index 0
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+    count: u32,
+
+    /// Chain of the addresses to list
+    #[arg(long, value_enum, default_value_t)]
+    role: Role,
+}
+
+#[derive(Serialize)]
+struct DerivedAddress {
+    index: u32,
+    address: String,
+    /// Whether the address currently holds UTxOs
+    used: bool,
+    coin: String,
+}
+
+struct DerivedAddresses(Vec<DerivedAddress>);
+
+impl OutputFormatter for DerivedAddresses {
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
+
+        table.set_header(vec!["Index", "Address", "Used", "Lovelace"]);
+
+        for x in &self.0 {
+            table.add_row(vec![
+                x.index.to_string(),
+                x.address.clone(),
+                if x.used { "yes" } else { "no" }.to_string(),
+                x.coin.clone(),
+            ]);
+        }
+
+        println!("{table}");
+    }
+
+    fn to_json(&self) -> anyhow::Result<Value> {
+        Ok(serde_json::to_value(&self.0)?)
+    }
+}
+
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let wallet = match args.name {
+        Some(name) => ctx.store.find_wallet(&name),
+        None => ctx.store.default_wallet(),
+    };
+
+    let provider = match args.provider {
+        Some(name) => ctx.store.find_provider(&name),
+        None => ctx.store.wallet_provider(wallet),
+    };
+
+    let (wallet, provider) = match (wallet, provider) {
+        (Some(wallet), Some(provider)) => (wallet, provider),
+        (None, Some(_)) => bail!("Wallet not found."),
+        (Some(_), None) => bail!("Provider not found."),
+        (None, None) => bail!("Wallet and provider not found."),
+    };
+
+    if !wallet.is_hd() {
+        bail!(
+            "wallet '{}' is not an HD wallet, it only has the address `wallet info` shows",
+            wallet.name
+        )
+    }
+
+    let mut addresses = vec![];
+
+    for index in 0..args.count {
+        let address = wallet.role_address(args.role, index, provider.is_testnet())?;
+        let utxos = provider
+            .get_detailed_balance(&address, AddressMatch::Full)
+            .await?;
+        let summary = BalanceSummary::from_utxos(&address.to_string(), &utxos);
+
+        addresses.push(DerivedAddress {
+            index,
+            address: summary.address,
+            used: summary.utxos > 0,
+            coin: summary.coin,
+        });
+    }
+
+    DerivedAddresses(addresses).output(&ctx.output_format, ctx.output_select())
+}
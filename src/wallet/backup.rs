@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+use super::types::{decrypt_bytes, encrypt_bytes, Wallet};
+
+const FORMAT: &str = "cshell-wallet-backup";
+const VERSION: u8 = 1;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to back up
+    name: String,
+
+    /// Path of the backup file
+    #[arg(long)]
+    out: PathBuf,
+
+    /// Password protecting the backup (leave blank to enter in interactive
+    /// mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Overwrite the backup file if it already exists
+    #[arg(long)]
+    force: bool,
+}
+
+/// A wallet entry of the store, encrypted with a backup password. Keys stay
+/// encrypted with the spending password inside.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Bundle {
+    format: String,
+    version: u8,
+    #[serde(with = "hex::serde")]
+    payload: Vec<u8>,
+}
+
+impl Bundle {
+    pub fn seal(wallet: &Wallet, password: &str) -> Result<Self> {
+        let payload = encrypt_bytes(
+            rand_core::UnwrapErr(rand_core::OsRng),
+            &serde_json::to_vec(wallet)?,
+            &password.to_string(),
+        );
+
+        Ok(Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            payload,
+        })
+    }
+
+    pub fn open(&self, password: &str) -> Result<Wallet> {
+        if self.format != FORMAT {
+            bail!("not a cshell wallet backup")
+        }
+
+        if self.version != VERSION {
+            bail!("unsupported backup version {}", self.version)
+        }
+
+        let payload =
+            decrypt_bytes(&password.to_string(), &self.payload).context("invalid password")?;
+
+        serde_json::from_slice(&payload).context("corrupted backup")
+    }
+}
+
+#[instrument("backup", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    if args.out.exists() && !args.force {
+        bail!(
+            "{} already exists, use --force to overwrite it",
+            args.out.display()
+        )
+    }
+
+    let password = match args.password {
+        Some(password) => password,
+        None => inquire::Password::new("Backup password:")
+            .with_help_message("Needed to restore the backup")
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .prompt()?,
+    };
+
+    let bundle = Bundle::seal(wallet, &password)?;
+
+    std::fs::write(&args.out, serde_json::to_string_pretty(&bundle)?)
+        .with_context(|| format!("writing {}", args.out.display()))?;
+
+    match ctx.output_format {
+        OutputFormat::Json => crate::output::print_json(
+            &json!({ "file": args.out.display().to_string() }),
+            ctx.output_select(),
+        )?,
+        OutputFormat::Table | OutputFormat::Markdown => {
+            println!("Wrote {}", args.out.display());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::types::Derivation;
+
+    #[test]
+    fn bundles_round_trip() {
+        let (mnemonic, wallet) =
+            Wallet::try_from("alice", "spend", true, false, Derivation::Cip1852, None).unwrap();
+        let wallet = wallet.store_entropy(&mnemonic, "spend").unwrap();
+
+        let bundle = Bundle::seal(&wallet, "backup").unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains(&hex::encode(wallet.private_key.as_ref().unwrap())));
+
+        let bundle: Bundle = serde_json::from_str(&json).unwrap();
+        assert!(bundle.open("spend").is_err());
+        assert_eq!(bundle.open("backup").unwrap(), wallet);
+
+        let other = Bundle {
+            format: "something-else".to_string(),
+            ..bundle
+        };
+        assert!(other.open("backup").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::bail;
+use bip39::Mnemonic;
+use clap::Parser;
+use serde_json::json;
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+use super::shares;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to split the mnemonic of
+    name: String,
+
+    /// Number of shares needed to restore the wallet
+    #[arg(long, default_value_t = 2)]
+    threshold: u8,
+
+    /// Number of shares to create
+    #[arg(long, default_value_t = 3)]
+    shares: u8,
+
+    /// Spending password of the wallet (leave blank to enter in interactive
+    /// mode)
+    #[arg(long)]
+    password: Option<String>,
+}
+
+#[instrument("backup-shares", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    if wallet.entropy.is_none() {
+        bail!(
+            "wallet '{}' was created without --store-entropy, its mnemonic can't be recovered",
+            wallet.name
+        )
+    }
+
+    let password = match (wallet.is_unsafe, args.password) {
+        (true, _) => String::new(),
+        (false, Some(password)) => password,
+        (false, None) => inquire::Password::new("Password:")
+            .with_help_message("The spending password of your wallet")
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .without_confirmation()
+            .prompt()?,
+    };
+
+    let entropy = Mnemonic::parse(wallet.mnemonic(&password)?)?.to_entropy();
+
+    let shares: Vec<String> = shares::split(
+        rand_core::UnwrapErr(rand_core::OsRng),
+        &entropy,
+        args.threshold,
+        args.shares,
+    )?
+    .iter()
+    .map(|x| x.encode())
+    .collect();
+
+    match ctx.output_format {
+        OutputFormat::Json => crate::output::print_json(
+            &json!({ "threshold": args.threshold, "shares": shares }),
+            ctx.output_select(),
+        )?,
+        OutputFormat::Table | OutputFormat::Markdown => {
+            for share in &shares {
+                println!("{share}");
+            }
+            println!();
+            println!(
+                "Keep each share in a different place. Any {} of them restore the wallet with `wallet restore-shares`{}.",
+                args.threshold,
+                match (wallet.is_hd(), wallet.has_passphrase) {
+                    (true, true) => ", --derivation cip1852 and the same BIP39 passphrase",
+                    (true, false) => ", --derivation cip1852 and the same spending password",
+                    (false, true) => " and the same BIP39 passphrase",
+                    (false, false) => " and the same spending password",
+                }
+            );
+        }
+    }
+
+    Ok(())
+}
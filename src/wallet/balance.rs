@@ -1,7 +1,18 @@
+use std::collections::BTreeMap;
+
 use anyhow::bail;
 use clap::Parser;
 
-use crate::output::OutputFormatter;
+use crate::{
+    output::OutputFormatter,
+    provider::types::{AddressMatch, NetworkName, Provider},
+    types::{Balance, BalanceAsset, BalanceSummary, TokenMetadata},
+};
+
+use super::{
+    discovery::{self, DEFAULT_GAP_LIMIT},
+    registry::{self, DEFAULT_REGISTRY_URL},
+};
 
 #[derive(Parser)]
 pub struct Args {
@@ -15,31 +26,70 @@ pub struct Args {
     /// Whether to include details of all UTxOs or aggregated data.
     #[arg(long, action)]
     detail: bool,
+
+    /// Only show total lovelace, distinct asset count and UTxO count.
+    #[arg(long, action, conflicts_with = "detail")]
+    summary: bool,
+
+    /// Only print the total lovelace as a plain integer.
+    #[arg(long, action, conflicts_with_all = ["detail", "summary"])]
+    raw: bool,
+
+    /// How UTxO addresses are matched against the wallet address.
+    #[arg(long = "match", value_enum, default_value_t)]
+    matching: AddressMatch,
+
+    /// Consecutive unused addresses after which HD wallets stop looking
+    /// for more funds.
+    #[arg(long, default_value_t = DEFAULT_GAP_LIMIT, value_parser = clap::value_parser!(u32).range(1..))]
+    gap_limit: u32,
+
+    /// Show asset names and decimals from the Cardano token registry.
+    #[arg(long, action, conflicts_with_all = ["detail", "summary", "raw"])]
+    registry: bool,
+
+    /// URL of the token registry to query.
+    #[arg(long, default_value = DEFAULT_REGISTRY_URL, requires = "registry")]
+    registry_url: String,
 }
 
 pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
-    let wallet = match args.name {
-        Some(name) => ctx.store.find_wallet(&name),
+    let wallet = match &args.name {
+        Some(name) => ctx.store.find_wallet(name),
         None => ctx.store.default_wallet(),
     };
 
-    let provider = match args.provider {
-        Some(name) => ctx.store.find_provider(&name),
-        None => ctx.store.default_provider(),
+    let provider = match &args.provider {
+        Some(name) => ctx.store.find_provider(name),
+        None => ctx.store.wallet_provider(wallet),
     };
 
     match (wallet, provider) {
         (Some(wallet), Some(provider)) => {
-            if args.detail {
-                let balance = provider
-                    .get_detailed_balance(&wallet.address(provider.is_testnet()))
-                    .await?;
-                balance.output(&ctx.output_format);
+            let address = wallet.address(provider.is_testnet())?;
+
+            if !(args.raw || args.summary || args.detail || wallet.is_hd()) {
+                let mut balance = provider.get_balance(&address, args.matching).await?;
+                balance.metadata = registry_metadata(&args, provider, &balance.assets).await?;
+                balance.output(&ctx.output_format, ctx.output_select())?;
+                return Ok(());
+            }
+
+            let utxos =
+                discovery::wallet_utxos(wallet, provider, args.matching, args.gap_limit).await?;
+
+            if args.raw {
+                let summary = BalanceSummary::from_utxos(&address.to_string(), &utxos);
+                println!("{}", summary.coin);
+            } else if args.summary {
+                BalanceSummary::from_utxos(&address.to_string(), &utxos)
+                    .output(&ctx.output_format, ctx.output_select())?;
+            } else if args.detail {
+                utxos.output(&ctx.output_format, ctx.output_select())?;
             } else {
-                let balance = provider
-                    .get_balance(&wallet.address(provider.is_testnet()))
-                    .await?;
-                balance.output(&ctx.output_format);
+                let mut balance = Balance::from_utxos(&address.to_string(), &utxos)?;
+                balance.metadata = registry_metadata(&args, provider, &balance.assets).await?;
+                balance.output(&ctx.output_format, ctx.output_select())?;
             }
 
             Ok(())
@@ -49,3 +99,23 @@ pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
         (None, None) => bail!("Wallet and provider not found."),
     }
 }
+
+/// Token registry details of the assets, when asked for. The default
+/// registry only lists mainnet tokens, so it's skipped on testnets.
+async fn registry_metadata(
+    args: &Args,
+    provider: &Provider,
+    assets: &[BalanceAsset],
+) -> anyhow::Result<BTreeMap<String, TokenMetadata>> {
+    if !args.registry {
+        return Ok(BTreeMap::new());
+    }
+
+    if args.registry_url == DEFAULT_REGISTRY_URL && provider.network() != Some(NetworkName::Mainnet)
+    {
+        eprintln!("The default token registry only lists mainnet tokens, skipping it.");
+        return Ok(BTreeMap::new());
+    }
+
+    registry::lookup(&args.registry_url, assets).await
+}
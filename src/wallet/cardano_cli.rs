@@ -0,0 +1,208 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use pallas::{codec::minicbor, crypto::key::ed25519::PublicKey};
+use serde::{Deserialize, Serialize};
+
+use super::types::PrivateKey;
+
+const PAYMENT_SKEY: &str = "PaymentSigningKeyShelley_ed25519";
+const PAYMENT_EXTENDED_SKEY: &str = "PaymentExtendedSigningKeyShelley_ed25519_bip32";
+const PAYMENT_VKEY: &str = "PaymentVerificationKeyShelley_ed25519";
+const PAYMENT_EXTENDED_VKEY: &str = "PaymentExtendedVerificationKeyShelley_ed25519_bip32";
+
+/// The JSON file format cardano-cli uses for keys.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct TextEnvelope {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub description: String,
+    #[serde(rename = "cborHex")]
+    pub cbor_hex: String,
+}
+
+impl TextEnvelope {
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading key file {}", path.display()))?;
+
+        serde_json::from_str(&contents)
+            .with_context(|| format!("{} is not a cardano-cli key file", path.display()))
+    }
+
+    fn new(kind: String, description: String, payload: &[u8]) -> Result<Self> {
+        let mut cbor = minicbor::Encoder::new(vec![]);
+        cbor.bytes(payload)?;
+
+        Ok(Self {
+            kind,
+            description,
+            cbor_hex: hex::encode(cbor.into_writer()),
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+
+        std::fs::write(path, contents + "\n")
+            .with_context(|| format!("writing key file {}", path.display()))
+    }
+
+    /// The key bytes, wrapped in a CBOR byte string.
+    fn payload(&self) -> Result<Vec<u8>> {
+        let cbor = hex::decode(&self.cbor_hex).context("invalid cborHex")?;
+        let bytes = minicbor::Decoder::new(&cbor)
+            .bytes()
+            .context("cborHex is not a CBOR byte string")?;
+
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Reads a payment signing key, returning it along with its public key in
+/// the form wallets store it: the extended public key for bip32 keys, the
+/// plain ed25519 key otherwise.
+pub fn parse_signing_key(envelope: &TextEnvelope) -> Result<(PrivateKey, Vec<u8>)> {
+    let payload = envelope.payload()?;
+
+    match envelope.kind.as_str() {
+        PAYMENT_SKEY => {
+            let key = PrivateKey::try_from(payload.as_slice())?;
+            let public_key = key.public_key().as_ref().to_vec();
+            Ok((key, public_key))
+        }
+        // extended secret key (64) + public key (32) + chain code (32)
+        PAYMENT_EXTENDED_SKEY if payload.len() == 128 => {
+            let key = PrivateKey::try_from(&payload[..64])?;
+            Ok((key, payload[64..].to_vec()))
+        }
+        PAYMENT_EXTENDED_SKEY => bail!("unexpected extended key length: {}", payload.len()),
+        other => bail!("unsupported signing key type '{other}'"),
+    }
+}
+
+/// Reads a payment verification key.
+pub fn parse_verification_key(envelope: &TextEnvelope) -> Result<PublicKey> {
+    let payload = envelope.payload()?;
+
+    let key = match envelope.kind.as_str() {
+        PAYMENT_VKEY => payload.as_slice(),
+        // public key (32) + chain code (32)
+        PAYMENT_EXTENDED_VKEY if payload.len() == 64 => &payload[..32],
+        PAYMENT_EXTENDED_VKEY => bail!("unexpected extended key length: {}", payload.len()),
+        other => bail!("unsupported verification key type '{other}'"),
+    };
+
+    PublicKey::try_from(key).context("invalid verification key")
+}
+
+/// Builds the signing key envelope of a `role` ("Payment" or "Stake") key.
+/// Keys with an extended public key are written in the bip32 format.
+pub fn signing_key(role: &str, key: &PrivateKey, public_key: &[u8]) -> Result<TextEnvelope> {
+    let description = format!("{role} Signing Key");
+
+    match (key.len(), public_key.len()) {
+        (32, 32) => TextEnvelope::new(
+            format!("{role}SigningKeyShelley_ed25519"),
+            description,
+            &key.as_bytes(),
+        ),
+        (64, 64) => TextEnvelope::new(
+            format!("{role}ExtendedSigningKeyShelley_ed25519_bip32"),
+            description,
+            &[key.as_bytes(), public_key.to_vec()].concat(),
+        ),
+        _ => bail!("this key can't be exported in the cardano-cli format"),
+    }
+}
+
+/// Builds the verification key envelope of a `role` ("Payment" or "Stake")
+/// key.
+pub fn verification_key(role: &str, public_key: &[u8]) -> Result<TextEnvelope> {
+    let kind = match public_key.len() {
+        32 => format!("{role}VerificationKeyShelley_ed25519"),
+        64 => format!("{role}ExtendedVerificationKeyShelley_ed25519_bip32"),
+        other => bail!("unexpected public key length: {other}"),
+    };
+
+    TextEnvelope::new(kind, format!("{role} Verification Key"), public_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::types::Bip32PrivateKey;
+
+    fn envelope(kind: &str, payload: &[u8]) -> TextEnvelope {
+        TextEnvelope::new(kind.to_string(), String::new(), payload).unwrap()
+    }
+
+    #[test]
+    fn parses_payment_keys() {
+        let (key, public_key) = parse_signing_key(&envelope(PAYMENT_SKEY, &[7; 32])).unwrap();
+        assert_eq!(key.len(), 32);
+        assert_eq!(public_key, key.public_key().as_ref());
+
+        let vkey = parse_verification_key(&envelope(PAYMENT_VKEY, &public_key)).unwrap();
+        assert_eq!(vkey, key.public_key());
+
+        let json = format!(
+            r#"{{"type":"{PAYMENT_VKEY}","description":"Payment Verification Key","cborHex":"5820{}"}}"#,
+            hex::encode(&public_key)
+        );
+        let parsed: TextEnvelope = serde_json::from_str(&json).unwrap();
+        assert_eq!(parse_verification_key(&parsed).unwrap(), vkey);
+
+        let xprv = Bip32PrivateKey::generate(rand_core::UnwrapErr(rand_core::OsRng));
+        let xpub = xprv.to_public().as_bytes();
+        let payload = [xprv.to_ed25519_private_key().as_bytes(), xpub.clone()].concat();
+        let (key, public_key) =
+            parse_signing_key(&envelope(PAYMENT_EXTENDED_SKEY, &payload)).unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(public_key, xpub);
+        assert_eq!(
+            parse_verification_key(&envelope(PAYMENT_EXTENDED_VKEY, &xpub)).unwrap(),
+            key.public_key()
+        );
+
+        assert!(parse_signing_key(&envelope(PAYMENT_VKEY, &public_key)).is_err());
+        assert!(parse_signing_key(&envelope(PAYMENT_EXTENDED_SKEY, &[7; 64])).is_err());
+    }
+
+    #[test]
+    fn exported_keys_parse_back() {
+        let plain = PrivateKey::try_from([9; 32].as_slice()).unwrap();
+        let plain_public = plain.public_key().as_ref().to_vec();
+
+        let skey = signing_key("Payment", &plain, &plain_public).unwrap();
+        assert_eq!(skey.kind, PAYMENT_SKEY);
+        assert!(skey.cbor_hex.starts_with("5820"));
+        let (parsed, public_key) = parse_signing_key(&skey).unwrap();
+        assert_eq!(parsed.as_bytes(), plain.as_bytes());
+        assert_eq!(public_key, plain_public);
+
+        let vkey = verification_key("Payment", &plain_public).unwrap();
+        assert_eq!(vkey.kind, PAYMENT_VKEY);
+        assert_eq!(parse_verification_key(&vkey).unwrap(), plain.public_key());
+
+        let xprv = Bip32PrivateKey::generate(rand_core::UnwrapErr(rand_core::OsRng));
+        let xpub = xprv.to_public().as_bytes();
+        let extended = xprv.to_ed25519_private_key();
+
+        let skey = signing_key("Payment", &extended, &xpub).unwrap();
+        assert_eq!(skey.kind, PAYMENT_EXTENDED_SKEY);
+        assert!(skey.cbor_hex.starts_with("5880"));
+        let (parsed, public_key) = parse_signing_key(&skey).unwrap();
+        assert_eq!(parsed.as_bytes(), extended.as_bytes());
+        assert_eq!(public_key, xpub);
+
+        let stake = verification_key("Stake", &xpub).unwrap();
+        assert_eq!(
+            stake.kind,
+            "StakeExtendedVerificationKeyShelley_ed25519_bip32"
+        );
+        assert_eq!(stake.description, "Stake Verification Key");
+
+        assert!(signing_key("Payment", &extended, &plain_public).is_err());
+    }
+}
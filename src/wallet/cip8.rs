@@ -0,0 +1,240 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use pallas::{
+    codec::minicbor::{self, data::Type},
+    crypto::{
+        hash::Hasher,
+        key::ed25519::{PublicKey, Signature},
+    },
+    ledger::addresses::{Address, ShelleyPaymentPart},
+};
+
+use super::types::PrivateKey;
+
+/// COSE algorithm id of EdDSA.
+const ALG_EDDSA: i8 = -8;
+/// COSE key type id of octet key pairs.
+const KTY_OKP: u8 = 1;
+/// COSE curve id of Ed25519.
+const CRV_ED25519: u8 = 6;
+
+/// A CIP-8 data signature, as CIP-30 `signData` returns it: a COSE_Sign1
+/// structure and the COSE_Key to verify it with, both CBOR encoded.
+pub struct DataSignature {
+    pub signature: Vec<u8>,
+    pub key: Vec<u8>,
+}
+
+/// Reads a payload given either as a path to a file or as hex.
+pub fn read_payload(value: &str) -> Result<Vec<u8>> {
+    let path = Path::new(value);
+
+    if path.is_file() {
+        return std::fs::read(path).with_context(|| format!("reading {}", path.display()));
+    }
+
+    hex::decode(value).context("payload is neither a file nor hex")
+}
+
+fn protected_header(address: &[u8]) -> Result<Vec<u8>> {
+    let mut cbor = minicbor::Encoder::new(vec![]);
+    cbor.map(2)?
+        .u8(1)?
+        .i8(ALG_EDDSA)?
+        .str("address")?
+        .bytes(address)?;
+
+    Ok(cbor.into_writer())
+}
+
+/// The Sig_structure of RFC 8152, which is what actually gets signed.
+fn sig_structure(protected: &[u8], payload: &[u8]) -> Result<Vec<u8>> {
+    let mut cbor = minicbor::Encoder::new(vec![]);
+    cbor.array(4)?
+        .str("Signature1")?
+        .bytes(protected)?
+        .bytes(&[])?
+        .bytes(payload)?;
+
+    Ok(cbor.into_writer())
+}
+
+fn cose_key(public_key: &PublicKey) -> Result<Vec<u8>> {
+    let mut cbor = minicbor::Encoder::new(vec![]);
+    cbor.map(4)?
+        .u8(1)?
+        .u8(KTY_OKP)?
+        .u8(3)?
+        .i8(ALG_EDDSA)?
+        .i8(-1)?
+        .u8(CRV_ED25519)?
+        .i8(-2)?
+        .bytes(public_key.as_ref())?;
+
+    Ok(cbor.into_writer())
+}
+
+/// Signs `payload` on behalf of `address` with the key controlling it.
+pub fn sign(key: &PrivateKey, address: &Address, payload: &[u8]) -> Result<DataSignature> {
+    let protected = protected_header(&address.to_vec())?;
+    let signature = key.sign(sig_structure(&protected, payload)?);
+
+    let mut cbor = minicbor::Encoder::new(vec![]);
+    cbor.array(4)?
+        .bytes(&protected)?
+        .map(1)?
+        .str("hashed")?
+        .bool(false)?
+        .bytes(payload)?
+        .bytes(signature.as_ref())?;
+
+    Ok(DataSignature {
+        signature: cbor.into_writer(),
+        key: cose_key(&key.public_key())?,
+    })
+}
+
+/// Finds the bytes stored under a label of a CBOR map, COSE labels being
+/// either integers or text.
+fn find_bytes(cbor: &[u8], label: Label) -> Result<Option<Vec<u8>>> {
+    let mut decoder = minicbor::Decoder::new(cbor);
+    let entries = decoder
+        .map()?
+        .context("indefinite maps are not supported")?;
+
+    for _ in 0..entries {
+        let matches = match (decoder.datatype()?, label) {
+            (Type::String, Label::Text(text)) => decoder.str()? == text,
+            (Type::String, _) => {
+                decoder.str()?;
+                false
+            }
+            (_, Label::Int(int)) => decoder.i64()? == int,
+            (_, _) => {
+                decoder.i64()?;
+                false
+            }
+        };
+
+        match (matches, decoder.datatype()?) {
+            (true, Type::Bytes) => return Ok(Some(decoder.bytes()?.to_vec())),
+            (true, _) => bail!("unexpected value type under {label:?}"),
+            (false, _) => decoder.skip()?,
+        }
+    }
+
+    Ok(None)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Label {
+    Int(i64),
+    Text(&'static str),
+}
+
+/// Checks that `signature` was made by the key controlling `address` over
+/// `payload`, failing with the reason when it wasn't.
+pub fn verify(signature: &[u8], key: &[u8], address: &Address, payload: &[u8]) -> Result<()> {
+    let mut cbor = minicbor::Decoder::new(signature);
+    if cbor.array()? != Some(4) {
+        bail!("signature is not a COSE_Sign1 structure")
+    }
+    let protected = cbor.bytes()?.to_vec();
+    cbor.skip()?;
+    // a null payload means it's detached from the signature
+    if cbor.datatype()? == Type::Null {
+        cbor.null()?;
+    } else if cbor.bytes()? != payload {
+        bail!("signature is for another payload")
+    }
+    let signed = Signature::try_from(cbor.bytes()?).context("invalid signature length")?;
+
+    match find_bytes(&protected, Label::Text("address"))? {
+        Some(signed_address) if signed_address == address.to_vec() => (),
+        Some(_) => bail!("signature is for another address"),
+        None => bail!("signature has no address header"),
+    }
+
+    let public_key = find_bytes(key, Label::Int(-2))?.context("key has no public key")?;
+    let public_key = PublicKey::try_from(public_key.as_slice()).context("invalid public key")?;
+    let key_hash = Hasher::<224>::hash(public_key.as_ref());
+
+    let controls_address = match address {
+        Address::Shelley(x) => match x.payment() {
+            ShelleyPaymentPart::Key(hash) => *hash == key_hash,
+            ShelleyPaymentPart::Script(_) => false,
+        },
+        Address::Stake(x) => x.payload().as_hash() == &key_hash,
+        Address::Byron(_) => bail!("byron addresses are not supported"),
+    };
+
+    if !controls_address {
+        bail!("key doesn't control the address")
+    }
+
+    if !public_key.verify(sig_structure(&protected, payload)?, &signed) {
+        bail!("signature doesn't match")
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::addresses::{Network, ShelleyAddress, ShelleyDelegationPart};
+
+    use super::*;
+
+    #[test]
+    fn signs_cose_sign1() {
+        let key = PrivateKey::try_from([3; 32].as_slice()).unwrap();
+        let address =
+            Address::from_bech32("addr_test1vz09v9yfxguvlp0zsnrpa3tdtm7el8xufp3m5lsm7qxzclgmzkket")
+                .unwrap();
+
+        let signed = sign(&key, &address, b"hello").unwrap();
+
+        let mut cbor = minicbor::Decoder::new(&signed.signature);
+        assert_eq!(cbor.array().unwrap(), Some(4));
+        let protected = cbor.bytes().unwrap().to_vec();
+        cbor.skip().unwrap();
+        assert_eq!(cbor.bytes().unwrap(), b"hello");
+        let signature = Signature::try_from(cbor.bytes().unwrap()).unwrap();
+
+        assert_eq!(protected, protected_header(&address.to_vec()).unwrap());
+        assert!(key
+            .public_key()
+            .verify(sig_structure(&protected, b"hello").unwrap(), &signature));
+
+        assert!(signed
+            .key
+            .ends_with(&[&[0x58, 32][..], key.public_key().as_ref()].concat()));
+    }
+
+    #[test]
+    fn verifies_signatures() {
+        let key = PrivateKey::try_from([3; 32].as_slice()).unwrap();
+        let address: Address = ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::Key(Hasher::<224>::hash(key.public_key().as_ref())),
+            ShelleyDelegationPart::Null,
+        )
+        .into();
+
+        let signed = sign(&key, &address, b"hello").unwrap();
+        verify(&signed.signature, &signed.key, &address, b"hello").unwrap();
+
+        assert!(verify(&signed.signature, &signed.key, &address, b"other").is_err());
+
+        let other = PrivateKey::try_from([4; 32].as_slice()).unwrap();
+        let forged = sign(&other, &address, b"hello").unwrap();
+        assert!(verify(&forged.signature, &forged.key, &address, b"hello").is_err());
+        assert!(verify(&signed.signature, &forged.key, &address, b"hello").is_err());
+
+        let elsewhere =
+            Address::from_bech32("addr_test1vz09v9yfxguvlp0zsnrpa3tdtm7el8xufp3m5lsm7qxzclgmzkket")
+                .unwrap();
+        assert!(verify(&signed.signature, &signed.key, &elsewhere, b"hello").is_err());
+    }
+}
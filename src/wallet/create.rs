@@ -4,7 +4,7 @@ use tracing::instrument;
 
 use crate::{output::OutputFormatter, utils::Name};
 
-use super::types::Wallet;
+use super::types::{generate_mnemonic, Derivation, Wallet, MNEMONIC_WORDS};
 
 #[derive(Parser, Clone)]
 pub struct Args {
@@ -21,6 +21,33 @@ pub struct Args {
     /// disable password requirement (not recommended)
     #[arg(long)]
     r#unsafe: bool,
+
+    /// keep the mnemonic entropy, encrypted with the spending password, so
+    /// the mnemonic can be exported later with `wallet export-mnemonic`
+    #[arg(long)]
+    store_entropy: bool,
+
+    /// how keys are derived from the mnemonic. `cip1852` creates an HD
+    /// wallet whose balance spans all of its discovered addresses
+    #[arg(long, value_enum, default_value_t)]
+    derivation: Derivation,
+
+    /// BIP39 passphrase (the "25th word") to derive the keys with. If
+    /// undefined, cip1852 wallets use an empty one and root wallets the
+    /// spending password
+    #[arg(long)]
+    passphrase: Option<String>,
+
+    /// number of words of the mnemonic
+    #[arg(long, default_value_t = 24, value_parser = parse_words)]
+    words: usize,
+}
+
+fn parse_words(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(words) if MNEMONIC_WORDS.contains(&words) => Ok(words),
+        _ => Err("must be one of 12, 15, 18, 21 or 24".to_string()),
+    }
 }
 
 #[instrument("create", skip_all)]
@@ -49,16 +76,44 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
         },
     };
 
-    let new_wallet = Wallet::try_from(
+    let mnemonic = generate_mnemonic(args.words)?;
+
+    let wallet = Wallet::try_from_mnemonic(
         &name,
         &password,
-        ctx.store.default_wallet().is_none(),
+        &mnemonic,
+        ctx.store.flagged_default_wallet().is_none(),
         args.r#unsafe,
+        args.derivation,
+        args.passphrase.as_deref(),
     )?;
 
+    let wallet = match args.store_entropy {
+        true => wallet.store_entropy(&mnemonic, &password)?,
+        false => wallet,
+    };
+
+    let new_wallet = (mnemonic, wallet);
+
     ctx.store.add_wallet(&new_wallet.1)?;
 
     // Log, print, and finish
-    new_wallet.output(&ctx.output_format);
+    new_wallet.output(&ctx.output_format, ctx.output_select())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_word_counts() {
+        let args = Args::parse_from(["create", "--words", "12"]);
+        assert_eq!(args.words, 12);
+
+        let args = Args::parse_from(["create"]);
+        assert_eq!(args.words, 24);
+
+        assert!(Args::try_parse_from(["create", "--words", "13"]).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{bail, Result};
+use bip39::{Language, Mnemonic};
+use clap::Parser;
+use pallas::crypto::hash::Hasher;
+use serde_json::{json, Value};
+use tracing::instrument;
+
+use crate::{
+    output::{OutputFormatter, TableStyle},
+    utils::Name,
+};
+
+use super::types::{Derivation, Wallet};
+
+#[derive(Parser)]
+pub struct Args {
+    /// number of wallets to create
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    count: u32,
+
+    /// prefix of the wallet names, followed by the index of each wallet
+    #[arg(long, default_value = "test")]
+    prefix: String,
+
+    /// seed the mnemonics are derived from. The same seed always yields the
+    /// same wallets
+    #[arg(long, default_value = "cshell")]
+    seed: String,
+
+    /// acknowledge that batch wallets have no password. Required
+    #[arg(long)]
+    r#unsafe: bool,
+
+    /// how keys are derived from the mnemonic
+    #[arg(long, value_enum, default_value_t)]
+    derivation: Derivation,
+}
+
+/// Mnemonic of the wallet at `index` of a batch. Anyone knowing the seed
+/// can spend from it, so these are only meant for test networks.
+fn seeded_mnemonic(seed: &str, index: u32) -> Result<String> {
+    let entropy = Hasher::<256>::hash(format!("{seed}/{index}").as_bytes());
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, entropy.as_ref())?;
+
+    Ok(mnemonic.to_string())
+}
+
+struct Batch {
+    wallets: Vec<Wallet>,
+    is_testnet: bool,
+}
+
+impl OutputFormatter for Batch {
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
+
+        table.set_header(vec!["Name", "Address"]);
+
+        for wallet in &self.wallets {
+            table.add_row(vec![
+                wallet.name.to_string(),
+                wallet
+                    .address(self.is_testnet)
+                    .map_or("-".into(), |x| x.to_string()),
+            ]);
+        }
+
+        println!("{table}");
+
+        if self.is_testnet {
+            println!();
+            println!("Fund them with `cshell wallet faucet <name>` or by sending to the addresses above.");
+        }
+    }
+
+    fn to_json(&self) -> Result<Value> {
+        Ok(Value::Array(
+            self.wallets
+                .iter()
+                .map(|wallet| {
+                    json!({
+                        "name": &wallet.name,
+                        "address": wallet.address(self.is_testnet).ok().map(|x| x.to_string()),
+                    })
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[instrument("create-batch", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
+    if !args.r#unsafe {
+        bail!("batch wallets are created without a password, pass --unsafe to acknowledge it")
+    }
+
+    let names = (0..args.count)
+        .map(|index| Name::try_from(format!("{}-{index}", args.prefix)))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(name) = names.iter().find(|name| {
+        ctx.store
+            .wallets()
+            .iter()
+            .any(|x| x.name.normalized() == name.normalized())
+    }) {
+        bail!(
+            "Wallet with the same or conflicting name '{}' already exists.",
+            name
+        )
+    }
+
+    let mut wallets = vec![];
+
+    for (index, name) in (0..args.count).zip(names) {
+        let wallet = Wallet::try_from_mnemonic(
+            &name,
+            "",
+            &seeded_mnemonic(&args.seed, index)?,
+            ctx.store.flagged_default_wallet().is_none(),
+            true,
+            args.derivation,
+            None,
+        )?;
+
+        ctx.store.add_wallet(&wallet)?;
+        wallets.push(wallet);
+    }
+
+    let is_testnet = ctx
+        .store
+        .default_provider()
+        .is_none_or(|provider| provider.is_testnet());
+
+    // Log, print, and finish
+    Batch {
+        wallets,
+        is_testnet,
+    }
+    .output(&ctx.output_format, ctx.output_select())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_mnemonics_are_deterministic() {
+        let first = seeded_mnemonic("devnet", 0).unwrap();
+
+        assert_eq!(first, seeded_mnemonic("devnet", 0).unwrap());
+        assert_ne!(first, seeded_mnemonic("devnet", 1).unwrap());
+        assert_ne!(first, seeded_mnemonic("other", 0).unwrap());
+        assert_eq!(first.split_whitespace().count(), 24);
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::bail;
+use clap::Parser;
+use tracing::instrument;
+
+use crate::{output::OutputFormatter, utils::Name};
+
+use super::types::Wallet;
+
+#[derive(Parser, Clone)]
+pub struct Args {
+    /// name to identify the wallet
+    /// (leave blank to enter in interactive mode)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// wallet whose payment key can sign for the multisig wallet. Repeat
+    /// for each signer, watch-only wallets of other people included
+    #[arg(long = "signer", required = true)]
+    signers: Vec<String>,
+
+    /// number of signatures required to spend from the wallet
+    #[arg(long)]
+    required: u32,
+}
+
+#[instrument("create-multisig", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
+    let raw_name = match args.name {
+        Some(name) => name,
+        None => inquire::Text::new("Name of the wallet:").prompt()?,
+    };
+    let name = Name::try_from(raw_name)?;
+
+    if ctx
+        .store
+        .wallets()
+        .iter()
+        .any(|wallet| wallet.name.normalized() == name.normalized())
+    {
+        bail!(
+            "Wallet with the same or conflicting name '{}' already exists.",
+            name
+        )
+    }
+
+    let signers = args
+        .signers
+        .iter()
+        .map(|signer| match ctx.store.find_wallet(signer) {
+            Some(wallet) => Ok(wallet),
+            None => bail!("invalid signer wallet '{signer}'"),
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let wallet = Wallet::multisig(
+        &name,
+        args.required,
+        &signers,
+        ctx.store.flagged_default_wallet().is_none(),
+    )?;
+
+    ctx.store.add_wallet(&wallet)?;
+
+    // Log, print, and finish
+    wallet.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
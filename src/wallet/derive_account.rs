@@ -0,0 +1,85 @@
+use anyhow::bail;
+use clap::Parser;
+use tracing::instrument;
+
+use crate::{output::OutputFormatter, utils::Name};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the HD wallet whose mnemonic the account derives from
+    name: String,
+
+    /// Index of the account to derive (m/1852'/1815'/index'). The wallet
+    /// itself is account 0
+    #[arg(long)]
+    index: u32,
+
+    /// Name of the new wallet. Defaults to the wallet name followed by the
+    /// account index
+    #[arg(long)]
+    new_name: Option<String>,
+
+    /// Spending password of the wallet, which also protects the new one
+    /// (leave blank to enter in interactive mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// BIP39 passphrase of the wallet, for wallets created with one (leave
+    /// blank to enter in interactive mode)
+    #[arg(long)]
+    passphrase: Option<String>,
+}
+
+#[instrument("derive-account", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    let name = Name::try_from(
+        args.new_name
+            .unwrap_or_else(|| format!("{}-{}", wallet.name, args.index)),
+    )?;
+
+    if ctx
+        .store
+        .wallets()
+        .iter()
+        .any(|x| x.name.normalized() == name.normalized())
+    {
+        bail!(
+            "Wallet with the same or conflicting name '{}' already exists.",
+            name
+        )
+    }
+
+    let password = match (wallet.is_unsafe, args.password) {
+        (true, _) => String::new(),
+        (false, Some(password)) => password,
+        (false, None) => inquire::Password::new("Password:")
+            .with_help_message("The spending password of your wallet")
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .without_confirmation()
+            .prompt()?,
+    };
+
+    let passphrase = match (args.passphrase, wallet.has_passphrase) {
+        (Some(passphrase), _) => Some(passphrase),
+        (None, true) => Some(
+            inquire::Password::new("BIP39 passphrase:")
+                .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                .without_confirmation()
+                .prompt()?,
+        ),
+        (None, false) => None,
+    };
+
+    let account =
+        wallet.derive_account(&name, args.index, &password, passphrase.as_deref(), false)?;
+
+    ctx.store.add_wallet(&account)?;
+
+    // Log, print, and finish
+    account.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::future::Future;
+
+use anyhow::Result;
+use pallas::ledger::addresses::Address;
+use utxorpc::spec::query::AnyUtxoData;
+
+use crate::{
+    provider::types::{AddressMatch, Provider},
+    types::DetailedBalance,
+};
+
+use super::types::{Role, Wallet};
+
+/// Consecutive unused addresses after which discovery stops, as BIP44
+/// recommends.
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// Walks the address indexes of an HD wallet, collecting what `fetch`
+/// returns for each of them, until `gap_limit` consecutive addresses come
+/// back empty.
+pub async fn discover<T, F, Fut>(gap_limit: u32, mut fetch: F) -> Result<Vec<T>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let mut found = vec![];
+    let mut gap = 0;
+    let mut index = 0;
+
+    while gap < gap_limit {
+        let items = fetch(index).await?;
+
+        if items.is_empty() {
+            gap += 1;
+        } else {
+            gap = 0;
+            found.extend(items);
+        }
+
+        index += 1;
+    }
+
+    Ok(found)
+}
+
+/// Runs `discover` over both the external and the change chains of an HD
+/// wallet, handing each derived address to `fetch`.
+pub async fn discover_wallet<T, F, Fut>(
+    wallet: &Wallet,
+    is_testnet: bool,
+    gap_limit: u32,
+    mut fetch: F,
+) -> Result<Vec<T>>
+where
+    F: FnMut(Address) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let mut found = vec![];
+
+    for role in [Role::External, Role::Change] {
+        let items = discover(gap_limit, |index| {
+            let items = wallet.role_address(role, index, is_testnet).map(&mut fetch);
+            async move { items?.await }
+        })
+        .await?;
+
+        found.extend(items);
+    }
+
+    Ok(found)
+}
+
+/// UTxOs held by every address of the wallet. Wallets that aren't HD only
+/// have the one address.
+pub async fn wallet_utxos(
+    wallet: &Wallet,
+    provider: &Provider,
+    matching: AddressMatch,
+    gap_limit: u32,
+) -> Result<DetailedBalance> {
+    let is_testnet = provider.is_testnet();
+
+    if !wallet.is_hd() {
+        return provider
+            .get_detailed_balance(&wallet.address(is_testnet)?, matching)
+            .await;
+    }
+
+    discover_wallet(wallet, is_testnet, gap_limit, |address| async move {
+        provider.get_detailed_balance(&address, matching).await
+    })
+    .await
+}
+
+/// Raw UTxOs of every address of the wallet, as the provider returns them.
+pub async fn wallet_utxo_data(
+    wallet: &Wallet,
+    provider: &Provider,
+    matching: AddressMatch,
+    gap_limit: u32,
+) -> Result<Vec<AnyUtxoData>> {
+    let is_testnet = provider.is_testnet();
+
+    if !wallet.is_hd() {
+        return provider
+            .get_wallet_utxos(&wallet.address(is_testnet)?, matching)
+            .await;
+    }
+
+    discover_wallet(wallet, is_testnet, gap_limit, |address| async move {
+        provider.get_wallet_utxos(&address, matching).await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn discovery_stops_after_gap_limit() {
+        let used: HashSet<u32> = [0, 1, 4, 9].into();
+        let mut queried = vec![];
+
+        let found = discover(5, |index| {
+            queried.push(index);
+            let items = match used.contains(&index) {
+                true => vec![index],
+                false => vec![],
+            };
+            async move { Ok(items) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(found, vec![0, 1, 4, 9]);
+        assert_eq!(queried, (0..15).collect::<Vec<_>>());
+
+        let found = discover(3, |index| {
+            let items = match used.contains(&index) {
+                true => vec![index],
+                false => vec![],
+            };
+            async move { Ok(items) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(found, vec![0, 1, 4]);
+    }
+
+    #[tokio::test]
+    async fn discovery_covers_change_addresses() {
+        let (_, wallet) = Wallet::try_from(
+            "hd",
+            "",
+            false,
+            true,
+            crate::wallet::types::Derivation::Cip1852,
+            None,
+        )
+        .unwrap();
+
+        let used = [
+            wallet.role_address(Role::External, 1, true).unwrap(),
+            wallet.role_address(Role::Change, 0, true).unwrap(),
+            wallet.role_address(Role::Change, 3, true).unwrap(),
+        ];
+
+        let found = discover_wallet(&wallet, true, 5, |address| {
+            let items = match used.contains(&address) {
+                true => vec![address],
+                false => vec![],
+            };
+            async move { Ok(items) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(found, used);
+    }
+}
@@ -6,6 +6,7 @@ use tracing::instrument;
 
 use crate::{
     output::OutputFormatter,
+    provider::types::NetworkName,
     utils::{show_is_current, Name},
     wallet::types::Wallet,
 };
@@ -22,6 +23,10 @@ pub struct Args {
     /// Whether to set as default wallet.
     #[arg(long)]
     is_default: Option<bool>,
+
+    /// Network whose default provider to use with the wallet.
+    #[arg(long, value_enum)]
+    network: Option<NetworkName>,
 }
 
 #[instrument(skip_all, name = "edit")]
@@ -66,17 +71,25 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
     let new_wallet = Wallet {
         created: wallet.created,
         private_key: wallet.private_key.clone(),
+        entropy: wallet.entropy.clone(),
+        account_public_key: wallet.account_public_key.clone(),
+        script: wallet.script.clone(),
+        tracked_address: wallet.tracked_address.clone(),
+        locked_utxos: wallet.locked_utxos.clone(),
+        has_passphrase: wallet.has_passphrase,
+        derivation: wallet.derivation,
         name: new_name,
         modified: Local::now(),
         public_key: wallet.public_key.clone(),
         is_default: new_is_default,
         is_unsafe: wallet.is_unsafe,
+        network: args.network.or(wallet.network),
     };
 
     ctx.store.remove_wallet(wallet.clone())?;
     ctx.store.add_wallet(&new_wallet)?;
 
     // Log, print, and finish
-    new_wallet.output(&ctx.output_format);
+    new_wallet.output(&ctx.output_format, ctx.output_select())?;
     Ok(())
 }
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use clap::{Parser, ValueEnum};
+use serde_json::json;
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+use super::cardano_cli::{signing_key, verification_key, TextEnvelope};
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Format {
+    /// payment.skey/payment.vkey text envelopes, plus stake keys for HD
+    /// wallets
+    CardanoCli,
+}
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to export the keys of
+    name: String,
+
+    /// Format of the exported keys
+    #[arg(long, value_enum)]
+    format: Format,
+
+    /// Directory to write the key files to
+    #[arg(long, default_value = ".")]
+    out_dir: PathBuf,
+
+    /// Spending password of the wallet (leave blank to enter in interactive
+    /// mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// BIP39 passphrase of the wallet, needed to export the stake key of
+    /// wallets created with one (leave blank to enter in interactive mode)
+    #[arg(long)]
+    passphrase: Option<String>,
+
+    /// Overwrite key files that already exist
+    #[arg(long)]
+    force: bool,
+}
+
+#[instrument("export", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    if wallet.is_watch_only() {
+        bail!(
+            "wallet '{}' is watch-only, it has no keys to export",
+            wallet.name
+        )
+    }
+
+    let password = match (wallet.is_unsafe, args.password) {
+        (true, _) => None,
+        (false, Some(password)) => Some(password),
+        (false, None) => Some(
+            inquire::Password::new("Password:")
+                .with_help_message("The spending password of your wallet")
+                .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                .without_confirmation()
+                .prompt()?,
+        ),
+    };
+
+    // decrypting the key verifies the password
+    let payment = wallet.signing_key(&password).context("invalid password")?;
+
+    let mut files: Vec<(&str, TextEnvelope)> = vec![
+        (
+            "payment.skey",
+            signing_key("Payment", &payment, &wallet.public_key)?,
+        ),
+        (
+            "payment.vkey",
+            verification_key("Payment", &wallet.public_key)?,
+        ),
+    ];
+
+    let password = password.unwrap_or_default();
+
+    let passphrase = match (args.passphrase, wallet.has_passphrase && wallet.is_hd()) {
+        (Some(passphrase), _) => Some(passphrase),
+        (None, true) if wallet.entropy.is_some() => Some(
+            inquire::Password::new("BIP39 passphrase:")
+                .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                .without_confirmation()
+                .prompt()?,
+        ),
+        (None, _) => None,
+    };
+
+    if let Some(stake) = wallet.stake_signing_key(&password, passphrase.as_deref())? {
+        let public_key = stake.to_public().as_bytes();
+        files.push((
+            "stake.skey",
+            signing_key("Stake", &stake.to_ed25519_private_key(), &public_key)?,
+        ));
+        files.push(("stake.vkey", verification_key("Stake", &public_key)?));
+    } else if let Some(stake) = wallet.stake_public_key()? {
+        files.push(("stake.vkey", verification_key("Stake", stake.as_ref())?));
+    }
+
+    let paths: Vec<PathBuf> = files
+        .iter()
+        .map(|(name, _)| args.out_dir.join(name))
+        .collect();
+
+    if !args.force {
+        if let Some(existing) = paths.iter().find(|path| path.exists()) {
+            bail!(
+                "{} already exists, use --force to overwrite it",
+                existing.display()
+            )
+        }
+    }
+
+    std::fs::create_dir_all(&args.out_dir)?;
+    for ((_, envelope), path) in files.iter().zip(&paths) {
+        envelope.write(path)?;
+    }
+
+    match ctx.output_format {
+        OutputFormat::Json => crate::output::print_json(
+            &json!({ "files": paths.iter().map(|x| x.display().to_string()).collect::<Vec<_>>() }),
+            ctx.output_select(),
+        )?,
+        OutputFormat::Table | OutputFormat::Markdown => {
+            for path in &paths {
+                println!("Wrote {}", path.display());
+            }
+
+            if wallet.is_hd() && wallet.entropy.is_none() {
+                println!();
+                println!(
+                    "stake.skey can only be exported from wallets created with --store-entropy."
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
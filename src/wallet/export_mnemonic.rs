@@ -0,0 +1,80 @@
+use anyhow::bail;
+use clap::Parser;
+use serde_json::json;
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to export the mnemonic of
+    name: String,
+
+    /// Spending password of the wallet (leave blank to enter in interactive
+    /// mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Skip the confirmation prompt
+    #[arg(long)]
+    yes: bool,
+}
+
+#[instrument("export-mnemonic", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    if wallet.entropy.is_none() {
+        bail!(
+            "wallet '{}' was created without --store-entropy, its mnemonic can't be recovered",
+            wallet.name
+        )
+    }
+
+    eprintln!("Anyone with this mnemonic and the spending password controls the wallet funds.");
+    eprintln!("Make sure nobody is watching your screen and the output isn't logged.");
+
+    if !args.yes {
+        let confirm = inquire::Confirm::new("Print the mnemonic?")
+            .with_default(false)
+            .prompt()?;
+
+        if !confirm {
+            return Ok(());
+        }
+    }
+
+    let password = match (wallet.is_unsafe, args.password) {
+        (true, _) => String::new(),
+        (false, Some(password)) => password,
+        (false, None) => inquire::Password::new("Password:")
+            .with_help_message("The spending password of your wallet")
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .without_confirmation()
+            .prompt()?,
+    };
+
+    let mnemonic = wallet.mnemonic(&password)?;
+
+    match ctx.output_format {
+        OutputFormat::Json => {
+            crate::output::print_json(&json!({ "mnemonic": mnemonic }), ctx.output_select())?
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            println!("{mnemonic}");
+            println!();
+            match wallet.has_passphrase {
+                true => {
+                    println!("Restoring from this mnemonic requires the same BIP39 passphrase.")
+                }
+                false => {
+                    println!("Restoring from this mnemonic requires the same spending password.")
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
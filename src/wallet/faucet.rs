@@ -0,0 +1,221 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Parser, ValueEnum};
+use reqwest::{header::RETRY_AFTER, StatusCode};
+use serde_json::{json, Value};
+use tracing::instrument;
+
+use crate::{output::OutputFormat, provider::types::NetworkName};
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Network {
+    Preview,
+    Preprod,
+}
+
+impl Network {
+    fn faucet_url(&self) -> &'static str {
+        match self {
+            Network::Preview => "https://faucet.preview.world.dev.cardano.org/send-money/{address}",
+            Network::Preprod => "https://faucet.preprod.world.dev.cardano.org/send-money/{address}",
+        }
+    }
+
+    fn from_name(network: NetworkName) -> Option<Self> {
+        match network {
+            NetworkName::Preview => Some(Network::Preview),
+            NetworkName::Preprod => Some(Network::Preprod),
+            NetworkName::Mainnet => None,
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to fund. If undefined, will use default
+    name: Option<String>,
+
+    /// Name of the provider to use. If undefined, will use default
+    #[arg(long)]
+    provider: Option<String>,
+
+    /// Testnet whose faucet is used. If undefined, the network of the
+    /// provider or the wallet is used
+    #[arg(long, value_enum)]
+    network: Option<Network>,
+
+    /// Faucet url template, overriding the one of the network. The
+    /// `{address}` placeholder is replaced with the wallet address
+    #[arg(long, env = "CSHELL_FAUCET_URL")]
+    faucet_url: Option<String>,
+
+    /// API key sent to the faucet
+    #[arg(long, env = "CSHELL_FAUCET_API_KEY")]
+    api_key: Option<String>,
+}
+
+#[instrument("faucet", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
+    let wallet = match &args.name {
+        Some(name) => ctx.store.find_wallet(name),
+        None => ctx.store.default_wallet(),
+    };
+
+    let provider = match &args.provider {
+        Some(name) => ctx.store.find_provider(name),
+        None => ctx.store.wallet_provider(wallet),
+    };
+
+    let (Some(wallet), Some(provider)) = (wallet, provider) else {
+        bail!("Wallet or provider not found.")
+    };
+
+    if !provider.is_testnet() {
+        bail!("faucets are only available for testnet providers");
+    }
+
+    let address = wallet.address(true)?.to_string();
+    let template = match (&args.faucet_url, args.network) {
+        (Some(template), _) => template.as_str(),
+        (None, Some(network)) => network.faucet_url(),
+        (None, None) => provider
+            .network()
+            .or(wallet.network)
+            .and_then(Network::from_name)
+            .context("unknown testnet, pass --network or --faucet-url")?
+            .faucet_url(),
+    };
+    let url = faucet_url(template, &address, args.api_key.as_deref())?;
+
+    let response = reqwest::Client::new()
+        .post(url)
+        .send()
+        .await
+        .context("requesting faucet")?;
+
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    let body = response.text().await.unwrap_or_default();
+
+    let tx = parse_response(status, retry_after.as_deref(), &body, Utc::now())?;
+
+    match ctx.output_format {
+        OutputFormat::Json => {
+            crate::output::print_json(
+                &json!({
+                    "address": address,
+                    "tx": tx,
+                }),
+                ctx.output_select(),
+            )?;
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            println!("Funds requested for {address}");
+            match tx {
+                Some(tx) => println!("Funding tx: {tx}"),
+                None => println!("The faucet didn't report the funding tx"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn faucet_url(template: &str, address: &str, api_key: Option<&str>) -> Result<reqwest::Url> {
+    if !template.contains("{address}") {
+        bail!("faucet url must contain an {{address}} placeholder");
+    }
+
+    let mut url = reqwest::Url::parse(&template.replace("{address}", address))
+        .context("invalid faucet url")?;
+
+    if let Some(api_key) = api_key {
+        url.query_pairs_mut().append_pair("api_key", api_key);
+    }
+
+    Ok(url)
+}
+
+/// Seconds to wait from a Retry-After header, which is either a number of
+/// seconds or an HTTP date.
+fn retry_after_secs(value: &str, now: DateTime<Utc>) -> Option<i64> {
+    if let Ok(secs) = value.trim().parse() {
+        return Some(secs);
+    }
+
+    let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    Some((date.with_timezone(&Utc) - now).num_seconds().max(0))
+}
+
+/// Extracts the funding tx hash from the faucet response, if reported.
+fn parse_response(
+    status: StatusCode,
+    retry_after: Option<&str>,
+    body: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<String>> {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        match retry_after {
+            Some(value) => match retry_after_secs(value, now) {
+                Some(secs) => bail!("faucet rate limit reached, retry after {secs} seconds"),
+                None => bail!("faucet rate limit reached, retry after {value}"),
+            },
+            None => bail!("faucet rate limit reached, try again later"),
+        }
+    }
+
+    if !status.is_success() {
+        bail!("faucet request failed with status {status}: {body}");
+    }
+
+    let value: Value = serde_json::from_str(body).unwrap_or_default();
+
+    Ok(value.get("txid").and_then(Value::as_str).map(String::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_faucet_urls() {
+        let url = faucet_url(Network::Preview.faucet_url(), "addr_test1xyz", Some("k3y")).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://faucet.preview.world.dev.cardano.org/send-money/addr_test1xyz?api_key=k3y"
+        );
+
+        assert!(faucet_url("https://faucet.example.com", "addr_test1xyz", None).is_err());
+    }
+
+    #[test]
+    fn handles_faucet_responses() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let parse = |status, retry_after, body| parse_response(status, retry_after, body, now);
+
+        let body = r#"{"amount":{"lovelace":10000000000},"txid":"abcd","txin":"abcd#0"}"#;
+        assert_eq!(
+            parse(StatusCode::OK, None, body).unwrap(),
+            Some("abcd".to_string())
+        );
+        assert_eq!(parse(StatusCode::OK, None, "ok").unwrap(), None);
+
+        let err = parse(StatusCode::TOO_MANY_REQUESTS, Some("3600"), "").unwrap_err();
+        assert!(err.to_string().contains("retry after 3600 seconds"));
+
+        let date = "Wed, 21 Oct 2015 07:28:00 GMT";
+        let err = parse(StatusCode::TOO_MANY_REQUESTS, Some(date), "").unwrap_err();
+        assert!(err.to_string().contains("retry after 1680 seconds"));
+
+        let err = parse(StatusCode::TOO_MANY_REQUESTS, Some("soon"), "").unwrap_err();
+        assert!(err.to_string().contains("retry after soon"));
+
+        assert!(parse(StatusCode::BAD_REQUEST, None, "bad address").is_err());
+    }
+}
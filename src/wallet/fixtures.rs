@@ -0,0 +1,50 @@
+use utxorpc::spec::{
+    cardano::{big_int, Asset, BigInt, Multiasset, TxOutput},
+    query::{any_utxo_data::ParsedState, AnyUtxoData, ChainPoint, TxoRef},
+};
+
+/// Fields of a Cardano UTxO that wallet tests build fixtures from. The
+/// reference hash is `hash` repeated 32 times and the asset policy is
+/// `policy` repeated 28 times.
+#[derive(Default)]
+pub struct Utxo<'a> {
+    pub coin: i64,
+    pub slot: Option<u64>,
+    pub hash: u8,
+    pub index: u32,
+    pub asset: Option<(u8, &'a [u8])>,
+}
+
+impl Utxo<'_> {
+    pub fn build(self) -> AnyUtxoData {
+        AnyUtxoData {
+            txo_ref: Some(TxoRef {
+                hash: vec![self.hash; 32].into(),
+                index: self.index,
+            }),
+            block_ref: self.slot.map(|slot| ChainPoint {
+                slot,
+                ..Default::default()
+            }),
+            parsed_state: Some(ParsedState::Cardano(TxOutput {
+                coin: Some(BigInt {
+                    big_int: Some(big_int::BigInt::Int(self.coin)),
+                }),
+                assets: self
+                    .asset
+                    .map(|(policy, name)| Multiasset {
+                        policy_id: vec![policy; 28].into(),
+                        assets: vec![Asset {
+                            name: name.to_vec().into(),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    })
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+    }
+}
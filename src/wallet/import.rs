@@ -2,11 +2,18 @@ use anyhow::{bail, Context, Result};
 use chrono::Local;
 use clap::Parser;
 use inquire::list_option::ListOption;
-use pallas::crypto::key::ed25519::PublicKey;
-use std::str::FromStr;
+use pallas::{crypto::key::ed25519::PublicKey, ledger::addresses::Address};
+use std::{path::PathBuf, str::FromStr};
 use tracing::instrument;
 
-use crate::{output::OutputFormatter, utils::Name, wallet::types::Wallet};
+use crate::{
+    output::OutputFormatter,
+    utils::Name,
+    wallet::{
+        cardano_cli::{parse_signing_key, parse_verification_key, TextEnvelope},
+        types::{encrypt_private_key, Bip32PublicKey, Wallet},
+    },
+};
 
 #[derive(Parser)]
 pub struct Args {
@@ -14,8 +21,34 @@ pub struct Args {
     name: Option<String>,
 
     // Public Key
+    #[arg(conflicts_with_all = ["xpub", "skey", "address"])]
     public_key: Option<String>,
 
+    /// Bech32 script address to track, such as the one of a Plutus
+    /// contract. The wallet holds no keys and only monitors the address.
+    #[arg(long, conflicts_with_all = ["xpub", "skey"])]
+    address: Option<String>,
+
+    /// Bech32 CIP-1852 account public key (`xpub1...` or `acct_xvk1...`).
+    /// Imports an HD watch-only wallet, tracking all the account addresses.
+    #[arg(long, conflicts_with = "skey")]
+    xpub: Option<String>,
+
+    /// cardano-cli payment signing key file (payment.skey). Imports a wallet
+    /// able to sign, its key encrypted with the spending password.
+    #[arg(long)]
+    skey: Option<PathBuf>,
+
+    /// cardano-cli payment verification key file (payment.vkey), checked
+    /// against the signing key.
+    #[arg(long, requires = "skey")]
+    vkey: Option<PathBuf>,
+
+    /// spending password used to encrypt the imported signing key
+    /// (leave blank to enter in interactive mode)
+    #[arg(long, requires = "skey")]
+    password: Option<String>,
+
     /// Whether to set as default wallet.
     #[arg(long)]
     is_default: Option<bool>,
@@ -53,27 +86,164 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
         },
     };
 
-    let public_key = match args.public_key {
-        Some(public_key) => public_key,
-        None => inquire::Text::new("Public key: ")
-            .prompt()
-            .map_err(anyhow::Error::msg)?,
+    if let Some(address) = args.address {
+        let address = Address::from_bech32(&address).context("invalid address")?;
+        let wallet = Wallet::tracked(&name, &address, new_is_default)?;
+
+        ctx.store.add_wallet(&wallet)?;
+
+        return wallet.output(&ctx.output_format, ctx.output_select());
+    }
+
+    if let Some(skey) = args.skey {
+        let (private_key, public_key) = parse_signing_key(&TextEnvelope::read(&skey)?)?;
+
+        if let Some(vkey) = args.vkey {
+            let vkey = parse_verification_key(&TextEnvelope::read(&vkey)?)?;
+            if vkey != private_key.public_key() {
+                bail!("the verification key doesn't match the signing key")
+            }
+        }
+
+        let password = match args.password {
+            Some(password) => password,
+            None => inquire::Password::new("Password:")
+                .with_help_message("The spending password of your wallet")
+                .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                .prompt()
+                .map_err(anyhow::Error::msg)?,
+        };
+
+        let private_key = encrypt_private_key(
+            rand_core::UnwrapErr(rand_core::OsRng),
+            private_key,
+            &password,
+        );
+
+        let wallet = Wallet {
+            created: Local::now(),
+            private_key: Some(private_key),
+            entropy: None,
+            account_public_key: None,
+            script: None,
+            tracked_address: None,
+            locked_utxos: vec![],
+            has_passphrase: false,
+            derivation: None,
+            name,
+            modified: Local::now(),
+            public_key,
+            is_default: new_is_default,
+            is_unsafe: false,
+            network: None,
+        };
+
+        ctx.store.add_wallet(&wallet)?;
+
+        return wallet.output(&ctx.output_format, ctx.output_select());
+    }
+
+    let (public_key, account_public_key) = match args.xpub {
+        Some(xpub) => {
+            let account = parse_account_key(&xpub)?;
+            let payment = account.derive(0)?.derive(0)?.to_ed25519_pubkey();
+
+            (payment, Some(account.as_bytes()))
+        }
+        None => {
+            let public_key = match args.public_key {
+                Some(public_key) => public_key,
+                None => inquire::Text::new("Public key: ")
+                    .prompt()
+                    .map_err(anyhow::Error::msg)?,
+            };
+
+            let public_key = PublicKey::from_str(&public_key).context("invalid public key")?;
+
+            (public_key, None)
+        }
     };
-    let public_key = PublicKey::from_str(&public_key).context("invalid public key")?;
 
     let wallet = Wallet {
         created: Local::now(),
         private_key: None,
+        entropy: None,
+        account_public_key,
+        script: None,
+        tracked_address: None,
+        locked_utxos: vec![],
+        has_passphrase: false,
+        derivation: None,
         name,
         modified: Local::now(),
         public_key: public_key.as_ref().to_vec(),
         is_default: new_is_default,
         is_unsafe: false,
+        network: None,
     };
 
     ctx.store.add_wallet(&wallet)?;
 
     // Log, print, and finish
-    wallet.output(&ctx.output_format);
+    wallet.output(&ctx.output_format, ctx.output_select())?;
     Ok(())
 }
+
+/// Parses an account public key, either in the `xpub` encoding used by
+/// cshell or the CIP-5 `acct_xvk` one.
+fn parse_account_key(bech32: &str) -> Result<Bip32PublicKey> {
+    let (hrp, data) = bech32::decode(bech32).context("invalid bech32 account key")?;
+
+    if hrp.as_str() != "xpub" && hrp.as_str() != "acct_xvk" {
+        bail!("expected an xpub or acct_xvk account key, got '{hrp}'")
+    }
+
+    match data.try_into() {
+        Ok(bytes) => Ok(Bip32PublicKey::from_bytes(bytes)),
+        Err(_) => bail!("unexpected account key length"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bech32::{Bech32, Hrp};
+
+    use super::*;
+    use crate::wallet::types::Derivation;
+
+    #[test]
+    fn imports_account_keys() {
+        let (_, hd) = Wallet::try_from("hd", "", false, true, Derivation::Cip1852, None).unwrap();
+        let account = hd.account_public_key.clone().unwrap();
+
+        let xpub = bech32::encode::<Bech32>(Hrp::parse("xpub").unwrap(), &account).unwrap();
+        let acct_xvk = bech32::encode::<Bech32>(Hrp::parse("acct_xvk").unwrap(), &account).unwrap();
+
+        assert_eq!(parse_account_key(&xpub).unwrap().as_bytes(), account);
+        assert_eq!(parse_account_key(&acct_xvk).unwrap().as_bytes(), account);
+
+        let other = bech32::encode::<Bech32>(Hrp::parse("addr").unwrap(), &account).unwrap();
+        assert!(parse_account_key(&other).is_err());
+        assert!(parse_account_key("not bech32").is_err());
+
+        let payment = parse_account_key(&xpub)
+            .unwrap()
+            .derive(0)
+            .unwrap()
+            .derive(0)
+            .unwrap()
+            .to_ed25519_pubkey();
+        let watcher = Wallet {
+            public_key: payment.as_ref().to_vec(),
+            private_key: None,
+            ..hd.clone()
+        };
+
+        assert!(watcher.is_watch_only());
+        assert_eq!(watcher.address(false).unwrap(), hd.address(false).unwrap());
+        assert_eq!(
+            watcher.derived_address(5, true).unwrap(),
+            hd.derived_address(5, true).unwrap()
+        );
+    }
+}
@@ -1,14 +1,42 @@
 use anyhow::bail;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use qrcode::{render::unicode::Dense1x2, QrCode};
 use tracing::instrument;
 
-use crate::output::OutputFormatter;
+use crate::output::{OutputFormat, OutputFormatter};
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Network {
+    Mainnet,
+    Testnet,
+}
 
 #[derive(Parser)]
 pub struct Args {
     /// Name of the wallet to show info for. If undefined, will use default
     #[arg(long)]
     name: Option<String>,
+
+    /// Print the wallet address as a QR code instead of the wallet info
+    #[arg(long)]
+    qr: bool,
+
+    /// Network of the address shown as QR code. If undefined, will use the
+    /// one of the default provider
+    #[arg(long, requires = "qr")]
+    network: Option<Network>,
+}
+
+/// Renders a string as a QR code made of unicode half blocks.
+pub(super) fn render_qr(data: &str) -> anyhow::Result<String> {
+    let code = QrCode::new(data)?;
+
+    // colors are inverted so the code scans on dark terminals
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
 }
 
 #[instrument("info", skip_all, fields(name=args.name))]
@@ -18,11 +46,52 @@ pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
         None => ctx.store.default_wallet(),
     };
 
-    match wallet {
-        Some(wallet) => {
-            wallet.output(&ctx.output_format);
-            Ok(())
+    let Some(wallet) = wallet else {
+        bail!("Wallet not found.")
+    };
+
+    if !args.qr {
+        return wallet.output(&ctx.output_format, ctx.output_select());
+    }
+
+    let is_testnet = match args.network {
+        Some(network) => matches!(network, Network::Testnet),
+        None => ctx
+            .store
+            .wallet_provider(Some(wallet))
+            .is_some_and(|provider| provider.is_testnet()),
+    };
+
+    let address = wallet.address(is_testnet)?.to_string();
+
+    match ctx.output_format {
+        OutputFormat::Json => crate::output::print_json(
+            &serde_json::json!({ "address": address }),
+            ctx.output_select(),
+        )?,
+        OutputFormat::Table | OutputFormat::Markdown => {
+            println!("{}", render_qr(&address)?);
+            println!("{address}");
         }
-        None => bail!("Wallet not found."),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_address_qr() {
+        let qr = render_qr("addr_test1vzqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq")
+            .unwrap();
+
+        let lines: Vec<_> = qr.lines().collect();
+        assert!(lines.len() > 10);
+        assert!(lines
+            .iter()
+            .all(|line| line.chars().count() == lines[0].chars().count()));
+        assert!(qr.contains('█') || qr.contains('▀') || qr.contains('▄'));
     }
 }
@@ -0,0 +1,213 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use clap::Parser;
+use pallas::ledger::addresses::{
+    Address, Network, ShelleyDelegationPart, ShelleyPaymentPart, StakePayload,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::instrument;
+
+use crate::output::{OutputFormatter, TableStyle};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Address to decode, as bech32, base58 (byron) or hex
+    address: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Credential {
+    kind: &'static str,
+    hash: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Pointer {
+    slot: u64,
+    tx_index: u64,
+    cert_index: u64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct AddressInfo {
+    address: String,
+    kind: &'static str,
+    network: Option<String>,
+    payment: Option<Credential>,
+    delegation: Option<Credential>,
+    pointer: Option<Pointer>,
+}
+
+fn network_name(network: Network) -> String {
+    match network {
+        Network::Mainnet => "mainnet".to_string(),
+        Network::Testnet => "testnet".to_string(),
+        Network::Other(id) => format!("other ({id})"),
+    }
+}
+
+fn payment_credential(payment: &ShelleyPaymentPart) -> Credential {
+    Credential {
+        kind: if payment.is_script() { "script" } else { "key" },
+        hash: payment.as_hash().to_string(),
+    }
+}
+
+impl AddressInfo {
+    fn new(address: &Address) -> Self {
+        let mut info = Self {
+            address: address.to_string(),
+            kind: "byron",
+            network: address.network().map(network_name),
+            payment: None,
+            delegation: None,
+            pointer: None,
+        };
+
+        match address {
+            Address::Byron(_) => (),
+            Address::Shelley(x) => {
+                info.payment = Some(payment_credential(x.payment()));
+
+                match x.delegation() {
+                    ShelleyDelegationPart::Key(hash) => {
+                        info.kind = "base";
+                        info.delegation = Some(Credential {
+                            kind: "key",
+                            hash: hash.to_string(),
+                        });
+                    }
+                    ShelleyDelegationPart::Script(hash) => {
+                        info.kind = "base";
+                        info.delegation = Some(Credential {
+                            kind: "script",
+                            hash: hash.to_string(),
+                        });
+                    }
+                    ShelleyDelegationPart::Pointer(pointer) => {
+                        info.kind = "pointer";
+                        info.pointer = Some(Pointer {
+                            slot: pointer.slot(),
+                            tx_index: pointer.tx_idx(),
+                            cert_index: pointer.cert_idx(),
+                        });
+                    }
+                    ShelleyDelegationPart::Null => info.kind = "enterprise",
+                }
+            }
+            Address::Stake(x) => {
+                info.kind = "stake";
+                info.delegation = Some(match x.payload() {
+                    StakePayload::Stake(hash) => Credential {
+                        kind: "key",
+                        hash: hash.to_string(),
+                    },
+                    StakePayload::Script(hash) => Credential {
+                        kind: "script",
+                        hash: hash.to_string(),
+                    },
+                });
+            }
+        }
+
+        info
+    }
+}
+
+impl OutputFormatter for AddressInfo {
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
+
+        table.set_header(vec!["Property", "Value"]);
+
+        table.add_row(vec!["Address", &self.address]);
+        table.add_row(vec!["Type", self.kind]);
+        if let Some(network) = &self.network {
+            table.add_row(vec!["Network", network]);
+        }
+        if let Some(payment) = &self.payment {
+            table.add_row(vec!["Payment Credential", payment.kind]);
+            table.add_row(vec!["Payment Hash", &payment.hash]);
+        }
+        if let Some(delegation) = &self.delegation {
+            table.add_row(vec!["Stake Credential", delegation.kind]);
+            table.add_row(vec!["Stake Hash", &delegation.hash]);
+        }
+        if let Some(pointer) = &self.pointer {
+            table.add_row(vec![
+                "Stake Pointer",
+                &format!(
+                    "slot {}, tx {}, cert {}",
+                    pointer.slot, pointer.tx_index, pointer.cert_index
+                ),
+            ]);
+        }
+
+        println!("{table}");
+    }
+
+    fn to_json(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+fn parse_address(value: &str) -> Result<Address> {
+    match Address::from_str(value) {
+        Ok(address) => Ok(address),
+        Err(_) => Address::from_hex(value)
+            .map_err(|_| anyhow::anyhow!("'{value}' is not a bech32, base58 or hex address")),
+    }
+}
+
+#[instrument("inspect-address", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> Result<()> {
+    let address = parse_address(&args.address)?;
+
+    AddressInfo::new(&address).output(&ctx.output_format, ctx.output_select())
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::addresses::ShelleyAddress;
+
+    use super::*;
+
+    #[test]
+    fn decodes_addresses() {
+        let script =
+            parse_address("addr1w9jx45flh83z6wuqypyash54mszwmdj8r64fydafxtfc6jgrw4rm3").unwrap();
+        let info = AddressInfo::new(&script);
+        assert_eq!(info.kind, "enterprise");
+        assert_eq!(info.network.as_deref(), Some("mainnet"));
+        assert_eq!(info.payment.unwrap().kind, "script");
+        assert!(info.delegation.is_none());
+
+        let from_hex = parse_address(&script.to_hex()).unwrap();
+        assert_eq!(from_hex, script);
+
+        let Address::Shelley(shelley) = script else {
+            panic!("expected a shelley address");
+        };
+        let base = Address::Shelley(ShelleyAddress::new(
+            Network::Testnet,
+            shelley.payment().clone(),
+            ShelleyDelegationPart::key_hash(*shelley.payment().as_hash()),
+        ));
+        let info = AddressInfo::new(&base);
+        assert_eq!(info.kind, "base");
+        assert_eq!(info.network.as_deref(), Some("testnet"));
+        assert_eq!(info.delegation.as_ref().unwrap().kind, "key");
+
+        let Address::Shelley(base) = base else {
+            unreachable!()
+        };
+        let stake = Address::Stake(base.try_into().unwrap());
+        let info = AddressInfo::new(&stake);
+        assert_eq!(info.kind, "stake");
+        assert!(info.payment.is_none());
+
+        assert!(parse_address("not an address").is_err());
+    }
+}
@@ -1,8 +1,21 @@
-use crate::output::OutputFormatter;
+use crate::{
+    output::{OutputFormat, OutputFormatter},
+    store::Store,
+};
 use tracing::instrument;
 
 #[instrument("list", skip_all)]
 pub async fn run(ctx: &crate::Context) -> anyhow::Result<()> {
-    ctx.store.wallets().output(&ctx.output_format);
+    ctx.store
+        .wallets()
+        .output(&ctx.output_format, ctx.output_select())?;
+
+    if let Some(name) = Store::wallet_override() {
+        if !matches!(ctx.output_format, OutputFormat::Json) {
+            println!();
+            println!("CSHELL_WALLET selects '{name}' instead of the default wallet.");
+        }
+    }
+
     Ok(())
 }
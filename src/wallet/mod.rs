@@ -1,16 +1,46 @@
 use clap::{Parser, Subcommand};
 use tracing::instrument;
 
+mod addresses;
+mod backup;
+mod backup_shares;
 mod balance;
+mod cardano_cli;
+mod cip8;
 mod create;
+mod create_batch;
+mod create_multisig;
 mod delete;
+mod derive_account;
+mod discovery;
 mod edit;
+mod export;
+mod export_mnemonic;
+mod faucet;
+#[cfg(test)]
+mod fixtures;
 mod import;
 mod info;
+mod inspect_address;
 mod list;
+mod lock_utxo;
+mod passwd;
+mod query;
+mod receive;
+mod registry;
+mod rename;
 mod restore;
+mod restore_backup;
+mod restore_shares;
+mod shares;
+mod sign_data;
+mod stats;
 pub mod types;
+mod unlock_utxo;
+mod use_wallet;
 mod utxos;
+mod verify_data;
+mod xpub;
 
 #[derive(Parser)]
 pub struct Args {
@@ -22,36 +52,105 @@ pub struct Args {
 enum Commands {
     /// Create a new wallet. Leave arguments blank for interactive mode
     Create(create::Args),
+    /// Create deterministic, password-less wallets for test networks
+    CreateBatch(create_batch::Args),
+    /// Create a wallet locked by an N-of-M native script of other wallets
+    CreateMultisig(create_multisig::Args),
     /// Restore wallet using BIP39 Mnemonic. Leave arguments blank for
     /// interactive mode
     Restore(restore::Args),
+    /// Derive another account of an HD wallet mnemonic as a new wallet
+    DeriveAccount(derive_account::Args),
     /// Edit an existing wallet
     Edit(edit::Args),
+    /// Rename a wallet, keeping its keys and settings
+    Rename(rename::Args),
+    /// Change the spending password of a wallet
+    Passwd(passwd::Args),
     /// Import a wallet
     Import(import::Args),
     /// Show wallet info
     Info(info::Args),
+    /// Decode any Cardano address into its network and credentials
+    InspectAddress(inspect_address::Args),
     /// List available wallets
     List,
+    /// Set the wallet commands use when none is named
+    Use(use_wallet::Args),
     /// Delete a wallet. Caution!! This cannot be undone.
     Delete(delete::Args),
+    /// List the derived addresses of an HD wallet and their balances
+    Addresses(addresses::Args),
     /// show wallet balance
     Balance(balance::Args),
     /// List wallet UTxOs
     Utxos(utxos::Args),
+    /// List the wallet UTxOs meeting conditions like `coin > 5000000`
+    Query(query::Args),
+    /// Summarize the wallet UTxOs: count, size histogram and slot range
+    Stats(stats::Args),
+    /// Keep a UTxO from being spent by transactions the wallet signs
+    LockUtxo(lock_utxo::Args),
+    /// Release a UTxO locked with lock-utxo
+    UnlockUtxo(unlock_utxo::Args),
+    /// Show the wallet address and its QR code to receive funds
+    Receive(receive::Args),
+    /// Request test ADA from a testnet faucet
+    Faucet(faucet::Args),
+    /// Print the account public key of an HD wallet, to watch it elsewhere
+    Xpub(xpub::Args),
+    /// Write the wallet keys in a format other tools can use
+    Export(export::Args),
+    /// Sign arbitrary data with the wallet key, as CIP-8/CIP-30 dApps expect
+    SignData(sign_data::Args),
+    /// Check a CIP-8 data signature, no private key needed
+    VerifyData(verify_data::Args),
+    /// Write a password protected backup of a wallet
+    Backup(backup::Args),
+    /// Restore a wallet from a backup file
+    RestoreBackup(restore_backup::Args),
+    /// Split the mnemonic of a wallet in shares, some of which restore it
+    BackupShares(backup_shares::Args),
+    /// Restore a wallet from mnemonic shares
+    RestoreShares(restore_shares::Args),
+    /// Print the mnemonic of a wallet created with --store-entropy
+    ExportMnemonic(export_mnemonic::Args),
 }
 
 #[instrument("wallet", skip_all)]
 pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
     match args.command {
         Commands::Create(args) => create::run(args, ctx).await,
+        Commands::CreateBatch(args) => create_batch::run(args, ctx).await,
+        Commands::CreateMultisig(args) => create_multisig::run(args, ctx).await,
         Commands::Restore(args) => restore::run(args, ctx).await,
+        Commands::DeriveAccount(args) => derive_account::run(args, ctx).await,
         Commands::Edit(args) => edit::run(args, ctx).await,
+        Commands::Rename(args) => rename::run(args, ctx).await,
+        Commands::Passwd(args) => passwd::run(args, ctx).await,
         Commands::Import(args) => import::run(args, ctx).await,
         Commands::Info(args) => info::run(args, ctx).await,
+        Commands::InspectAddress(args) => inspect_address::run(args, ctx).await,
         Commands::List => list::run(ctx).await,
+        Commands::Use(args) => use_wallet::run(args, ctx).await,
         Commands::Delete(args) => delete::run(args, ctx).await,
+        Commands::Addresses(args) => addresses::run(args, ctx).await,
         Commands::Balance(args) => balance::run(args, ctx).await,
         Commands::Utxos(args) => utxos::run(args, ctx).await,
+        Commands::Query(args) => query::run(args, ctx).await,
+        Commands::Stats(args) => stats::run(args, ctx).await,
+        Commands::LockUtxo(args) => lock_utxo::run(args, ctx).await,
+        Commands::UnlockUtxo(args) => unlock_utxo::run(args, ctx).await,
+        Commands::Receive(args) => receive::run(args, ctx).await,
+        Commands::Faucet(args) => faucet::run(args, ctx).await,
+        Commands::Xpub(args) => xpub::run(args, ctx).await,
+        Commands::Export(args) => export::run(args, ctx).await,
+        Commands::SignData(args) => sign_data::run(args, ctx).await,
+        Commands::VerifyData(args) => verify_data::run(args, ctx).await,
+        Commands::Backup(args) => backup::run(args, ctx).await,
+        Commands::RestoreBackup(args) => restore_backup::run(args, ctx).await,
+        Commands::BackupShares(args) => backup_shares::run(args, ctx).await,
+        Commands::RestoreShares(args) => restore_shares::run(args, ctx).await,
+        Commands::ExportMnemonic(args) => export_mnemonic::run(args, ctx).await,
     }
 }
@@ -0,0 +1,64 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use tracing::instrument;
+
+use crate::{output::OutputFormatter, wallet::types::Derivation};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to change the spending password of
+    name: String,
+
+    /// Current spending password (leave blank to enter in interactive mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// New spending password (leave blank to enter in interactive mode)
+    #[arg(long)]
+    new_password: Option<String>,
+}
+
+#[instrument(skip_all, name = "passwd")]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    let password = match args.password {
+        Some(password) => password,
+        None => inquire::Password::new("Current password:")
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .without_confirmation()
+            .prompt()?,
+    };
+
+    let new_password = match args.new_password {
+        Some(password) => password,
+        None => inquire::Password::new("New password:")
+            .with_help_message("The new spending password of your wallet")
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .prompt()?,
+    };
+
+    let wallet = wallet.change_password(&password, &new_password)?;
+
+    ctx.store.update_wallet(wallet.clone())?;
+
+    // root key wallets use the spending password as the BIP39 passphrase
+    // unless created with a passphrase of their own
+    if !wallet.has_passphrase {
+        match wallet.derivation {
+            Some(Derivation::Root) => eprintln!(
+                "Restoring this wallet from its mnemonic still requires the previous password."
+            ),
+            None if !wallet.is_hd() && wallet.private_key.is_some() => eprintln!(
+                "If this wallet comes from a mnemonic, restoring it still requires the previous password."
+            ),
+            _ => (),
+        }
+    }
+
+    // Log, print, and finish
+    wallet.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
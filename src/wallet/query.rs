@@ -0,0 +1,326 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use clap::Parser;
+use utxorpc::spec::query::{any_utxo_data::ParsedState, AnyUtxoData};
+
+use crate::{
+    output::{OutputFormat, OutputFormatter},
+    provider::types::AddressMatch,
+};
+
+use super::{
+    discovery::{self, DEFAULT_GAP_LIMIT},
+    utxos::WalletUtxoOutput,
+};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to query the UTxOs of
+    name: String,
+
+    /// Conditions the UTxOs must meet, e.g. `coin > 5000000 and slot > 1234`.
+    /// Fields: coin, slot, index, assets. Operators: =, !=, <, <=, >, >=.
+    /// Conditions are joined with `and` / `or`, `and` binding tighter.
+    filter: Filter,
+
+    /// Name of the provider to use. If undefined, the default provider is used.
+    provider: Option<String>,
+
+    /// How UTxO addresses are matched against the wallet address.
+    #[arg(long = "match", value_enum, default_value_t)]
+    matching: AddressMatch,
+
+    /// Consecutive unused addresses after which HD wallets stop looking
+    /// for more UTxOs.
+    #[arg(long, default_value_t = DEFAULT_GAP_LIMIT, value_parser = clap::value_parser!(u32).range(1..))]
+    gap_limit: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Field {
+    /// Lovelace held by the UTxO
+    Coin,
+    /// Slot of the block that created the UTxO
+    Slot,
+    /// Output index within its transaction
+    Index,
+    /// Number of distinct native assets held by the UTxO
+    Assets,
+}
+
+impl Field {
+    fn value(&self, utxo: &AnyUtxoData) -> Option<u128> {
+        let output = utxo
+            .parsed_state
+            .as_ref()
+            .map(|ParsedState::Cardano(output)| output);
+
+        match self {
+            Field::Coin => crate::utils::format_bigint_opt(&output?.coin).parse().ok(),
+            Field::Slot => utxo.block_ref.as_ref().map(|x| x.slot as u128),
+            Field::Index => utxo.txo_ref.as_ref().map(|x| x.index as u128),
+            Field::Assets => Some(
+                output?
+                    .assets
+                    .iter()
+                    .map(|multiasset| multiasset.assets.len() as u128)
+                    .sum(),
+            ),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "coin" => Ok(Field::Coin),
+            "slot" => Ok(Field::Slot),
+            "index" => Ok(Field::Index),
+            "assets" => Ok(Field::Assets),
+            other => bail!("unknown field '{other}', expected coin, slot, index or assets"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn holds(&self, left: u128, right: u128) -> bool {
+        match self {
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "=" | "==" => Ok(Op::Eq),
+            "!=" => Ok(Op::Ne),
+            "<" => Ok(Op::Lt),
+            "<=" => Ok(Op::Le),
+            ">" => Ok(Op::Gt),
+            ">=" => Ok(Op::Ge),
+            other => bail!("unknown operator '{other}'"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Condition {
+    field: Field,
+    op: Op,
+    value: u128,
+}
+
+impl Condition {
+    /// UTxOs lacking the field (e.g. no slot from the provider) never match.
+    fn matches(&self, utxo: &AnyUtxoData) -> bool {
+        self.field
+            .value(utxo)
+            .is_some_and(|value| self.op.holds(value, self.value))
+    }
+}
+
+/// Conditions joined by `or`, each a group of conditions joined by `and`.
+#[derive(Clone, Debug, PartialEq)]
+struct Filter(Vec<Vec<Condition>>);
+
+impl Filter {
+    fn matches(&self, utxo: &AnyUtxoData) -> bool {
+        self.0
+            .iter()
+            .any(|group| group.iter().all(|condition| condition.matches(utxo)))
+    }
+}
+
+/// Splits a filter into words (fields, numbers, `and`/`or`) and operators,
+/// so that `coin>5` reads the same as `coin > 5`.
+fn tokenize(s: &str) -> Vec<String> {
+    let is_op = |c: char| matches!(c, '<' | '>' | '=' | '!');
+    let mut tokens: Vec<String> = vec![];
+    let mut last_is_op = false;
+
+    for c in s.chars() {
+        if c.is_whitespace() {
+            tokens.push(String::new());
+            continue;
+        }
+
+        match tokens.last_mut() {
+            Some(token) if !token.is_empty() && is_op(c) == last_is_op => token.push(c),
+            _ => tokens.push(c.to_string()),
+        }
+        last_is_op = is_op(c);
+    }
+
+    tokens.retain(|x| !x.is_empty());
+    tokens
+}
+
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s);
+        let mut groups = vec![vec![]];
+        let mut rest = tokens.as_slice();
+
+        loop {
+            let [field, op, value, tail @ ..] = rest else {
+                bail!("expected a condition like `coin > 5000000`")
+            };
+
+            let condition = Condition {
+                field: field.parse()?,
+                op: op.parse()?,
+                value: value
+                    .replace('_', "")
+                    .parse()
+                    .with_context(|| format!("'{value}' is not a number"))?,
+            };
+            groups.last_mut().unwrap().push(condition);
+
+            match tail {
+                [] => break,
+                [joiner, tail @ ..] => {
+                    match joiner.to_lowercase().as_str() {
+                        "and" => (),
+                        "or" => groups.push(vec![]),
+                        other => bail!("expected `and` or `or`, got '{other}'"),
+                    }
+                    rest = tail;
+                }
+            }
+        }
+
+        Ok(Self(groups))
+    }
+}
+
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    let provider = match args.provider {
+        Some(name) => ctx.store.find_provider(&name),
+        None => ctx.store.wallet_provider(Some(wallet)),
+    };
+    let Some(provider) = provider else {
+        bail!("Provider not found.")
+    };
+
+    let mut utxos: Vec<_> =
+        discovery::wallet_utxo_data(wallet, provider, args.matching, args.gap_limit)
+            .await?
+            .into_iter()
+            .filter(|x| args.filter.matches(x))
+            .collect();
+
+    utxos.sort_by_key(|x| {
+        x.txo_ref
+            .as_ref()
+            .map(|reference| (reference.hash.to_vec(), reference.index))
+    });
+
+    let total = utxos.len();
+    let output = WalletUtxoOutput::new(utxos, total);
+
+    let format = if ctx.output_format_overridden {
+        ctx.output_format.clone()
+    } else {
+        OutputFormat::Json
+    };
+
+    output.output(&format, ctx.output_select())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::fixtures::Utxo;
+
+    fn utxo(coin: i64, slot: Option<u64>, index: u32) -> AnyUtxoData {
+        Utxo {
+            coin,
+            slot,
+            index,
+            ..Default::default()
+        }
+        .build()
+    }
+
+    #[test]
+    fn parses_filters() {
+        let filter: Filter = "coin > 5_000_000 and slot>=1234 or index = 0"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            filter,
+            Filter(vec![
+                vec![
+                    Condition {
+                        field: Field::Coin,
+                        op: Op::Gt,
+                        value: 5_000_000
+                    },
+                    Condition {
+                        field: Field::Slot,
+                        op: Op::Ge,
+                        value: 1234
+                    },
+                ],
+                vec![Condition {
+                    field: Field::Index,
+                    op: Op::Eq,
+                    value: 0
+                }],
+            ])
+        );
+
+        assert!("".parse::<Filter>().is_err());
+        assert!("coin >".parse::<Filter>().is_err());
+        assert!("coin ~ 5".parse::<Filter>().is_err());
+        assert!("height > 5".parse::<Filter>().is_err());
+        assert!("coin > 5 xor slot < 2".parse::<Filter>().is_err());
+        assert!("coin > 5 and".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn filters_utxos() {
+        let filter: Filter = "coin > 5000000 and slot > 1234".parse().unwrap();
+
+        assert!(filter.matches(&utxo(6_000_000, Some(2000), 0)));
+        assert!(!filter.matches(&utxo(6_000_000, Some(1000), 0)));
+        assert!(!filter.matches(&utxo(1_000_000, Some(2000), 0)));
+        assert!(!filter.matches(&utxo(6_000_000, None, 0)));
+
+        let filter: Filter = "coin <= 1000000 or index != 0".parse().unwrap();
+
+        assert!(filter.matches(&utxo(1_000_000, None, 0)));
+        assert!(filter.matches(&utxo(6_000_000, None, 1)));
+        assert!(!filter.matches(&utxo(6_000_000, None, 0)));
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::bail;
+use clap::Parser;
+use serde_json::json;
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+use super::info::render_qr;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to receive funds in. If undefined, will use
+    /// default
+    name: Option<String>,
+
+    /// Name of the provider whose network the address is for. If
+    /// undefined, will use default
+    provider: Option<String>,
+}
+
+#[instrument("receive", skip_all, fields(name=args.name))]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let wallet = match args.name {
+        Some(name) => ctx.store.find_wallet(&name),
+        None => ctx.store.default_wallet(),
+    };
+
+    let provider = match args.provider {
+        Some(name) => ctx.store.find_provider(&name),
+        None => ctx.store.wallet_provider(wallet),
+    };
+
+    let (wallet, provider) = match (wallet, provider) {
+        (Some(wallet), Some(provider)) => (wallet, provider),
+        (None, Some(_)) => bail!("Wallet not found."),
+        (Some(_), None) => bail!("Provider not found."),
+        (None, None) => bail!("Wallet and provider not found."),
+    };
+
+    let address = wallet.address(provider.is_testnet())?.to_string();
+
+    match ctx.output_format {
+        OutputFormat::Json => crate::output::print_json(
+            &json!({ "address": address, "provider": provider.name.to_string() }),
+            ctx.output_select(),
+        )?,
+        OutputFormat::Table | OutputFormat::Markdown => {
+            println!("{}", render_qr(&address)?);
+            println!("{address}");
+        }
+    }
+
+    Ok(())
+}
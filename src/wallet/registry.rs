@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::types::{asset_subject, BalanceAsset, TokenMetadata};
+
+/// The Cardano token registry of mainnet tokens.
+pub const DEFAULT_REGISTRY_URL: &str = "https://tokens.cardano.org";
+
+#[derive(Deserialize)]
+struct Property<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+struct Subject {
+    subject: String,
+    name: Option<Property<String>>,
+    ticker: Option<Property<String>>,
+    decimals: Option<Property<u8>>,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    subjects: Vec<Subject>,
+}
+
+fn parse_response(response: Response) -> BTreeMap<String, TokenMetadata> {
+    response
+        .subjects
+        .into_iter()
+        .map(|x| {
+            let metadata = TokenMetadata {
+                name: x.name.map(|x| x.value),
+                ticker: x.ticker.map(|x| x.value),
+                decimals: x.decimals.map(|x| x.value),
+            };
+            (x.subject, metadata)
+        })
+        .collect()
+}
+
+/// Looks up the display details of the given assets in a token registry.
+/// Assets the registry doesn't know are left out.
+pub async fn lookup(url: &str, assets: &[BalanceAsset]) -> Result<BTreeMap<String, TokenMetadata>> {
+    let subjects: Vec<String> = assets
+        .iter()
+        .flat_map(|entry| {
+            entry
+                .assets
+                .iter()
+                .map(|asset| asset_subject(&entry.policy_id, &asset.name))
+        })
+        .collect();
+
+    if subjects.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let response: Response = reqwest::Client::new()
+        .post(format!("{}/metadata/query", url.trim_end_matches('/')))
+        .json(&json!({
+            "subjects": subjects,
+            "properties": ["name", "ticker", "decimals"],
+        }))
+        .send()
+        .await
+        .context("failed to reach the token registry")?
+        .error_for_status()
+        .context("token registry rejected the query")?
+        .json()
+        .await
+        .context("unexpected token registry response")?;
+
+    Ok(parse_response(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_registry_responses() {
+        let response: Response = serde_json::from_str(
+            r#"{"subjects": [
+                {
+                    "subject": "aabb",
+                    "name": {"value": "Token", "sequenceNumber": 0, "signatures": []},
+                    "ticker": {"value": "TKN", "sequenceNumber": 0, "signatures": []},
+                    "decimals": {"value": 6, "sequenceNumber": 0, "signatures": []}
+                },
+                {"subject": "ccdd", "name": {"value": "Plain"}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let metadata = parse_response(response);
+
+        assert_eq!(
+            metadata["aabb"],
+            TokenMetadata {
+                name: Some("Token".to_string()),
+                ticker: Some("TKN".to_string()),
+                decimals: Some(6),
+            }
+        );
+        assert_eq!(metadata["ccdd"].decimals, None);
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::Parser;
+use tracing::instrument;
+
+use crate::{output::OutputFormatter, utils::Name};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Current name of the wallet
+    name: String,
+
+    /// New name for the wallet
+    new_name: String,
+}
+
+#[instrument(skip_all, name = "rename")]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
+    let new_name = Name::try_from(args.new_name)?;
+
+    let wallet = ctx.store.rename_wallet(&args.name, new_name)?.clone();
+
+    // Log, print, and finish
+    wallet.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
@@ -2,7 +2,11 @@ use anyhow::bail;
 use clap::Parser;
 use tracing::instrument;
 
-use crate::{output::OutputFormatter, utils::Name, wallet::types::Wallet};
+use crate::{
+    output::OutputFormatter,
+    utils::Name,
+    wallet::types::{english_mnemonic, Derivation, Wallet, WordList},
+};
 
 #[derive(Parser, Clone)]
 pub struct Args {
@@ -24,6 +28,21 @@ pub struct Args {
     /// disable password requirement (not recommended)
     #[arg(long)]
     r#unsafe: bool,
+
+    /// how keys are derived from the mnemonic. Use `cip1852` for
+    /// mnemonics coming from other Cardano wallets
+    #[arg(long, value_enum, default_value_t)]
+    derivation: Derivation,
+
+    /// BIP39 passphrase (the "25th word") the mnemonic was used with. If
+    /// undefined, cip1852 wallets use an empty one, like wallets without
+    /// passphrase, and root wallets the spending password
+    #[arg(long)]
+    passphrase: Option<String>,
+
+    /// wordlist of the mnemonic. If undefined, it's detected from the words
+    #[arg(long, value_enum)]
+    language: Option<WordList>,
 }
 
 #[instrument("restore", skip_all)]
@@ -61,17 +80,21 @@ pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
             .prompt()
             .map_err(anyhow::Error::msg)?,
     };
+    let mnemonic = english_mnemonic(&mnemonic, args.language)?;
+
     let wallet = Wallet::try_from_mnemonic(
         &name,
         &password,
         &mnemonic,
-        ctx.store.default_wallet().is_none(),
+        ctx.store.flagged_default_wallet().is_none(),
         args.r#unsafe,
+        args.derivation,
+        args.passphrase.as_deref(),
     )?;
 
     ctx.store.add_wallet(&wallet)?;
 
     // Log, print, and finish
-    wallet.output(&ctx.output_format);
+    wallet.output(&ctx.output_format, ctx.output_select())?;
     Ok(())
 }
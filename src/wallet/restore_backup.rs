@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use clap::Parser;
+use tracing::instrument;
+
+use crate::{output::OutputFormatter, utils::Name};
+
+use super::backup::Bundle;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Path of the backup file
+    file: PathBuf,
+
+    /// Password protecting the backup (leave blank to enter in interactive
+    /// mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Restore the wallet under another name
+    #[arg(long)]
+    name: Option<String>,
+}
+
+#[instrument("restore-backup", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> Result<()> {
+    let contents = std::fs::read_to_string(&args.file)
+        .with_context(|| format!("reading {}", args.file.display()))?;
+    let bundle: Bundle = serde_json::from_str(&contents).context("not a cshell wallet backup")?;
+
+    let password = match args.password {
+        Some(password) => password,
+        None => inquire::Password::new("Backup password:")
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .without_confirmation()
+            .prompt()?,
+    };
+
+    let mut wallet = bundle.open(&password)?;
+
+    if let Some(name) = args.name {
+        wallet.name = Name::try_from(name)?;
+    }
+
+    if ctx
+        .store
+        .wallets()
+        .iter()
+        .any(|x| x.name.normalized() == wallet.name.normalized())
+    {
+        bail!(
+            "Wallet with the same or conflicting name '{}' already exists, use --name to restore it under another one.",
+            wallet.name
+        )
+    }
+
+    wallet.is_default = ctx.store.flagged_default_wallet().is_none();
+    wallet.modified = Local::now();
+
+    ctx.store.add_wallet(&wallet)?;
+
+    // Log, print, and finish
+    wallet.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use anyhow::bail;
+use bip39::{Language, Mnemonic};
+use clap::Parser;
+use tracing::instrument;
+
+use crate::{
+    output::OutputFormatter,
+    utils::Name,
+    wallet::types::{Derivation, Wallet},
+};
+
+use super::shares::{self, Share};
+
+#[derive(Parser)]
+pub struct Args {
+    /// name to identify the wallet
+    /// (leave blank to enter in interactive mode)
+    #[arg(long)]
+    name: Option<String>,
+
+    /// share created by `wallet backup-shares`. Repeat for each share
+    /// (leave blank to enter in interactive mode)
+    #[arg(long = "share")]
+    shares: Vec<String>,
+
+    /// spending password used to encrypt the private keys
+    /// (leave blank to enter in interactive mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// disable password requirement (not recommended)
+    #[arg(long)]
+    r#unsafe: bool,
+
+    /// how keys are derived from the mnemonic
+    #[arg(long, value_enum, default_value_t)]
+    derivation: Derivation,
+
+    /// BIP39 passphrase the mnemonic was used with. If undefined, the
+    /// spending password is used as passphrase
+    #[arg(long)]
+    passphrase: Option<String>,
+
+    /// keep the mnemonic entropy, so it can be split again or exported later
+    #[arg(long)]
+    store_entropy: bool,
+}
+
+#[instrument("restore-shares", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
+    let raw_name = match args.name {
+        Some(name) => name,
+        None => inquire::Text::new("Name of the wallet:").prompt()?,
+    };
+    let name = Name::try_from(raw_name)?;
+
+    if ctx
+        .store
+        .wallets()
+        .iter()
+        .any(|wallet| wallet.name.normalized() == name.normalized())
+    {
+        bail!(
+            "Wallet with the same or conflicting name '{}' already exists.",
+            name
+        )
+    }
+
+    let mut given = args
+        .shares
+        .iter()
+        .map(|x| Share::decode(x))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if given.is_empty() {
+        loop {
+            let share = inquire::Text::new(&format!("Share {}:", given.len() + 1))
+                .with_help_message("Leave blank once every share is entered")
+                .prompt()?;
+
+            if share.trim().is_empty() {
+                break;
+            }
+
+            given.push(Share::decode(&share)?);
+        }
+    }
+
+    let entropy = shares::combine(&given)?;
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy)?.to_string();
+
+    let password = match args.r#unsafe {
+        true => String::new(),
+        false => match args.password {
+            Some(password) => password,
+            None => inquire::Password::new("Password:")
+                .with_help_message("The spending password of your wallet")
+                .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                .prompt()?,
+        },
+    };
+
+    let wallet = Wallet::try_from_mnemonic(
+        &name,
+        &password,
+        &mnemonic,
+        ctx.store.flagged_default_wallet().is_none(),
+        args.r#unsafe,
+        args.derivation,
+        args.passphrase.as_deref(),
+    )?;
+
+    let wallet = match args.store_entropy {
+        true => wallet.store_entropy(&mnemonic, &password)?,
+        false => wallet,
+    };
+
+    ctx.store.add_wallet(&wallet)?;
+
+    // Log, print, and finish
+    wallet.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use anyhow::{bail, Context, Result};
+use bech32::{Bech32m, Hrp};
+
+const HRP: Hrp = Hrp::parse_unchecked("cshare");
+const VERSION: u8 = 1;
+
+/// One share of a secret split with Shamir's secret sharing over GF(256).
+/// Shares of the same split carry the same random `id`, so mixing shares of
+/// different backups is caught instead of yielding garbage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    id: u16,
+    threshold: u8,
+    index: u8,
+    data: Vec<u8>,
+}
+
+/// Multiplication in GF(256) with the AES polynomial.
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+
+    product
+}
+
+/// Multiplicative inverse in GF(256), as a^254.
+fn inv(a: u8) -> u8 {
+    let mut result = 1;
+    let mut base = a;
+    let mut exp = 254;
+
+    while exp > 0 {
+        if exp & 1 != 0 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// Splits `secret` in `shares` shares, any `threshold` of which rebuild it.
+pub fn split<Rng>(mut rng: Rng, secret: &[u8], threshold: u8, shares: u8) -> Result<Vec<Share>>
+where
+    Rng: rand_core::RngCore + rand_core::CryptoRng,
+{
+    if threshold < 2 || threshold > shares {
+        bail!("threshold must be between 2 and the number of shares")
+    }
+
+    let id = rng.next_u32() as u16;
+
+    // one random polynomial per secret byte, the constant term being the byte
+    let coefficients: Vec<Vec<u8>> = secret
+        .iter()
+        .map(|byte| {
+            let mut poly = vec![0; threshold as usize];
+            rng.fill_bytes(&mut poly[1..]);
+            poly[0] = *byte;
+            poly
+        })
+        .collect();
+
+    Ok((1..=shares)
+        .map(|index| Share {
+            id,
+            threshold,
+            index,
+            data: coefficients
+                .iter()
+                .map(|poly| poly.iter().rev().fold(0, |acc, c| mul(acc, index) ^ c))
+                .collect(),
+        })
+        .collect())
+}
+
+/// Rebuilds the secret out of at least `threshold` shares of the same split.
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>> {
+    let Some(first) = shares.first() else {
+        bail!("no shares given")
+    };
+
+    if shares.iter().any(|x| {
+        x.id != first.id || x.threshold != first.threshold || x.data.len() != first.data.len()
+    }) {
+        bail!("shares belong to different backups")
+    }
+
+    let mut indexes: Vec<u8> = shares.iter().map(|x| x.index).collect();
+    indexes.sort();
+    indexes.dedup();
+    if indexes.len() != shares.len() {
+        bail!("the same share was given more than once")
+    }
+
+    if shares.len() < first.threshold as usize {
+        bail!(
+            "{} shares are needed, got {}",
+            first.threshold,
+            shares.len()
+        )
+    }
+
+    let shares = &shares[..first.threshold as usize];
+
+    // Lagrange interpolation at x = 0, where addition and subtraction are xor
+    let weights: Vec<u8> = shares
+        .iter()
+        .map(|x| {
+            shares
+                .iter()
+                .filter(|y| y.index != x.index)
+                .fold(1, |acc, y| mul(acc, mul(y.index, inv(y.index ^ x.index))))
+        })
+        .collect();
+
+    Ok((0..first.data.len())
+        .map(|i| {
+            shares
+                .iter()
+                .zip(&weights)
+                .fold(0, |acc, (share, weight)| acc ^ mul(share.data[i], *weight))
+        })
+        .collect())
+}
+
+impl Share {
+    pub fn encode(&self) -> String {
+        let mut payload = vec![VERSION];
+        payload.extend(self.id.to_be_bytes());
+        payload.push(self.threshold);
+        payload.push(self.index);
+        payload.extend(&self.data);
+
+        bech32::encode::<Bech32m>(HRP, &payload).unwrap()
+    }
+
+    pub fn decode(value: &str) -> Result<Self> {
+        let (hrp, payload) = bech32::decode(value.trim()).context("invalid share")?;
+
+        if hrp != HRP {
+            bail!("expected a cshare1... share, got '{hrp}'")
+        }
+
+        match payload.as_slice() {
+            [VERSION, _, _, threshold, ..] if *threshold < 2 => {
+                bail!("invalid share threshold {threshold}")
+            }
+            [VERSION, _, _, _, 0, ..] => bail!("invalid share index 0"),
+            [VERSION, id0, id1, threshold, index, data @ ..] if !data.is_empty() => Ok(Self {
+                id: u16::from_be_bytes([*id0, *id1]),
+                threshold: *threshold,
+                index: *index,
+                data: data.to_vec(),
+            }),
+            [VERSION, ..] => bail!("truncated share"),
+            _ => bail!("unsupported share version"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rng() -> rand_core::UnwrapErr<rand_core::OsRng> {
+        rand_core::UnwrapErr(rand_core::OsRng)
+    }
+
+    #[test]
+    fn any_threshold_shares_rebuild_the_secret() {
+        let secret: Vec<u8> = (0..32).collect();
+        let shares = split(rng(), &secret, 3, 5).unwrap();
+
+        for picked in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<_> = picked.iter().map(|i| shares[*i].clone()).collect();
+            assert_eq!(combine(&subset).unwrap(), secret);
+        }
+
+        assert!(combine(&shares[..2]).is_err());
+        assert!(combine(&[shares[0].clone(), shares[0].clone(), shares[1].clone()]).is_err());
+
+        let other_id = Share {
+            id: shares[0].id.wrapping_add(1),
+            ..shares[0].clone()
+        };
+        assert!(combine(&[other_id, shares[1].clone(), shares[2].clone()]).is_err());
+
+        let other_threshold = Share {
+            threshold: 2,
+            ..shares[0].clone()
+        };
+        assert!(combine(&[other_threshold, shares[1].clone(), shares[2].clone()]).is_err());
+
+        assert!(split(rng(), &secret, 1, 3).is_err());
+        assert!(split(rng(), &secret, 4, 3).is_err());
+    }
+
+    #[test]
+    fn shares_round_trip_as_text() {
+        let shares = split(rng(), &[7; 16], 2, 3).unwrap();
+        let encoded = shares[1].encode();
+
+        assert!(encoded.starts_with("cshare1"));
+        assert_eq!(Share::decode(&encoded).unwrap(), shares[1]);
+        assert!(Share::decode("xpub1qqqqqq").is_err());
+
+        // a zero threshold or index would combine into an all-zero secret
+        let zero_threshold = Share {
+            threshold: 0,
+            ..shares[1].clone()
+        };
+        assert!(Share::decode(&zero_threshold.encode()).is_err());
+
+        let zero_index = Share {
+            index: 0,
+            ..shares[1].clone()
+        };
+        assert!(Share::decode(&zero_index.encode()).is_err());
+    }
+
+    #[test]
+    fn field_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, inv(a)), 1);
+        }
+    }
+}
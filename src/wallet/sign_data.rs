@@ -0,0 +1,108 @@
+use anyhow::bail;
+use clap::Parser;
+use serde_json::json;
+use tracing::instrument;
+
+use crate::{output::OutputFormat, provider::types::NetworkName};
+
+use super::cip8;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to sign with
+    name: String,
+
+    /// Data to sign, as hex or as the path to a file
+    #[arg(long)]
+    payload: String,
+
+    /// Spending password of the wallet (leave blank to enter in interactive
+    /// mode)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Network of the signed address. If undefined, will use the one of the
+    /// wallet provider
+    #[arg(long, value_enum)]
+    network: Option<NetworkName>,
+
+    /// Name of the provider to take the network from
+    #[arg(long, conflicts_with = "network")]
+    provider: Option<String>,
+}
+
+#[instrument("sign-data", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    if wallet.is_multisig() {
+        bail!(
+            "wallet '{}' is a multisig wallet, sign with one of its signers instead",
+            wallet.name
+        )
+    }
+
+    let payload = cip8::read_payload(&args.payload)?;
+
+    let password = match (wallet.is_unsafe, args.password) {
+        (true, _) => None,
+        (false, Some(password)) => Some(password),
+        (false, None) => Some(
+            inquire::Password::new("Password:")
+                .with_help_message("The spending password of your wallet")
+                .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                .without_confirmation()
+                .prompt()?,
+        ),
+    };
+
+    let key = wallet.signing_key(&password)?;
+
+    let provider = match &args.provider {
+        Some(name) => match ctx.store.find_provider(name) {
+            Some(provider) => Some(provider),
+            None => bail!("Provider not found"),
+        },
+        None => ctx.store.wallet_provider(Some(wallet)),
+    };
+
+    // the address is part of the signature, so a guessed network would
+    // produce one that verifies against the wrong address
+    let is_testnet = match (args.network, provider, wallet.network) {
+        (Some(network), _, _) => network != NetworkName::Mainnet,
+        (None, Some(provider), _) => provider.is_testnet(),
+        (None, None, Some(network)) => network != NetworkName::Mainnet,
+        (None, None, None) => {
+            bail!("can't tell the network of the address, use --network or --provider")
+        }
+    };
+    let address = wallet.address(is_testnet)?;
+
+    let signed = cip8::sign(&key, &address, &payload)?;
+
+    match ctx.output_format {
+        OutputFormat::Json => crate::output::print_json(
+            &json!({
+                "address": address.to_string(),
+                "signature": hex::encode(&signed.signature),
+                "key": hex::encode(&signed.key),
+            }),
+            ctx.output_select(),
+        )?,
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let mut table = crate::output::table(ctx.output_format.table_style());
+            table.set_header(vec!["Property", "Value"]);
+            table.add_row(vec!["Address".to_string(), address.to_string()]);
+            table.add_row(vec![
+                "Signature".to_string(),
+                hex::encode(&signed.signature),
+            ]);
+            table.add_row(vec!["Key".to_string(), hex::encode(&signed.key)]);
+            println!("{table}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+
+use anyhow::bail;
+use clap::Parser;
+use serde_json::{json, Value};
+use utxorpc::spec::query::{any_utxo_data::ParsedState, AnyUtxoData};
+
+use crate::{
+    output::{OutputFormatter, TableStyle},
+    provider::types::AddressMatch,
+};
+
+use super::discovery::{self, DEFAULT_GAP_LIMIT};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to summarize. If undefined, the default wallet is used.
+    name: Option<String>,
+
+    /// Name of the provider to use. If undefined, the default provider is used.
+    provider: Option<String>,
+
+    /// How UTxO addresses are matched against the wallet address.
+    #[arg(long = "match", value_enum, default_value_t)]
+    matching: AddressMatch,
+
+    /// Consecutive unused addresses after which HD wallets stop looking
+    /// for more UTxOs.
+    #[arg(long, default_value_t = DEFAULT_GAP_LIMIT, value_parser = clap::value_parser!(u32).range(1..))]
+    gap_limit: u32,
+}
+
+/// Upper bounds (exclusive, in lovelace) of the UTxO size histogram.
+const BUCKETS: [(u128, &str); 5] = [
+    (1_000_000, "< 1 ADA"),
+    (10_000_000, "1 - 10 ADA"),
+    (100_000_000, "10 - 100 ADA"),
+    (1_000_000_000, "100 - 1000 ADA"),
+    (u128::MAX, ">= 1000 ADA"),
+];
+
+struct WalletStats {
+    utxos: usize,
+    coin: u128,
+    /// Distinct native assets held across UTxOs.
+    assets: usize,
+    /// UTxO count per `BUCKETS` entry, to tell how fragmented the wallet is.
+    histogram: [usize; BUCKETS.len()],
+    /// Slots of the oldest and newest UTxOs, when the provider reports them.
+    first_slot: Option<u64>,
+    last_slot: Option<u64>,
+}
+
+impl WalletStats {
+    fn from_utxos(utxos: &[AnyUtxoData]) -> Self {
+        let mut coin = 0;
+        let mut assets = HashSet::new();
+        let mut histogram = [0; BUCKETS.len()];
+
+        for utxo in utxos {
+            let Some(ParsedState::Cardano(output)) = &utxo.parsed_state else {
+                continue;
+            };
+
+            let value: u128 = crate::utils::format_bigint_opt(&output.coin)
+                .parse()
+                .unwrap_or_default();
+            coin += value;
+
+            let bucket = BUCKETS
+                .iter()
+                .position(|(bound, _)| value < *bound)
+                .unwrap_or(BUCKETS.len() - 1);
+            histogram[bucket] += 1;
+
+            for multiasset in &output.assets {
+                for asset in &multiasset.assets {
+                    assets.insert((multiasset.policy_id.clone(), asset.name.clone()));
+                }
+            }
+        }
+
+        let slots = utxos
+            .iter()
+            .filter_map(|x| x.block_ref.as_ref().map(|x| x.slot));
+
+        Self {
+            utxos: utxos.len(),
+            coin,
+            assets: assets.len(),
+            histogram,
+            first_slot: slots.clone().min(),
+            last_slot: slots.max(),
+        }
+    }
+}
+
+impl OutputFormatter for WalletStats {
+    fn to_table(&self, style: TableStyle) {
+        let slot = |x: Option<u64>| x.map_or("-".to_string(), |x| x.to_string());
+
+        let mut table = crate::output::table(style);
+
+        table.set_header(vec!["Property", "Value"]);
+        table.add_row(vec!["UTxOs".to_string(), self.utxos.to_string()]);
+        table.add_row(vec!["Lovelace".to_string(), self.coin.to_string()]);
+        table.add_row(vec!["Assets".to_string(), self.assets.to_string()]);
+        table.add_row(vec!["First Slot".to_string(), slot(self.first_slot)]);
+        table.add_row(vec!["Last Slot".to_string(), slot(self.last_slot)]);
+
+        println!("{table}");
+
+        let mut histogram = crate::output::table(style);
+
+        histogram.set_header(vec!["UTxO Size", "UTxOs"]);
+        for ((_, label), count) in BUCKETS.iter().zip(self.histogram) {
+            histogram.add_row(vec![label.to_string(), count.to_string()]);
+        }
+
+        println!("{histogram}");
+    }
+
+    fn to_json(&self) -> anyhow::Result<Value> {
+        Ok(json!({
+            "utxos": self.utxos,
+            "coin": self.coin.to_string(),
+            "assets": self.assets,
+            "histogram": BUCKETS
+                .iter()
+                .zip(self.histogram)
+                .map(|((_, label), count)| json!({ "range": label, "utxos": count }))
+                .collect::<Vec<_>>(),
+            "first_slot": self.first_slot,
+            "last_slot": self.last_slot,
+        }))
+    }
+}
+
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let wallet = match args.name {
+        Some(name) => ctx.store.find_wallet(&name),
+        None => ctx.store.default_wallet(),
+    };
+
+    let provider = match args.provider {
+        Some(name) => ctx.store.find_provider(&name),
+        None => ctx.store.wallet_provider(wallet),
+    };
+
+    match (wallet, provider) {
+        (Some(wallet), Some(provider)) => {
+            let utxos =
+                discovery::wallet_utxo_data(wallet, provider, args.matching, args.gap_limit)
+                    .await?;
+
+            WalletStats::from_utxos(&utxos).output(&ctx.output_format, ctx.output_select())?;
+
+            Ok(())
+        }
+        (None, Some(_)) => bail!("Wallet not found."),
+        (Some(_), None) => bail!("Provider not found."),
+        (None, None) => bail!("Wallet and provider not found."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::fixtures::Utxo;
+
+    fn utxo(coin: i64, slot: u64, asset: &[u8]) -> AnyUtxoData {
+        Utxo {
+            coin,
+            slot: Some(slot),
+            asset: Some((1, asset)),
+            ..Default::default()
+        }
+        .build()
+    }
+
+    #[test]
+    fn summarizes_utxos() {
+        let stats = WalletStats::from_utxos(&[
+            utxo(500_000, 30, b"foo"),
+            utxo(1_000_000, 10, b"foo"),
+            utxo(5_000_000, 20, b"bar"),
+            utxo(2_000_000_000, 40, b"bar"),
+        ]);
+
+        assert_eq!(stats.utxos, 4);
+        assert_eq!(stats.coin, 2_006_500_000);
+        assert_eq!(stats.assets, 2);
+        assert_eq!(stats.histogram, [1, 2, 0, 0, 1]);
+        assert_eq!(stats.first_slot, Some(10));
+        assert_eq!(stats.last_slot, Some(40));
+
+        let json = stats.to_json().unwrap();
+        assert_eq!(json["coin"], "2006500000");
+        assert_eq!(
+            json["histogram"][1],
+            json!({ "range": "1 - 10 ADA", "utxos": 2 })
+        );
+
+        let empty = WalletStats::from_utxos(&[]);
+        assert_eq!(empty.first_slot, None);
+        assert_eq!(empty.histogram, [0; 5]);
+    }
+}
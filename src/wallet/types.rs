@@ -1,22 +1,28 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use bech32::Bech32;
 use bip39::{Language, Mnemonic};
 use chrono::{DateTime, Local};
-use comfy_table::Table;
+use clap::ValueEnum;
 use cryptoxide::chacha20poly1305::ChaCha20Poly1305;
 use cryptoxide::kdf::argon2;
 use cryptoxide::{hmac::Hmac, pbkdf2::pbkdf2, sha2::Sha512};
 use ed25519_bip32::{self, XPrv, XPub, XPRV_SIZE};
 use pallas::{
     codec::{minicbor, utils::NonEmptySet},
-    crypto::key::ed25519::{self, PublicKey, SecretKey, SecretKeyExtended, Signature},
+    crypto::{
+        hash::{Hash, Hasher},
+        key::ed25519::{self, PublicKey, SecretKey, SecretKeyExtended, Signature},
+    },
     ledger::{
-        addresses::{Address, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart},
+        addresses::{
+            Address, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+            StakeAddress,
+        },
         primitives::{
-            conway::{Tx, VKeyWitness},
+            conway::{NativeScript, Tx, VKeyWitness},
             Fragment,
         },
-        traverse::ComputeHash,
+        traverse::{ComputeHash, MultiEraTx},
     },
 };
 
@@ -24,7 +30,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::str::FromStr;
 
-use crate::{output::OutputFormatter, utils, utils::Name};
+use crate::{
+    output::{OutputFormatter, TableStyle},
+    provider::types::NetworkName,
+    utils,
+    utils::Name,
+};
 
 const ITERATIONS: u32 = 2500;
 const VERSION_SIZE: usize = 1;
@@ -43,57 +54,267 @@ pub struct Wallet {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(with = "utils::option_hex_vec_u8")]
     pub private_key: Option<Vec<u8>>,
+    /// BIP39 entropy, only kept for wallets created with `--store-entropy`.
+    /// Encrypted with the spending password unless the wallet is unsafe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "utils::option_hex_vec_u8")]
+    pub entropy: Option<Vec<u8>>,
+    /// CIP-1852 account public key of HD wallets, used to derive the
+    /// payment addresses beyond the first one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "utils::option_hex_vec_u8")]
+    pub account_public_key: Option<Vec<u8>>,
+    /// CBOR of the N-of-M native script of multisig wallets, which hold no
+    /// keys of their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "utils::option_hex_vec_u8")]
+    pub script: Option<Vec<u8>>,
+    /// Script address tracked by wallets registered to monitor a contract,
+    /// which hold no keys either.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "utils::option_hex_vec_u8")]
+    pub tracked_address: Option<Vec<u8>>,
+    /// `<tx hash>#<index>` refs of the UTxOs this wallet refuses to spend.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locked_utxos: Vec<String>,
+    /// Whether the mnemonic root was derived with a BIP39 passphrase
+    /// instead of the spending password.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_passphrase: bool,
+    /// How the keys of mnemonic wallets were derived. Unknown for wallets
+    /// created before it was recorded, and for wallets without mnemonic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation: Option<Derivation>,
     pub created: DateTime<Local>,
     pub modified: DateTime<Local>,
     pub is_default: bool,
     #[serde(default)]
     pub is_unsafe: bool,
+    /// Network whose default provider commands use for this wallet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkName>,
+}
+
+/// Mnemonic lengths allowed by BIP39.
+pub const MNEMONIC_WORDS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Generates a new english mnemonic with the given amount of words.
+pub fn generate_mnemonic(words: usize) -> Result<String> {
+    if !MNEMONIC_WORDS.contains(&words) {
+        bail!("mnemonics must have 12, 15, 18, 21 or 24 words, got {words}")
+    }
+
+    let mnemonic =
+        Mnemonic::generate_in_with(&mut bip39::rand_core::OsRng, Language::English, words)?;
+
+    Ok(mnemonic.to_string())
+}
+
+fn validate_mnemonic(mnemonic: &str) -> Result<()> {
+    let words = mnemonic.split_whitespace().count();
+
+    if !MNEMONIC_WORDS.contains(&words) {
+        bail!("mnemonics must have 12, 15, 18, 21 or 24 words, got {words}")
+    }
+
+    Ok(())
+}
+
+/// BIP39 wordlists a mnemonic can be written with.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum WordList {
+    English,
+    Japanese,
+    Korean,
+    Spanish,
+    ChineseSimplified,
+    ChineseTraditional,
+    French,
+    Italian,
+    Czech,
+    Portuguese,
+}
+
+impl From<WordList> for Language {
+    fn from(value: WordList) -> Self {
+        match value {
+            WordList::English => Language::English,
+            WordList::Japanese => Language::Japanese,
+            WordList::Korean => Language::Korean,
+            WordList::Spanish => Language::Spanish,
+            WordList::ChineseSimplified => Language::SimplifiedChinese,
+            WordList::ChineseTraditional => Language::TraditionalChinese,
+            WordList::French => Language::French,
+            WordList::Italian => Language::Italian,
+            WordList::Czech => Language::Czech,
+            WordList::Portuguese => Language::Portuguese,
+        }
+    }
+}
+
+/// Rewrites a mnemonic with the English wordlist, the one the rest of
+/// cshell works with. Keys only depend on the entropy, so they stay the
+/// same. The wordlist is detected unless given.
+pub fn english_mnemonic(mnemonic: &str, wordlist: Option<WordList>) -> Result<String> {
+    let parsed = match wordlist {
+        Some(wordlist) => Mnemonic::parse_in(wordlist.into(), mnemonic),
+        None => Mnemonic::parse(mnemonic),
+    }
+    .context("Error parsing mnemonic")?;
+
+    Ok(Mnemonic::from_entropy_in(Language::English, &parsed.to_entropy())?.to_string())
+}
+
+/// Checks a `<tx hash>#<index>` UTxO ref, returning it in lowercase.
+pub fn utxo_ref(s: &str) -> Result<String> {
+    let Some((hash, index)) = s.split_once('#') else {
+        bail!("expected a UTxO ref as <tx hash>#<index>")
+    };
+
+    let hash = hex::decode(hash).context("tx hash is not hex")?;
+    if hash.len() != 32 {
+        bail!("tx hashes are 32 bytes long, got {}", hash.len())
+    }
+
+    let index: u64 = index.parse().context("invalid output index")?;
+
+    Ok(format!("{}#{index}", hex::encode(hash)))
+}
+
+/// How wallet keys are derived from the root key of the mnemonic.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Derivation {
+    /// Sign with the root key itself (legacy cshell wallets)
+    #[default]
+    Root,
+    /// CIP-1852 account 0 (m/1852'/1815'/0'), signing with its first
+    /// payment key
+    Cip1852,
+}
+
+/// CIP-1852 chain of an HD address.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Role {
+    /// Addresses handed out to receive funds (m/.../0/index)
+    #[default]
+    External,
+    /// Change addresses (m/.../1/index)
+    Change,
+}
+
+impl Role {
+    fn chain(self) -> u32 {
+        match self {
+            Role::External => 0,
+            Role::Change => 1,
+        }
+    }
+}
+
+const HARDENED: u32 = 0x8000_0000;
+
+impl Derivation {
+    /// Returns the signing key and, for HD derivations, the account key.
+    fn derive(self, root: Bip32PrivateKey) -> (Bip32PrivateKey, Option<Bip32PrivateKey>) {
+        match self {
+            Derivation::Root => (root, None),
+            Derivation::Cip1852 => {
+                let account = cip1852_account(&root, 0);
+
+                (account.derive(0).derive(0), Some(account))
+            }
+        }
+    }
+}
+
+/// CIP-1852 account key at m/1852'/1815'/index'.
+fn cip1852_account(root: &Bip32PrivateKey, index: u32) -> Bip32PrivateKey {
+    root.derive(HARDENED | 1852)
+        .derive(HARDENED | 1815)
+        .derive(HARDENED | index)
+}
+
+fn shelley_address(pk: &PublicKey, stake: Option<&PublicKey>, is_testnet: bool) -> ShelleyAddress {
+    let network = match is_testnet {
+        true => Network::Testnet,
+        false => Network::Mainnet,
+    };
+
+    let delegation = match stake {
+        Some(stake) => ShelleyDelegationPart::key_hash(stake.compute_hash()),
+        None => ShelleyDelegationPart::Null,
+    };
+
+    ShelleyAddress::new(
+        network,
+        ShelleyPaymentPart::key_hash(pk.compute_hash()),
+        delegation,
+    )
 }
 
 impl Wallet {
+    /// Creates a wallet from a new 24 words mnemonic. Unless a BIP39
+    /// `passphrase` is given, CIP-1852 wallets use an empty one, like other
+    /// Cardano wallets, and root wallets the spending password.
     pub fn try_from(
         name: &str,
         password: &str,
         is_default: bool,
         is_unsafe: bool,
+        derivation: Derivation,
+        passphrase: Option<&str>,
     ) -> Result<NewWallet> {
-        let (private_key, mnemonic) =
-            Bip32PrivateKey::generate_with_mnemonic(bip39::rand_core::OsRng, password.to_string());
-        let public_key = private_key.to_public().as_bytes();
+        let mnemonic = generate_mnemonic(24)?;
 
-        let private_key = private_key.to_ed25519_private_key();
-        let private_key = match is_unsafe {
-            true => private_key.as_bytes(),
-            false => encrypt_private_key(
-                rand_core::UnwrapErr(rand_core::OsRng),
-                private_key,
-                &password.to_string(),
-            ),
-        };
+        let wallet = Self::try_from_mnemonic(
+            name, password, &mnemonic, is_default, is_unsafe, derivation, passphrase,
+        )?;
 
-        Ok((
-            mnemonic.to_string(),
-            Self {
-                name: Name::try_from(name)?,
-                private_key: Some(private_key),
-                public_key,
-                created: Local::now(),
-                modified: Local::now(),
-                is_default,
-                is_unsafe,
-            },
-        ))
+        Ok((mnemonic, wallet))
     }
 
+    /// Restores a wallet from its mnemonic. Unless a BIP39 `passphrase` is
+    /// given, CIP-1852 wallets use an empty one and root wallets the
+    /// spending password.
     pub fn try_from_mnemonic(
         name: &str,
         password: &str,
         mnemonic: &str,
         is_default: bool,
         is_unsafe: bool,
+        derivation: Derivation,
+        passphrase: Option<&str>,
     ) -> Result<Self> {
-        let private_key =
-            Bip32PrivateKey::from_bip39_mnenomic(mnemonic.to_string(), password.to_string())?;
+        validate_mnemonic(mnemonic)?;
+
+        let default_passphrase = match derivation {
+            Derivation::Root => password,
+            Derivation::Cip1852 => "",
+        };
+
+        let root = Bip32PrivateKey::from_bip39_mnenomic(
+            mnemonic.to_string(),
+            passphrase.unwrap_or(default_passphrase).to_string(),
+        )?;
+
+        let wallet = Self::from_root_key(name, password, root, is_default, is_unsafe, derivation)?;
+
+        Ok(Self {
+            has_passphrase: passphrase.is_some(),
+            ..wallet
+        })
+    }
+
+    fn from_root_key(
+        name: &str,
+        password: &str,
+        root: Bip32PrivateKey,
+        is_default: bool,
+        is_unsafe: bool,
+        derivation: Derivation,
+    ) -> Result<Self> {
+        let (private_key, account) = derivation.derive(root);
         let public_key = private_key.to_public().as_bytes();
 
         let private_key = private_key.to_ed25519_private_key();
@@ -109,54 +330,564 @@ impl Wallet {
         Ok(Self {
             name: Name::try_from(name)?,
             private_key: Some(private_key),
+            entropy: None,
             public_key,
+            account_public_key: account.map(|x| x.to_public().as_bytes()),
+            script: None,
+            tracked_address: None,
+            locked_utxos: vec![],
+            has_passphrase: false,
+            derivation: Some(derivation),
             created: Local::now(),
             modified: Local::now(),
             is_default,
             is_unsafe,
+            network: None,
+        })
+    }
+
+    /// Creates a wallet locked by a native script requiring `required`
+    /// signatures out of the payment keys of `signers`.
+    pub fn multisig(
+        name: &str,
+        required: u32,
+        signers: &[&Wallet],
+        is_default: bool,
+    ) -> Result<Self> {
+        if signers.iter().any(|signer| signer.is_multisig()) {
+            bail!("multisig wallets can't be signers of another multisig wallet")
+        }
+
+        if let Some(signer) = signers.iter().find(|signer| signer.is_tracked()) {
+            bail!(
+                "wallet '{}' tracks an address, it has no key to sign with",
+                signer.name
+            )
+        }
+
+        let mut hashes: Vec<_> = signers
+            .iter()
+            .map(|signer| Ok(signer.payment_public_key()?.compute_hash()))
+            .collect::<Result<_>>()?;
+        hashes.sort();
+        hashes.dedup();
+
+        if hashes.len() != signers.len() {
+            bail!("signers must have distinct keys")
+        }
+
+        if required == 0 || required as usize > hashes.len() {
+            bail!("required signatures must be between 1 and {}", hashes.len())
+        }
+
+        let script = NativeScript::ScriptNOfK(
+            required,
+            hashes.into_iter().map(NativeScript::ScriptPubkey).collect(),
+        );
+
+        Ok(Self {
+            name: Name::try_from(name)?,
+            public_key: vec![],
+            private_key: None,
+            entropy: None,
+            account_public_key: None,
+            script: Some(minicbor::to_vec(&script)?),
+            tracked_address: None,
+            locked_utxos: vec![],
+            has_passphrase: false,
+            derivation: None,
+            created: Local::now(),
+            modified: Local::now(),
+            is_default,
+            is_unsafe: false,
+            network: None,
         })
     }
 
-    pub fn address(&self, is_testnet: bool) -> Address {
-        let pk = match self.private_key {
-            Some(_) => Bip32PublicKey::from_bytes(self.public_key.clone().try_into().unwrap())
-                .to_ed25519_pubkey(),
-            None => PublicKey::from_str(&hex::encode(&self.public_key)).unwrap(),
+    /// Creates a keyless wallet monitoring a script address, such as the one
+    /// of a Plutus contract.
+    pub fn tracked(name: &str, address: &Address, is_default: bool) -> Result<Self> {
+        let Address::Shelley(shelley) = address else {
+            bail!("only shelley script addresses can be tracked")
         };
 
-        if is_testnet {
-            ShelleyAddress::new(
-                Network::Testnet,
-                ShelleyPaymentPart::key_hash(pk.compute_hash()),
-                ShelleyDelegationPart::Null,
+        if !shelley.payment().is_script() {
+            bail!("{address} is not a script address, import its public key instead")
+        }
+
+        Ok(Self {
+            name: Name::try_from(name)?,
+            public_key: vec![],
+            private_key: None,
+            entropy: None,
+            account_public_key: None,
+            script: None,
+            tracked_address: Some(address.to_vec()),
+            locked_utxos: vec![],
+            has_passphrase: false,
+            derivation: None,
+            created: Local::now(),
+            modified: Local::now(),
+            is_default,
+            is_unsafe: false,
+            network: None,
+        })
+    }
+
+    /// Keeps the entropy of the wallet mnemonic so it can be exported later.
+    pub fn store_entropy(mut self, mnemonic: &str, password: &str) -> Result<Self> {
+        let entropy = Mnemonic::parse(mnemonic)
+            .context("Error parsing mnemonic")?
+            .to_entropy();
+
+        self.entropy = Some(match self.is_unsafe {
+            true => entropy,
+            false => encrypt_bytes(
+                rand_core::UnwrapErr(rand_core::OsRng),
+                &entropy,
+                &password.to_string(),
+            ),
+        });
+
+        Ok(self)
+    }
+
+    /// Recovers the wallet mnemonic from the stored entropy.
+    pub fn mnemonic(&self, password: &str) -> Result<String> {
+        let Some(entropy) = &self.entropy else {
+            bail!(
+                "wallet '{}' was created without --store-entropy, its mnemonic can't be recovered",
+                self.name
             )
-            .into()
-        } else {
-            ShelleyAddress::new(
-                Network::Mainnet,
-                ShelleyPaymentPart::key_hash(pk.compute_hash()),
+        };
+
+        let entropy = match self.is_unsafe {
+            true => entropy.clone(),
+            false => decrypt_bytes(&password.to_string(), entropy).context("invalid password")?,
+        };
+
+        let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy)?;
+
+        Ok(mnemonic.to_string())
+    }
+
+    /// Wallets imported from a public key can't sign transactions.
+    pub fn is_watch_only(&self) -> bool {
+        self.private_key.is_none()
+    }
+
+    pub fn is_multisig(&self) -> bool {
+        self.script.is_some()
+    }
+
+    /// Wallets monitoring a script address rather than holding keys.
+    pub fn is_tracked(&self) -> bool {
+        self.tracked_address.is_some()
+    }
+
+    /// HD wallets derive several payment addresses from their account key.
+    pub fn is_hd(&self) -> bool {
+        self.account_public_key.is_some()
+    }
+
+    pub fn native_script(&self) -> Result<Option<NativeScript>> {
+        match &self.script {
+            Some(script) => Ok(Some(
+                minicbor::decode(script).context("invalid native script")?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Signatures required by the script and the key hashes allowed to
+    /// provide them.
+    fn multisig_policy(&self) -> Result<Option<(u32, Vec<Hash<28>>)>> {
+        match self.native_script()? {
+            Some(NativeScript::ScriptNOfK(required, scripts)) => {
+                let hashes = scripts
+                    .into_iter()
+                    .filter_map(|script| match script {
+                        NativeScript::ScriptPubkey(hash) => Some(hash),
+                        _ => None,
+                    })
+                    .collect();
+
+                Ok(Some((required, hashes)))
+            }
+            Some(_) => bail!("wallet '{}' has an unsupported native script", self.name),
+            None => Ok(None),
+        }
+    }
+
+    /// Wallets of the store whose payment key is part of this multisig
+    /// wallet script.
+    pub fn multisig_members<'a>(&self, wallets: &'a [Wallet]) -> Result<Vec<&'a Wallet>> {
+        let Some((_, hashes)) = self.multisig_policy()? else {
+            return Ok(vec![]);
+        };
+
+        Ok(wallets
+            .iter()
+            .filter(|wallet| !wallet.is_multisig() && !wallet.is_tracked())
+            .filter(|wallet| {
+                wallet
+                    .payment_public_key()
+                    .is_ok_and(|key| hashes.contains(&key.compute_hash()))
+            })
+            .collect())
+    }
+
+    /// Adds the script of a multisig wallet to the witnesses of a tx, unless
+    /// it's already there.
+    pub fn attach_script(&self, tx: Vec<u8>) -> Result<Vec<u8>> {
+        let Some(script) = self.native_script()? else {
+            bail!("wallet '{}' is not a multisig wallet", self.name)
+        };
+
+        let mut decoded: Tx = minicbor::decode(&tx)?;
+
+        let mut scripts = decoded
+            .transaction_witness_set
+            .native_script
+            .as_ref()
+            .map(|x| x.clone().to_vec())
+            .unwrap_or_default();
+
+        if scripts.iter().any(|x| **x == script) {
+            return Ok(tx);
+        }
+
+        scripts.push(script.into());
+
+        decoded.transaction_witness_set.native_script =
+            Some(NonEmptySet::from_vec(scripts).unwrap());
+
+        match decoded.encode_fragment() {
+            Ok(tx) => Ok(tx),
+            Err(_) => bail!("failed to encode tx"),
+        }
+    }
+
+    /// Counts the signatures a tx holds for this multisig wallet, returning
+    /// them along with the amount required.
+    pub fn multisig_signatures(&self, tx: &[u8]) -> Result<(usize, u32)> {
+        let Some((required, hashes)) = self.multisig_policy()? else {
+            bail!("wallet '{}' is not a multisig wallet", self.name)
+        };
+
+        let decoded: Tx = minicbor::decode(tx)?;
+
+        let collected = decoded
+            .transaction_witness_set
+            .vkeywitness
+            .iter()
+            .flat_map(|witnesses| witnesses.iter())
+            .filter(|witness| hashes.contains(&Hasher::<224>::hash(&witness.vkey)))
+            .count();
+
+        Ok((collected, required))
+    }
+
+    /// Wallet address. HD wallets get a base address delegating to their
+    /// stake key, multisig wallets the address of their script, other
+    /// wallets an enterprise address.
+    pub fn address(&self, is_testnet: bool) -> Result<Address> {
+        let network = match is_testnet {
+            true => Network::Testnet,
+            false => Network::Mainnet,
+        };
+
+        if let Some(Ok(Address::Shelley(tracked))) =
+            self.tracked_address.as_deref().map(Address::from_bytes)
+        {
+            return Ok(ShelleyAddress::new(
+                network,
+                tracked.payment().clone(),
+                tracked.delegation().clone(),
+            )
+            .into());
+        }
+
+        if let Some(script) = self.native_script()? {
+            return Ok(ShelleyAddress::new(
+                network,
+                ShelleyPaymentPart::script_hash(script.compute_hash()),
                 ShelleyDelegationPart::Null,
             )
-            .into()
+            .into());
         }
+
+        let stake = self.stake_public_key().ok().flatten();
+
+        Ok(shelley_address(&self.payment_public_key()?, stake.as_ref(), is_testnet).into())
     }
 
-    pub fn sign(&self, tx: Vec<u8>, password: &Option<String>) -> Result<Vec<u8>, anyhow::Error> {
+    /// Ed25519 key the wallet signs with. Keys derived by cshell are kept as
+    /// extended public keys, imported ones as plain ed25519 keys. Wallets
+    /// without keys, like multisig ones, have none.
+    pub fn payment_public_key(&self) -> Result<PublicKey> {
+        match <[u8; 64]>::try_from(self.public_key.as_slice()) {
+            Ok(xpub) => Ok(Bip32PublicKey::from_bytes(xpub).to_ed25519_pubkey()),
+            Err(_) => PublicKey::from_str(&hex::encode(&self.public_key))
+                .map_err(|_| anyhow!("wallet '{}' has no valid payment key", self.name)),
+        }
+    }
+
+    /// The CIP-1852 account public key of HD wallets, bech32 encoded as
+    /// CIP-5 `acct_xvk`, which `wallet import --xpub` takes back.
+    pub fn account_xvk(&self) -> Option<String> {
+        let account = self.account_public_key.as_ref()?;
+
+        bech32::encode::<Bech32>(bech32::Hrp::parse_unchecked("acct_xvk"), account).ok()
+    }
+
+    pub fn is_locked(&self, utxo_ref: &str) -> bool {
+        self.locked_utxos.iter().any(|x| x == utxo_ref)
+    }
+
+    /// Fails if the tx spends any of the UTxOs locked in this wallet.
+    pub fn check_unlocked(&self, tx: &[u8]) -> Result<()> {
+        if self.locked_utxos.is_empty() {
+            return Ok(());
+        }
+
+        let tx = MultiEraTx::decode(tx).context("decoding tx")?;
+
+        for input in tx.inputs() {
+            let utxo_ref = format!("{}#{}", input.hash(), input.index());
+
+            if self.is_locked(&utxo_ref) {
+                bail!(
+                    "tx spends {utxo_ref}, which is locked in wallet '{}' (unlock it with `cshell wallet unlock-utxo`)",
+                    self.name
+                )
+            }
+        }
+
+        Ok(())
+    }
+
+    fn account_key(&self) -> Result<Option<Bip32PublicKey>> {
+        let Some(account) = &self.account_public_key else {
+            return Ok(None);
+        };
+
+        match account.clone().try_into() {
+            Ok(bytes) => Ok(Some(Bip32PublicKey::from_bytes(bytes))),
+            Err(_) => bail!("invalid account key for wallet '{}'", self.name),
+        }
+    }
+
+    /// Stake key of HD wallets (m/1852'/1815'/0'/2/0).
+    pub fn stake_public_key(&self) -> Result<Option<PublicKey>> {
+        match self.account_key()? {
+            Some(account) => Ok(Some(account.derive(2)?.derive(0)?.to_ed25519_pubkey())),
+            None => Ok(None),
+        }
+    }
+
+    /// Reward address of the stake key, only HD wallets have one.
+    pub fn reward_address(&self, is_testnet: bool) -> Option<Address> {
+        match self.address(is_testnet).ok()? {
+            Address::Shelley(shelley) if self.is_hd() => {
+                StakeAddress::try_from(shelley).ok().map(Address::from)
+            }
+            _ => None,
+        }
+    }
+
+    /// Base address at `index` of the external chain of the account
+    /// (m/1852'/1815'/0'/0/index). Index 0 is the wallet address.
+    pub fn derived_address(&self, index: u32, is_testnet: bool) -> Result<Address> {
+        self.role_address(Role::External, index, is_testnet)
+    }
+
+    /// Base address at `index` of the given chain of the account.
+    pub fn role_address(&self, role: Role, index: u32, is_testnet: bool) -> Result<Address> {
+        let Some(account) = self.account_key()? else {
+            bail!("wallet '{}' is not an HD wallet", self.name)
+        };
+
+        let pk = account
+            .derive(role.chain())?
+            .derive(index)?
+            .to_ed25519_pubkey();
+        let stake = account.derive(2)?.derive(0)?.to_ed25519_pubkey();
+
+        Ok(shelley_address(&pk, Some(&stake), is_testnet).into())
+    }
+
+    /// Decrypts the payment key of the wallet.
+    pub fn signing_key(&self, password: &Option<String>) -> Result<PrivateKey> {
         let Some(private_key) = &self.private_key else {
-            bail!("cant sign tx with RO wallet")
+            bail!(
+                "wallet '{}' is watch-only and can't sign transactions",
+                self.name
+            )
         };
 
         if password.is_none() && !self.is_unsafe {
             bail!("safe wallets require password")
         }
 
-        let mut decoded: Tx = minicbor::decode(&tx)?;
+        match password {
+            Some(password) => decrypt_private_key(password, private_key.to_vec()),
+            None => PrivateKey::try_from(private_key.as_slice()),
+        }
+    }
+
+    /// Stake signing key of HD wallets, re-derived from the stored entropy.
+    /// `None` when the wallet isn't HD or was created without
+    /// `--store-entropy`.
+    pub fn stake_signing_key(
+        &self,
+        password: &str,
+        passphrase: Option<&str>,
+    ) -> Result<Option<Bip32PrivateKey>> {
+        if !self.is_hd() || self.entropy.is_none() {
+            return Ok(None);
+        }
+
+        let root = self.root_key(password, passphrase)?;
+
+        Ok(Some(cip1852_account(&root, 0).derive(2).derive(0)))
+    }
+
+    /// Recovers the mnemonic root key of an HD wallet with stored entropy,
+    /// checking it against the account key of the wallet.
+    fn root_key(&self, password: &str, passphrase: Option<&str>) -> Result<Bip32PrivateKey> {
+        let candidates = match (self.has_passphrase, passphrase) {
+            (true, Some(passphrase)) => vec![passphrase],
+            (true, None) => bail!("wallet '{}' requires its BIP39 passphrase", self.name),
+            // wallets predating the empty default used the spending password
+            (false, _) => vec!["", password],
+        };
+
+        let mnemonic = self.mnemonic(password)?;
+
+        // the mnemonic root depends on the passphrase the wallet was created
+        // with, which `wallet passwd` doesn't change
+        for passphrase in candidates {
+            let root =
+                Bip32PrivateKey::from_bip39_mnenomic(mnemonic.clone(), passphrase.to_string())?;
+
+            let account = cip1852_account(&root, 0);
+            if self.account_public_key.as_ref() == Some(&account.to_public().as_bytes()) {
+                return Ok(root);
+            }
+        }
+
+        bail!(
+            "the keys of wallet '{}' were derived with a different passphrase",
+            self.name
+        )
+    }
+
+    /// Derives account `index` of the mnemonic of an HD wallet as a wallet
+    /// of its own, protected by the same spending password.
+    pub fn derive_account(
+        &self,
+        name: &str,
+        index: u32,
+        password: &str,
+        passphrase: Option<&str>,
+        is_default: bool,
+    ) -> Result<Self> {
+        if !self.is_hd() {
+            bail!("wallet '{}' is not an HD wallet", self.name)
+        }
+
+        if self.entropy.is_none() {
+            bail!(
+                "wallet '{}' was created without --store-entropy, its other accounts can't be derived",
+                self.name
+            )
+        }
+
+        if index == 0 || index >= HARDENED {
+            bail!("account index must be between 1 and {}", HARDENED - 1)
+        }
 
-        let private_key = match password {
-            Some(password) => decrypt_private_key(password, private_key.to_vec())?,
-            None => PrivateKey::try_from(private_key.as_slice())?,
+        let account = cip1852_account(&self.root_key(password, passphrase)?, index);
+        let payment = account.derive(0).derive(0);
+
+        let private_key = payment.to_ed25519_private_key();
+        let private_key = match self.is_unsafe {
+            true => private_key.as_bytes(),
+            false => encrypt_private_key(
+                rand_core::UnwrapErr(rand_core::OsRng),
+                private_key,
+                &password.to_string(),
+            ),
         };
 
+        Ok(Self {
+            name: Name::try_from(name)?,
+            public_key: payment.to_public().as_bytes(),
+            private_key: Some(private_key),
+            entropy: None,
+            account_public_key: Some(account.to_public().as_bytes()),
+            script: None,
+            tracked_address: None,
+            locked_utxos: vec![],
+            has_passphrase: self.has_passphrase,
+            derivation: Some(Derivation::Cip1852),
+            created: Local::now(),
+            modified: Local::now(),
+            is_default,
+            is_unsafe: self.is_unsafe,
+            network: self.network,
+        })
+    }
+
+    /// Re-encrypts the private key, and the stored entropy if any, with a
+    /// new spending password.
+    pub fn change_password(&self, password: &str, new_password: &str) -> Result<Self> {
+        let Some(private_key) = &self.private_key else {
+            bail!("wallet '{}' has no private key to protect", self.name)
+        };
+
+        if self.is_unsafe {
+            bail!(
+                "wallet '{}' is unsafe, its keys aren't encrypted",
+                self.name
+            )
+        }
+
+        let password = password.to_string();
+        let new_password = new_password.to_string();
+
+        let private_key =
+            decrypt_private_key(&password, private_key.clone()).context("invalid password")?;
+
+        let entropy = match &self.entropy {
+            Some(entropy) => Some(encrypt_bytes(
+                rand_core::UnwrapErr(rand_core::OsRng),
+                &decrypt_bytes(&password, entropy).context("invalid password")?,
+                &new_password,
+            )),
+            None => None,
+        };
+
+        Ok(Self {
+            private_key: Some(encrypt_private_key(
+                rand_core::UnwrapErr(rand_core::OsRng),
+                private_key,
+                &new_password,
+            )),
+            entropy,
+            modified: Local::now(),
+            ..self.clone()
+        })
+    }
+
+    pub fn sign(&self, tx: Vec<u8>, password: &Option<String>) -> Result<Vec<u8>, anyhow::Error> {
+        self.check_unlocked(&tx)?;
+
+        let private_key = self.signing_key(password)?;
+
+        let mut decoded: Tx = minicbor::decode(&tx)?;
+
         let signature = private_key.sign(decoded.transaction_body.compute_hash());
 
         let mut vkey_witnesses = decoded
@@ -166,8 +897,7 @@ impl Wallet {
             .map(|x| x.clone().to_vec())
             .unwrap_or_default();
 
-        let public_key = Bip32PublicKey::from_bytes(self.public_key.clone().try_into().unwrap())
-            .to_ed25519_pubkey();
+        let public_key = self.payment_public_key()?;
 
         vkey_witnesses.push(VKeyWitness {
             vkey: public_key.as_ref().to_vec().into(),
@@ -185,49 +915,104 @@ impl Wallet {
 }
 
 impl OutputFormatter for Wallet {
-    fn to_table(&self) {
-        let mut table = Table::new();
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
 
         table.set_header(vec!["Property", "Value"]);
 
         table.add_row(vec!["Name", &self.name]);
-        table.add_row(vec!["Public Key Hash", &hex::encode(&self.public_key)]);
-        table.add_row(vec!["Address (mainnet)", &self.address(false).to_string()]);
-        table.add_row(vec!["Address (testnet)", &self.address(true).to_string()]);
+        if !self.public_key.is_empty() {
+            table.add_row(vec!["Public Key Hash", &hex::encode(&self.public_key)]);
+        }
+        table.add_row(vec![
+            "Address (mainnet)",
+            &self.address(false).map_or("-".into(), |x| x.to_string()),
+        ]);
+        table.add_row(vec![
+            "Address (testnet)",
+            &self.address(true).map_or("-".into(), |x| x.to_string()),
+        ]);
+        if let Ok(Some(NativeScript::ScriptNOfK(required, scripts))) = self.native_script() {
+            table.add_row(vec![
+                "Native Script",
+                &format!("{required} of {} signatures", scripts.len()),
+            ]);
+        }
+        if let Ok(Address::Shelley(tracked)) = self.address(false) {
+            if self.is_tracked() {
+                table.add_row(vec![
+                    "Script Hash",
+                    &tracked.payment().as_hash().to_string(),
+                ]);
+            }
+        }
+        if !self.locked_utxos.is_empty() {
+            table.add_row(vec!["Locked UTxOs", &self.locked_utxos.join("\n")]);
+        }
+        if let Some(account) = &self.account_public_key {
+            table.add_row(vec!["Account Public Key", &hex::encode(account)]);
+        }
+        if let Some(reward) = self.reward_address(false) {
+            table.add_row(vec!["Reward Address (mainnet)", &reward.to_string()]);
+        }
+        if let Some(reward) = self.reward_address(true) {
+            table.add_row(vec!["Reward Address (testnet)", &reward.to_string()]);
+        }
         table.add_row(vec!["Created", &utils::pretty_print_date(&self.created)]);
         table.add_row(vec!["Modified", &utils::pretty_print_date(&self.modified)]);
 
         println!("{table}");
     }
 
-    fn to_json(&self) {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&json!({
-                "name": &self.name,
-                "public_key": hex::encode(&self.public_key),
-                "addresses": {
-                    "mainnet": &self.address(false).to_string(),
-                    "testnet": &self.address(true).to_string(),
-                },
-                "created": self.created,
-                "modified": self.modified,
-                "is_default": self.is_default,
-            }))
-            .unwrap()
-        );
+    fn to_json(&self) -> Result<Value> {
+        Ok(json!({
+            "name": &self.name,
+            "public_key": hex::encode(&self.public_key),
+            "account_public_key": self.account_public_key.as_ref().map(hex::encode),
+            "script": self.script.as_ref().map(hex::encode),
+            "tracked": self.is_tracked(),
+            "locked_utxos": self.locked_utxos,
+            "addresses": {
+                "mainnet": self.address(false).ok().map(|x| x.to_string()),
+                "testnet": self.address(true).ok().map(|x| x.to_string()),
+            },
+            "reward_addresses": self.is_hd().then(|| json!({
+                "mainnet": self.reward_address(false).map(|x| x.to_string()),
+                "testnet": self.reward_address(true).map(|x| x.to_string()),
+            })),
+            "created": self.created,
+            "modified": self.modified,
+            "is_default": self.is_default,
+        }))
     }
 }
 
 impl OutputFormatter for &Vec<Wallet> {
-    fn to_table(&self) {
-        let mut table = Table::new();
-
-        table.set_header(vec!["Name", "Created", "Modified", "Is Default?"]);
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
+
+        table.set_header(vec![
+            "Name",
+            "Mainnet",
+            "Testnet",
+            "Watch Only?",
+            "Created",
+            "Modified",
+            "Is Default?",
+        ]);
 
         for wallet in self.iter() {
             table.add_row(vec![
                 wallet.name.to_string(),
+                utils::clip(
+                    wallet.address(false).map_or("-".into(), |x| x.to_string()),
+                    20,
+                ),
+                utils::clip(
+                    wallet.address(true).map_or("-".into(), |x| x.to_string()),
+                    20,
+                ),
+                wallet.is_watch_only().to_string(),
                 utils::pretty_print_date(&wallet.created),
                 utils::pretty_print_date(&wallet.modified),
                 wallet.is_default.to_string(),
@@ -237,60 +1022,52 @@ impl OutputFormatter for &Vec<Wallet> {
         println!("{table}");
     }
 
-    fn to_json(&self) {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(
-                &self
-                    .iter()
-                    .map(|wallet| {
-                        json!({
-                            "name": &wallet.name,
-                            "public_key": hex::encode(&wallet.public_key),
-                            "addresses": {
-                                "mainnet": &wallet.address(false).to_string(),
-                                "testnet": &wallet.address(true).to_string(),
-                            },
-                            "created": wallet.created,
-                            "modified": wallet.modified,
-                            "is_default": wallet.is_default,
-                        })
+    fn to_json(&self) -> Result<Value> {
+        Ok(Value::Array(
+            self.iter()
+                .map(|wallet| {
+                    json!({
+                        "name": &wallet.name,
+                        "public_key": hex::encode(&wallet.public_key),
+                        "addresses": {
+                            "mainnet": wallet.address(false).ok().map(|x| x.to_string()),
+                            "testnet": wallet.address(true).ok().map(|x| x.to_string()),
+                        },
+                        "created": wallet.created,
+                        "modified": wallet.modified,
+                        "is_default": wallet.is_default,
+                        "is_watch_only": wallet.is_watch_only(),
                     })
-                    .collect::<Vec<Value>>(),
-            )
-            .unwrap()
-        );
+                })
+                .collect(),
+        ))
     }
 }
 
 impl OutputFormatter for NewWallet {
-    fn to_table(&self) {
+    fn to_table(&self, style: TableStyle) {
         println!("Your mnemonic phrase is the following:");
         println!("\n");
         println!("* {}", self.0);
         println!("\n");
         println!("Save this phrase somewhere safe to restore your wallet if it ever gets lost.");
 
-        self.1.to_table();
-    }
-
-    fn to_json(&self) {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&json!({
-                "name": &self.1.name,
-                "mnemonic": &self.0,
-                "public_key": hex::encode(&self.1.public_key),
-                "addresses": {
-                    "mainnet": &self.1.address(false).to_string(),
-                    "testnet": &self.1.address(true).to_string(),
-                },
-                "created": self.1.created,
-                "modified": self.1.modified,
-                "is_default": self.1.is_default,
-            }))
-            .unwrap()
-        );
+        self.1.to_table(style);
+    }
+
+    fn to_json(&self) -> Result<Value> {
+        Ok(json!({
+            "name": &self.1.name,
+            "mnemonic": &self.0,
+            "public_key": hex::encode(&self.1.public_key),
+            "addresses": {
+                "mainnet": self.1.address(false).ok().map(|x| x.to_string()),
+                "testnet": self.1.address(true).ok().map(|x| x.to_string()),
+            },
+            "created": self.1.created,
+            "modified": self.1.modified,
+            "is_default": self.1.is_default,
+        }))
     }
 }
 
@@ -522,7 +1299,14 @@ impl Bip32PublicKey {
     }
 }
 
-pub fn encrypt_private_key<Rng>(mut rng: Rng, private_key: PrivateKey, password: &String) -> Vec<u8>
+pub fn encrypt_private_key<Rng>(rng: Rng, private_key: PrivateKey, password: &String) -> Vec<u8>
+where
+    Rng: rand_core::RngCore + rand_core::CryptoRng,
+{
+    encrypt_bytes(rng, &private_key.as_bytes(), password)
+}
+
+pub fn encrypt_bytes<Rng>(mut rng: Rng, data: &[u8], password: &String) -> Vec<u8>
 where
     Rng: rand_core::RngCore + rand_core::CryptoRng,
 {
@@ -548,12 +1332,12 @@ where
 
     let mut chacha20 = ChaCha20Poly1305::new(&sym_key, &nonce, &[]);
 
-    let data_size = private_key.len();
+    let data_size = data.len();
 
     let (ciphertext, ct_tag) = {
         let mut ciphertext = vec![0u8; data_size];
         let mut ct_tag = [0u8; 16];
-        chacha20.encrypt(&private_key.as_bytes(), &mut ciphertext, &mut ct_tag);
+        chacha20.encrypt(data, &mut ciphertext, &mut ct_tag);
 
         (ciphertext, ct_tag)
     };
@@ -574,13 +1358,38 @@ where
 pub fn decrypt_private_key(password: &String, data: Vec<u8>) -> Result<PrivateKey> {
     let data_len_without_ct = VERSION_SIZE + SALT_SIZE + NONCE_SIZE + TAG_SIZE;
 
-    let ciphertext_len = if data.len() == (data_len_without_ct + SecretKey::SIZE) {
-        SecretKey::SIZE
-    } else if data.len() == (data_len_without_ct + SecretKeyExtended::SIZE) {
-        SecretKeyExtended::SIZE
-    } else {
+    if data.len() != data_len_without_ct + SecretKey::SIZE
+        && data.len() != data_len_without_ct + SecretKeyExtended::SIZE
+    {
         bail!("Invalid wrapper size")
-    };
+    }
+
+    let plaintext = decrypt_bytes(password, &data)?;
+
+    match plaintext.len() {
+        SecretKey::SIZE => {
+            let plaintext: [u8; SecretKey::SIZE] = plaintext.try_into().unwrap();
+            let secret_key: SecretKey = plaintext.into();
+
+            Ok(secret_key.into())
+        }
+        SecretKeyExtended::SIZE => {
+            let plaintext: [u8; SecretKeyExtended::SIZE] = plaintext.try_into().unwrap();
+            let secret_key =
+                SecretKeyExtended::from_bytes(plaintext).context("decoding secret key")?;
+
+            Ok(secret_key.into())
+        }
+        _ => unreachable!(),
+    }
+}
+
+pub fn decrypt_bytes(password: &String, data: &[u8]) -> Result<Vec<u8>> {
+    let data_len_without_ct = VERSION_SIZE + SALT_SIZE + NONCE_SIZE + TAG_SIZE;
+
+    if data.len() <= data_len_without_ct {
+        bail!("Invalid wrapper size")
+    }
 
     let mut cursor = 0;
 
@@ -596,7 +1405,7 @@ pub fn decrypt_private_key(password: &String, data: Vec<u8>) -> Result<PrivateKe
     let tag = &data[cursor..cursor + TAG_SIZE];
     cursor += TAG_SIZE;
 
-    let ciphertext = &data[cursor..cursor + ciphertext_len];
+    let ciphertext = &data[cursor..];
 
     let sym_key: [u8; 32] = argon2::argon2(
         &argon2::Params::argon2d().iterations(ITERATIONS).unwrap(),
@@ -608,41 +1417,31 @@ pub fn decrypt_private_key(password: &String, data: Vec<u8>) -> Result<PrivateKe
 
     let mut chacha20 = ChaCha20Poly1305::new(&sym_key, nonce, &[]);
 
-    match ciphertext_len {
-        SecretKey::SIZE => {
-            let mut plaintext = [0u8; SecretKey::SIZE];
-
-            if chacha20.decrypt(ciphertext, &mut plaintext, tag) {
-                let secret_key: SecretKey = plaintext.into();
+    let mut plaintext = vec![0u8; ciphertext.len()];
 
-                Ok(secret_key.into())
-            } else {
-                Err(bail!("Wrapper data failed to decrypt"))
-            }
-        }
-        SecretKeyExtended::SIZE => {
-            let mut plaintext = [0u8; SecretKeyExtended::SIZE];
-
-            if chacha20.decrypt(ciphertext, &mut plaintext, tag) {
-                let secret_key =
-                    SecretKeyExtended::from_bytes(plaintext).context("decoding secret key")?;
-
-                Ok(secret_key.into())
-            } else {
-                Err(bail!("Wrapper data failed to decrypt"))
-            }
-        }
-        _ => unreachable!(),
+    if !chacha20.decrypt(ciphertext, &mut plaintext, tag) {
+        bail!("Wrapper data failed to decrypt")
     }
+
+    Ok(plaintext)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        decrypt_private_key, encrypt_private_key, Bip32PrivateKey, Bip32PublicKey, PrivateKey,
+        decrypt_private_key, encrypt_private_key, english_mnemonic, generate_mnemonic, utxo_ref,
+        Bip32PrivateKey, Bip32PublicKey, Derivation, PrivateKey, Role, Wallet, WordList,
+        MNEMONIC_WORDS,
     };
+    use crate::output::OutputFormatter;
 
-    use pallas::crypto::key::ed25519::{SecretKey, SecretKeyExtended};
+    use pallas::{
+        crypto::key::ed25519::{SecretKey, SecretKeyExtended},
+        ledger::{
+            addresses::{Address, Network, ShelleyDelegationPart},
+            traverse::ComputeHash,
+        },
+    };
 
     #[test]
     fn mnemonic_roundtrip() {
@@ -722,4 +1521,465 @@ mod tests {
 
         assert_eq!(private_key_bytes, decrypted_privkey.as_bytes())
     }
+
+    #[test]
+    fn mnemonic_round_trip_with_stored_entropy() {
+        let (mnemonic, wallet) =
+            Wallet::try_from("alice", "password", true, false, Derivation::Root, None).unwrap();
+
+        assert!(wallet.mnemonic("password").is_err());
+
+        let wallet = wallet.store_entropy(&mnemonic, "password").unwrap();
+
+        assert_eq!(wallet.mnemonic("password").unwrap(), mnemonic);
+        assert!(wallet.mnemonic("wrong").is_err());
+
+        let restored = Wallet::try_from_mnemonic(
+            "restored",
+            "password",
+            &mnemonic,
+            false,
+            false,
+            Derivation::Root,
+            None,
+        )
+        .unwrap();
+        assert_eq!(restored.public_key, wallet.public_key);
+
+        let (mnemonic, unsafe_wallet) =
+            Wallet::try_from("bob", "", false, true, Derivation::Root, None).unwrap();
+        let unsafe_wallet = unsafe_wallet.store_entropy(&mnemonic, "").unwrap();
+        assert_eq!(unsafe_wallet.mnemonic("").unwrap(), mnemonic);
+    }
+
+    #[test]
+    fn hd_wallets_derive_payment_addresses() {
+        let (mnemonic, legacy) =
+            Wallet::try_from("legacy", "", false, true, Derivation::Root, None).unwrap();
+        assert!(!legacy.is_hd());
+        assert!(legacy.derived_address(0, true).is_err());
+
+        let hd =
+            Wallet::try_from_mnemonic("hd", "", &mnemonic, false, true, Derivation::Cip1852, None)
+                .unwrap();
+        assert!(hd.is_hd());
+        assert_ne!(hd.public_key, legacy.public_key);
+
+        assert_eq!(
+            hd.derived_address(0, true).unwrap(),
+            hd.address(true).unwrap()
+        );
+        assert_eq!(
+            hd.derived_address(0, false).unwrap(),
+            hd.address(false).unwrap()
+        );
+        assert_ne!(
+            hd.derived_address(1, true).unwrap(),
+            hd.address(true).unwrap()
+        );
+        assert_ne!(
+            hd.derived_address(1, true).unwrap(),
+            hd.derived_address(2, true).unwrap()
+        );
+
+        let restored = Wallet::try_from_mnemonic(
+            "again",
+            "",
+            &mnemonic,
+            false,
+            true,
+            Derivation::Cip1852,
+            None,
+        )
+        .unwrap();
+        assert_eq!(restored.account_public_key, hd.account_public_key);
+    }
+
+    #[test]
+    fn hd_wallets_use_base_addresses() {
+        let (_, legacy) =
+            Wallet::try_from("legacy", "", false, true, Derivation::Root, None).unwrap();
+        assert!(legacy.stake_public_key().unwrap().is_none());
+        assert!(legacy.reward_address(true).is_none());
+        assert!(matches!(
+            legacy.address(true).unwrap(),
+            Address::Shelley(ref x) if *x.delegation() == ShelleyDelegationPart::Null
+        ));
+
+        let (_, hd) = Wallet::try_from("hd", "", false, true, Derivation::Cip1852, None).unwrap();
+        let stake = hd.stake_public_key().unwrap().unwrap();
+
+        for index in 0..3 {
+            let Address::Shelley(address) = hd.derived_address(index, true).unwrap() else {
+                panic!("expected a shelley address");
+            };
+            assert_eq!(
+                *address.delegation(),
+                ShelleyDelegationPart::key_hash(stake.compute_hash())
+            );
+        }
+
+        let change = hd.role_address(Role::Change, 0, true).unwrap();
+        assert_ne!(change, hd.derived_address(0, true).unwrap());
+        assert_eq!(
+            hd.role_address(Role::External, 1, true).unwrap(),
+            hd.derived_address(1, true).unwrap()
+        );
+
+        let reward = hd.reward_address(false).unwrap().to_string();
+        assert!(reward.starts_with("stake1"));
+        assert!(hd
+            .reward_address(true)
+            .unwrap()
+            .to_string()
+            .starts_with("stake_test1"));
+        assert_eq!(
+            hd.to_json().unwrap()["reward_addresses"]["mainnet"],
+            reward.as_str()
+        );
+    }
+
+    #[test]
+    fn password_change_re_encrypts_keys() {
+        let (mnemonic, wallet) =
+            Wallet::try_from("alice", "old", false, false, Derivation::Cip1852, None).unwrap();
+        let wallet = wallet.store_entropy(&mnemonic, "old").unwrap();
+        let key = wallet.signing_key(&Some("old".into())).unwrap();
+
+        assert!(wallet.change_password("wrong", "new").is_err());
+
+        let changed = wallet.change_password("old", "new").unwrap();
+        assert_eq!(changed.public_key, wallet.public_key);
+        assert!(changed.signing_key(&Some("old".into())).is_err());
+        assert_eq!(
+            changed.signing_key(&Some("new".into())).unwrap().as_bytes(),
+            key.as_bytes()
+        );
+        assert_eq!(changed.mnemonic("new").unwrap(), mnemonic);
+        // the empty default passphrase doesn't depend on the password
+        assert!(changed.stake_signing_key("new", None).unwrap().is_some());
+
+        let (_, unsafe_wallet) =
+            Wallet::try_from("bob", "", false, true, Derivation::Root, None).unwrap();
+        assert!(unsafe_wallet.change_password("", "new").is_err());
+    }
+
+    #[test]
+    fn passphrase_is_independent_of_password() {
+        let (mnemonic, wallet) = Wallet::try_from(
+            "alice",
+            "password",
+            false,
+            false,
+            Derivation::Cip1852,
+            Some("25th word"),
+        )
+        .unwrap();
+        assert!(wallet.has_passphrase);
+        let wallet = wallet.store_entropy(&mnemonic, "password").unwrap();
+
+        let restore = |password, passphrase| {
+            Wallet::try_from_mnemonic(
+                "restored",
+                password,
+                &mnemonic,
+                false,
+                false,
+                Derivation::Cip1852,
+                passphrase,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            restore("other", Some("25th word")).public_key,
+            wallet.public_key
+        );
+        assert_ne!(restore("other", Some("")).public_key, wallet.public_key);
+        assert_ne!(restore("password", None).public_key, wallet.public_key);
+        // like other Cardano wallets, no passphrase means an empty one
+        assert_eq!(
+            restore("password", None).public_key,
+            restore("other", Some("")).public_key
+        );
+        assert_eq!(
+            restore("a", Some("")).public_key,
+            restore("b", Some("")).public_key
+        );
+
+        assert!(wallet.stake_signing_key("password", None).is_err());
+        assert!(wallet.stake_signing_key("password", Some("wrong")).is_err());
+        assert!(wallet
+            .stake_signing_key("password", Some("25th word"))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn mnemonic_lengths() {
+        for words in MNEMONIC_WORDS {
+            let mnemonic = generate_mnemonic(words).unwrap();
+            assert_eq!(mnemonic.split_whitespace().count(), words);
+            assert!(Wallet::try_from_mnemonic(
+                "alice",
+                "",
+                &mnemonic,
+                false,
+                true,
+                Derivation::Root,
+                None
+            )
+            .is_ok());
+        }
+
+        assert!(generate_mnemonic(13).is_err());
+
+        let mnemonic = generate_mnemonic(12).unwrap();
+        let truncated = mnemonic.rsplit_once(' ').unwrap().0;
+        let err =
+            Wallet::try_from_mnemonic("a", "", truncated, false, true, Derivation::Root, None)
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "mnemonics must have 12, 15, 18, 21 or 24 words, got 11"
+        );
+    }
+
+    #[test]
+    fn mnemonics_of_other_wordlists() {
+        let english = generate_mnemonic(12).unwrap();
+        let entropy = bip39::Mnemonic::parse(&english).unwrap().to_entropy();
+        let spanish = bip39::Mnemonic::from_entropy_in(bip39::Language::Spanish, &entropy)
+            .unwrap()
+            .to_string();
+
+        assert_eq!(english_mnemonic(&spanish, None).unwrap(), english);
+        assert_eq!(
+            english_mnemonic(&spanish, Some(WordList::Spanish)).unwrap(),
+            english
+        );
+        assert!(english_mnemonic(&spanish, Some(WordList::Japanese)).is_err());
+        assert_eq!(english_mnemonic(&english, None).unwrap(), english);
+    }
+
+    #[test]
+    fn accounts_derive_from_the_same_mnemonic() {
+        let (mnemonic, wallet) = Wallet::try_from(
+            "main",
+            "pass",
+            false,
+            false,
+            Derivation::Cip1852,
+            Some("extra"),
+        )
+        .unwrap();
+
+        // without the entropy the root key is gone
+        assert!(wallet
+            .derive_account("second", 1, "pass", Some("extra"), false)
+            .is_err());
+
+        let wallet = wallet.store_entropy(&mnemonic, "pass").unwrap();
+        assert!(wallet
+            .derive_account("second", 1, "pass", None, false)
+            .is_err());
+        assert!(wallet
+            .derive_account("second", 1, "pass", Some("wrong"), false)
+            .is_err());
+        assert!(wallet
+            .derive_account("second", 0, "pass", Some("extra"), false)
+            .is_err());
+
+        let second = wallet
+            .derive_account("second", 1, "pass", Some("extra"), false)
+            .unwrap();
+        assert!(second.is_hd());
+        assert_eq!(wallet.derivation, Some(Derivation::Cip1852));
+        assert_eq!(second.derivation, Some(Derivation::Cip1852));
+        assert_ne!(second.address(true).unwrap(), wallet.address(true).unwrap());
+        assert_ne!(
+            second.stake_public_key().unwrap(),
+            wallet.stake_public_key().unwrap()
+        );
+
+        let root = Bip32PrivateKey::from_bip39_mnenomic(mnemonic, "extra".to_string()).unwrap();
+        let account = super::cip1852_account(&root, 1);
+        assert_eq!(
+            second.account_public_key,
+            Some(account.to_public().as_bytes())
+        );
+
+        // the derived account signs with the parent spending password
+        assert!(second.signing_key(&Some("pass".to_string())).is_ok());
+        assert!(second.signing_key(&Some("other".to_string())).is_err());
+    }
+
+    #[test]
+    fn tracked_wallets_follow_the_network() {
+        let script =
+            Address::from_bech32("addr1w9jx45flh83z6wuqypyash54mszwmdj8r64fydafxtfc6jgrw4rm3")
+                .unwrap();
+
+        let tracked = Wallet::tracked("contract", &script, false).unwrap();
+        assert!(tracked.is_tracked());
+        assert!(tracked.is_watch_only());
+        assert_eq!(tracked.address(false).unwrap(), script);
+
+        let Address::Shelley(testnet) = tracked.address(true).unwrap() else {
+            panic!("expected a shelley address");
+        };
+        assert_eq!(testnet.network(), Network::Testnet);
+        assert!(testnet.payment().is_script());
+
+        let (_, key) = Wallet::try_from("key", "", false, true, Derivation::Root, None).unwrap();
+        assert!(Wallet::tracked("key", &key.address(false).unwrap(), false).is_err());
+        assert!(Wallet::multisig("team", 1, &[&key, &tracked], false).is_err());
+    }
+
+    #[test]
+    fn multisig_wallets_collect_signatures() {
+        let (_, alice) =
+            Wallet::try_from("alice", "", false, true, Derivation::Root, None).unwrap();
+        let (_, bob) = Wallet::try_from("bob", "", false, true, Derivation::Cip1852, None).unwrap();
+        let (_, carol) =
+            Wallet::try_from("carol", "", false, true, Derivation::Root, None).unwrap();
+
+        assert!(Wallet::multisig("team", 3, &[&alice, &bob], false).is_err());
+        assert!(Wallet::multisig("team", 0, &[&alice, &bob], false).is_err());
+        assert!(Wallet::multisig("team", 1, &[&alice, &alice], false).is_err());
+
+        let team = Wallet::multisig("team", 2, &[&alice, &bob], false).unwrap();
+        assert!(team.is_multisig());
+        assert!(Wallet::multisig("nested", 1, &[&team], false).is_err());
+
+        let Address::Shelley(address) = team.address(true).unwrap() else {
+            panic!("expected a shelley address");
+        };
+        assert!(address.payment().is_script());
+
+        // keyless wallets with a broken script have no address at all
+        assert!(team.payment_public_key().is_err());
+        let mut broken = team.clone();
+        broken.script = Some(vec![0xff]);
+        assert!(broken.address(true).is_err());
+
+        let store = vec![alice.clone(), bob.clone(), carol.clone(), team.clone()];
+        let members: Vec<_> = team
+            .multisig_members(&store)
+            .unwrap()
+            .into_iter()
+            .map(|x| x.name.to_string())
+            .collect();
+        assert_eq!(members, vec!["alice", "bob"]);
+
+        // {0: [], 1: [], 2: 1000000000000}, {}, true, null
+        let tx = hex::decode("84a300800180021b000000e8d4a51000a0f5f6").unwrap();
+        let tx = team.attach_script(tx).unwrap();
+        assert_eq!(team.attach_script(tx.clone()).unwrap(), tx);
+        assert_eq!(team.multisig_signatures(&tx).unwrap(), (0, 2));
+
+        let tx = carol.sign(tx, &None).unwrap();
+        let tx = alice.sign(tx, &None).unwrap();
+        assert_eq!(team.multisig_signatures(&tx).unwrap(), (1, 2));
+
+        let tx = bob.sign(tx, &None).unwrap();
+        assert_eq!(team.multisig_signatures(&tx).unwrap(), (2, 2));
+        assert!(alice.attach_script(tx).is_err());
+    }
+
+    #[test]
+    fn watch_only_wallets_are_flagged() {
+        let (_, signer) =
+            Wallet::try_from("signer", "password", true, false, Derivation::Root, None).unwrap();
+
+        let watcher = Wallet {
+            name: "watcher".try_into().unwrap(),
+            public_key: Bip32PublicKey::from_bytes(signer.public_key.clone().try_into().unwrap())
+                .to_ed25519_pubkey()
+                .as_ref()
+                .to_vec(),
+            private_key: None,
+            is_default: false,
+            ..signer.clone()
+        };
+
+        assert!(!signer.is_watch_only());
+        assert!(watcher.is_watch_only());
+        assert_eq!(
+            watcher.address(true).unwrap(),
+            signer.address(true).unwrap()
+        );
+        assert!(watcher
+            .sign(vec![], &None)
+            .unwrap_err()
+            .to_string()
+            .contains("watch-only"));
+
+        let wallets = vec![signer, watcher];
+        let json = (&wallets).to_json().unwrap();
+        assert_eq!(json[0]["is_watch_only"], false);
+        assert_eq!(json[1]["is_watch_only"], true);
+        assert!(json[1]["addresses"]["mainnet"]
+            .as_str()
+            .unwrap()
+            .starts_with("addr1"));
+    }
+
+    #[test]
+    fn locked_utxos_block_signing() {
+        let locked = format!("{}#1", "aa".repeat(32));
+        assert_eq!(utxo_ref(&locked.to_uppercase()).unwrap(), locked);
+        assert!(utxo_ref("aa#1").is_err());
+        assert!(utxo_ref(&"aa".repeat(32)).is_err());
+
+        // spends aa..#1
+        let tx = crate::provider::types::probe_tx();
+
+        let (_, mut wallet) =
+            Wallet::try_from("alice", "", false, true, Derivation::Root, None).unwrap();
+        wallet.locked_utxos = vec![format!("{}#0", "aa".repeat(32))];
+        assert!(wallet.sign(tx.clone(), &None).is_ok());
+
+        wallet.locked_utxos.push(locked.clone());
+        let error = wallet.sign(tx, &None).unwrap_err().to_string();
+        assert!(error.contains(&locked));
+    }
+
+    #[test]
+    fn cip1852_matches_cip19_test_vectors() {
+        let wallet = Wallet::try_from_mnemonic(
+            "cip19",
+            "password",
+            "test walk nut penalty hip pave soap entry language right filter choice",
+            false,
+            false,
+            Derivation::Cip1852,
+            None,
+        )
+        .unwrap();
+
+        // payment key and type-0 addresses published in CIP-19
+        assert_eq!(
+            bech32::encode::<bech32::Bech32>(
+                bech32::Hrp::parse_unchecked("addr_vk"),
+                wallet.payment_public_key().unwrap().as_ref()
+            )
+            .unwrap(),
+            "addr_vk1w0l2sr2zgfm26ztc6nl9xy8ghsk5sh6ldwemlpmp9xylzy4dtf7st80zhd"
+        );
+        assert_eq!(
+            wallet.address(false).unwrap().to_string(),
+            "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3jcu5d8ps7zex2k2xt3uqxgjqnnj83ws8lhrn648jjxtwqfjkjv7"
+        );
+        assert_eq!(
+            wallet.address(true).unwrap().to_string(),
+            "addr_test1qz2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3jcu5d8ps7zex2k2xt3uqxgjqnnj83ws8lhrn648jjxtwq2ytjqp"
+        );
+
+        // the account key both of the keys above derive from
+        assert_eq!(
+            wallet.account_xvk().unwrap(),
+            "acct_xvk1eame4ge0x5yrwpuqs5eyw89kfmjpgfkfh02xzdx6c2k9k2swcr5clf0u634tm82x6nv2j750x3j7938g70ya4k0lv6pr59s7etw2vpqgfmule"
+        );
+    }
 }
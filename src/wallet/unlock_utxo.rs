@@ -0,0 +1,39 @@
+use anyhow::bail;
+use chrono::Local;
+use clap::Parser;
+use tracing::instrument;
+
+use crate::output::OutputFormatter;
+
+use super::types::utxo_ref;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet holding the UTxO
+    name: String,
+
+    /// Locked UTxO to release, as `<tx hash>#<index>`
+    #[arg(value_parser = utxo_ref)]
+    utxo: String,
+}
+
+#[instrument("unlock-utxo", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    if !wallet.is_locked(&args.utxo) {
+        bail!("{} is not locked in wallet '{}'", args.utxo, wallet.name)
+    }
+
+    let mut wallet = wallet.clone();
+    wallet.locked_utxos.retain(|x| *x != args.utxo);
+    wallet.modified = Local::now();
+
+    ctx.store.update_wallet(wallet.clone())?;
+
+    // Log, print, and finish
+    wallet.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
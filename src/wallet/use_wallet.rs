@@ -0,0 +1,23 @@
+use clap::Parser;
+use tracing::instrument;
+
+use crate::{output::OutputFormatter, store::Store};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the wallet to use by default
+    name: String,
+}
+
+#[instrument("use", skip_all)]
+pub async fn run(args: Args, ctx: &mut crate::Context) -> anyhow::Result<()> {
+    let wallet = ctx.store.set_default_wallet(&args.name)?.clone();
+
+    if let Some(name) = Store::wallet_override() {
+        eprintln!("CSHELL_WALLET is set, '{name}' stays in use while it is.");
+    }
+
+    // Log, print, and finish
+    wallet.output(&ctx.output_format, ctx.output_select())?;
+    Ok(())
+}
@@ -1,10 +1,17 @@
-use anyhow::bail;
-use clap::Parser;
-use comfy_table::Table;
-use serde_json::json;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use clap::{Parser, ValueEnum};
+use pallas::ledger::addresses::Address;
+use serde_json::{json, Value};
 use utxorpc::spec::query::{any_utxo_data::ParsedState, AnyUtxoData};
 
-use crate::output::{OutputFormat, OutputFormatter};
+use crate::{
+    output::{OutputFormat, OutputFormatter, TableStyle},
+    provider::types::AddressMatch,
+};
+
+use super::discovery::{self, DEFAULT_GAP_LIMIT};
 
 #[derive(Parser)]
 pub struct Args {
@@ -13,6 +20,179 @@ pub struct Args {
 
     /// Name of the provider to use. If undefined, the default provider is used.
     provider: Option<String>,
+
+    /// How UTxO addresses are matched against the wallet address.
+    #[arg(long = "match", value_enum, default_value_t)]
+    matching: AddressMatch,
+
+    /// Consecutive unused addresses after which HD wallets stop looking
+    /// for more UTxOs.
+    #[arg(long, default_value_t = DEFAULT_GAP_LIMIT, value_parser = clap::value_parser!(u32).range(1..))]
+    gap_limit: u32,
+
+    /// Only list UTxOs holding at least this many lovelace.
+    #[arg(long)]
+    min_coin: Option<u64>,
+
+    /// Only list UTxOs holding at most this many lovelace.
+    #[arg(long)]
+    max_coin: Option<u64>,
+
+    /// Only list UTxOs holding this asset, given as `<policy id>` or
+    /// `<policy id>.<asset name>` in hex.
+    #[arg(long)]
+    asset: Option<AssetFilter>,
+
+    /// Only list UTxOs locked by this address, e.g. one of the derived
+    /// addresses of an HD wallet.
+    #[arg(long, value_parser = parse_address)]
+    address: Option<Address>,
+
+    /// Order in which UTxOs are listed.
+    #[arg(long, value_enum, default_value_t)]
+    order_by: OrderBy,
+
+    /// List UTxOs in descending order.
+    #[arg(long)]
+    desc: bool,
+
+    /// Maximum number of UTxOs to list.
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Number of UTxOs to skip before listing, to page through large sets.
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+}
+
+fn parse_address(s: &str) -> anyhow::Result<Address> {
+    Address::from_bech32(s).context("invalid address")
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+enum OrderBy {
+    /// Transaction hash, then output index
+    #[default]
+    TxHash,
+    /// Lovelace held
+    Coin,
+    /// Slot of the block that created the UTxO, when the provider reports it
+    Slot,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct AssetFilter {
+    policy_id: Vec<u8>,
+    name: Option<Vec<u8>>,
+}
+
+impl FromStr for AssetFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (policy_id, name) = match s.split_once('.') {
+            Some((policy_id, name)) => (policy_id, Some(name)),
+            None => (s, None),
+        };
+
+        let policy_id = hex::decode(policy_id).context("policy id is not hex")?;
+        if policy_id.len() != 28 {
+            bail!("policy ids are 28 bytes long, got {}", policy_id.len())
+        }
+
+        let name = name
+            .map(|x| hex::decode(x).context("asset name is not hex"))
+            .transpose()?;
+
+        Ok(Self { policy_id, name })
+    }
+}
+
+fn coin(utxo: &AnyUtxoData) -> Option<u128> {
+    match &utxo.parsed_state {
+        Some(ParsedState::Cardano(output)) => {
+            crate::utils::format_bigint_opt(&output.coin).parse().ok()
+        }
+        None => None,
+    }
+}
+
+#[derive(Default)]
+struct Filters<'a> {
+    min_coin: Option<u64>,
+    max_coin: Option<u64>,
+    asset: Option<&'a AssetFilter>,
+    address: Option<&'a Address>,
+}
+
+impl Filters<'_> {
+    fn is_empty(&self) -> bool {
+        self.min_coin.is_none()
+            && self.max_coin.is_none()
+            && self.asset.is_none()
+            && self.address.is_none()
+    }
+
+    /// Whether a UTxO passes the `--min-coin`, `--max-coin`, `--asset` and
+    /// `--address` filters.
+    fn matches(&self, utxo: &AnyUtxoData) -> bool {
+        let Some(ParsedState::Cardano(output)) = &utxo.parsed_state else {
+            return self.is_empty();
+        };
+
+        let coin = coin(utxo);
+        let enough_coin = self
+            .min_coin
+            .is_none_or(|min| coin.is_some_and(|coin| coin >= min as u128));
+        let not_too_much_coin = self
+            .max_coin
+            .is_none_or(|max| coin.is_some_and(|coin| coin <= max as u128));
+
+        let has_asset = self.asset.is_none_or(|filter| {
+            output.assets.iter().any(|multiasset| {
+                multiasset.policy_id.as_ref() == filter.policy_id.as_slice()
+                    && filter.name.as_ref().is_none_or(|name| {
+                        multiasset
+                            .assets
+                            .iter()
+                            .any(|x| x.name.as_ref() == name.as_slice())
+                    })
+            })
+        });
+
+        let at_address = self
+            .address
+            .is_none_or(|address| output.address.as_ref() == address.to_vec().as_slice());
+
+        enough_coin && not_too_much_coin && has_asset && at_address
+    }
+}
+
+/// Sorts UTxOs by `order_by`, breaking ties by reference so that pages don't
+/// overlap.
+fn sort(utxos: &mut [AnyUtxoData], order_by: OrderBy, desc: bool) {
+    let reference = |x: &AnyUtxoData| {
+        x.txo_ref
+            .as_ref()
+            .map(|reference| (reference.hash.to_vec(), reference.index))
+    };
+
+    utxos.sort_by(|a, b| {
+        let ordering = match order_by {
+            OrderBy::TxHash => std::cmp::Ordering::Equal,
+            OrderBy::Coin => coin(a).cmp(&coin(b)),
+            OrderBy::Slot => {
+                let slot = |x: &AnyUtxoData| x.block_ref.as_ref().map(|x| x.slot);
+                slot(a).cmp(&slot(b))
+            }
+        };
+        let ordering = ordering.then_with(|| reference(a).cmp(&reference(b)));
+
+        match desc {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    });
 }
 
 pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
@@ -23,14 +203,33 @@ pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
 
     let provider = match args.provider {
         Some(name) => ctx.store.find_provider(&name),
-        None => ctx.store.default_provider(),
+        None => ctx.store.wallet_provider(wallet),
     };
 
     match (wallet, provider) {
         (Some(wallet), Some(provider)) => {
-            let address = wallet.address(provider.is_testnet());
-            let utxos = provider.get_wallet_utxos(&address).await?;
-            let output = WalletUtxoOutput::new(utxos);
+            let utxos =
+                discovery::wallet_utxo_data(wallet, provider, args.matching, args.gap_limit)
+                    .await?;
+
+            let filters = Filters {
+                min_coin: args.min_coin,
+                max_coin: args.max_coin,
+                asset: args.asset.as_ref(),
+                address: args.address.as_ref(),
+            };
+
+            let mut utxos: Vec<_> = utxos.into_iter().filter(|x| filters.matches(x)).collect();
+            sort(&mut utxos, args.order_by, args.desc);
+
+            let total = utxos.len();
+            let utxos = utxos
+                .into_iter()
+                .skip(args.offset)
+                .take(args.limit.unwrap_or(usize::MAX))
+                .collect();
+
+            let output = WalletUtxoOutput::new(utxos, total);
 
             let format = if ctx.output_format_overridden {
                 ctx.output_format.clone()
@@ -38,7 +237,7 @@ pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
                 OutputFormat::Json
             };
 
-            output.output(&format);
+            output.output(&format, ctx.output_select())?;
 
             Ok(())
         }
@@ -48,28 +247,30 @@ pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
     }
 }
 
-struct WalletUtxoOutput {
+pub(super) struct WalletUtxoOutput {
     utxos: Vec<AnyUtxoData>,
+    /// UTxOs matching the filters, before paging.
+    total: usize,
 }
 
 impl WalletUtxoOutput {
-    fn new(utxos: Vec<AnyUtxoData>) -> Self {
-        Self { utxos }
+    pub(super) fn new(utxos: Vec<AnyUtxoData>, total: usize) -> Self {
+        Self { utxos, total }
     }
 }
 
 impl OutputFormatter for WalletUtxoOutput {
-    fn to_table(&self) {
-        let mut table = Table::new();
+    fn to_table(&self, style: TableStyle) {
+        let mut table = crate::output::table(style);
 
-        table.set_header(vec!["Tx Hash", "Index", "Lovelace", "Assets", "Datum Hash"]);
+        table.set_header(vec!["Utxo Ref", "Lovelace", "Assets", "Datum Hash"]);
 
         for utxo in &self.utxos {
-            let (tx_hash, index) = utxo
+            let utxo_ref = utxo
                 .txo_ref
                 .as_ref()
-                .map(|reference| (hex::encode(&reference.hash), reference.index.to_string()))
-                .unwrap_or_else(|| ("-".to_string(), "-".to_string()));
+                .map(|reference| format!("{}#{}", hex::encode(&reference.hash), reference.index))
+                .unwrap_or_else(|| "-".to_string());
 
             let (coin, asset_count, datum_hash) = match &utxo.parsed_state {
                 Some(ParsedState::Cardano(output)) => {
@@ -94,21 +295,25 @@ impl OutputFormatter for WalletUtxoOutput {
                 None => ("-".to_string(), "0".to_string(), "-".to_string()),
             };
 
-            table.add_row(vec![tx_hash, index, coin, asset_count, datum_hash]);
+            table.add_row(vec![utxo_ref, coin, asset_count, datum_hash]);
         }
 
         println!("{table}");
+
+        if self.utxos.len() < self.total {
+            println!("Showing {} of {} UTxOs", self.utxos.len(), self.total);
+        }
     }
 
-    fn to_json(&self) {
-        let payload = json!({ "utxos": self.utxos });
-        println!("{}", serde_json::to_string_pretty(&payload).unwrap());
+    fn to_json(&self) -> anyhow::Result<Value> {
+        Ok(json!({ "utxos": self.utxos, "total": self.total }))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::wallet::fixtures::Utxo;
 
     #[test]
     fn parses_without_arguments() {
@@ -125,4 +330,79 @@ mod tests {
         assert_eq!(args.name.as_deref(), Some("alice"));
         assert_eq!(args.provider.as_deref(), Some("mainnet"));
     }
+
+    #[test]
+    fn filters_by_coin_and_asset() {
+        let utxo = |coin, policy, name: &'static [u8]| {
+            Utxo {
+                coin,
+                asset: Some((policy, name)),
+                ..Default::default()
+            }
+            .build()
+        };
+
+        let small = utxo(1_000_000, 1, b"foo");
+        let large = utxo(5_000_000, 2, b"bar");
+
+        let matches = |utxo: &AnyUtxoData, min_coin: Option<u64>, asset: Option<&AssetFilter>| {
+            Filters {
+                min_coin,
+                asset,
+                ..Default::default()
+            }
+            .matches(utxo)
+        };
+
+        assert!(matches(&small, None, None));
+        assert!(!matches(&small, Some(2_000_000), None));
+        assert!(matches(&large, Some(2_000_000), None));
+
+        let policy: AssetFilter = hex::encode([2; 28]).parse().unwrap();
+        assert!(matches(&large, None, Some(&policy)));
+        assert!(!matches(&small, None, Some(&policy)));
+
+        let named: AssetFilter = format!("{}.{}", hex::encode([2; 28]), hex::encode(b"baz"))
+            .parse()
+            .unwrap();
+        assert!(!matches(&large, None, Some(&named)));
+
+        assert!("abcd".parse::<AssetFilter>().is_err());
+
+        let at_most = Filters {
+            max_coin: Some(2_000_000),
+            ..Default::default()
+        };
+        assert!(at_most.matches(&small));
+        assert!(!at_most.matches(&large));
+    }
+
+    #[test]
+    fn sorts_by_coin_and_reference() {
+        let utxo = |hash, coin| {
+            Utxo {
+                coin,
+                hash,
+                ..Default::default()
+            }
+            .build()
+        };
+
+        let mut utxos = vec![utxo(1, 5), utxo(2, 1), utxo(3, 5)];
+        let hashes = |utxos: &[AnyUtxoData]| -> Vec<u8> {
+            utxos
+                .iter()
+                .map(|x| x.txo_ref.as_ref().unwrap().hash[0])
+                .collect()
+        };
+
+        sort(&mut utxos, OrderBy::Coin, false);
+        assert_eq!(hashes(&utxos), vec![2, 1, 3]);
+
+        sort(&mut utxos, OrderBy::Coin, true);
+        assert_eq!(hashes(&utxos), vec![3, 1, 2]);
+
+        sort(&mut utxos, OrderBy::TxHash, false);
+        assert_eq!(hashes(&utxos), vec![1, 2, 3]);
+    }
 }
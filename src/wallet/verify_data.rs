@@ -0,0 +1,47 @@
+use anyhow::Context;
+use clap::Parser;
+use pallas::ledger::addresses::Address;
+use serde_json::json;
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+use super::cip8;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Address the data was signed for
+    #[arg(long)]
+    address: String,
+
+    /// COSE_Sign1 signature, as hex
+    #[arg(long)]
+    signature: String,
+
+    /// COSE key returned along with the signature, as hex
+    #[arg(long)]
+    key: String,
+
+    /// Data that was signed, as hex or as the path to a file
+    #[arg(long)]
+    payload: String,
+}
+
+#[instrument("verify-data", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let address = Address::from_bech32(&args.address).context("invalid address")?;
+    let signature = hex::decode(&args.signature).context("signature is not hex")?;
+    let key = hex::decode(&args.key).context("key is not hex")?;
+    let payload = cip8::read_payload(&args.payload)?;
+
+    cip8::verify(&signature, &key, &address, &payload)?;
+
+    match ctx.output_format {
+        OutputFormat::Json => {
+            crate::output::print_json(&json!({ "valid": true }), ctx.output_select())?
+        }
+        OutputFormat::Table | OutputFormat::Markdown => println!("Signature is valid"),
+    }
+
+    Ok(())
+}
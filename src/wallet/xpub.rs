@@ -0,0 +1,35 @@
+use anyhow::bail;
+use clap::Parser;
+use serde_json::json;
+use tracing::instrument;
+
+use crate::output::OutputFormat;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Name of the HD wallet to print the account public key of
+    name: String,
+}
+
+#[instrument("xpub", skip_all)]
+pub async fn run(args: Args, ctx: &crate::Context) -> anyhow::Result<()> {
+    let Some(wallet) = ctx.store.find_wallet(&args.name) else {
+        bail!("Wallet not found.")
+    };
+
+    let Some(xvk) = wallet.account_xvk() else {
+        bail!(
+            "wallet '{}' is not an HD wallet, it has no account public key",
+            wallet.name
+        )
+    };
+
+    match ctx.output_format {
+        OutputFormat::Json => {
+            crate::output::print_json(&json!({ "account_public_key": xvk }), ctx.output_select())?
+        }
+        OutputFormat::Table | OutputFormat::Markdown => println!("{xvk}"),
+    }
+
+    Ok(())
+}